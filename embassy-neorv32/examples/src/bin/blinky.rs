@@ -13,8 +13,7 @@ use embassy_time::Timer;
 async fn main(_spawner: embassy_executor::Spawner) {
     let p = embassy_neorv32::init();
 
-    let mut uart = UartTx::new_blocking(p.UART0, UART_BAUD, UART_IS_SIM, false)
-        .expect("UART must be supported");
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
 
     let gpio = Gpio::new_blocking(p.GPIO).expect("GPIO must be supported");
     let mut output = gpio.new_output(p.PORT0);
@@ -0,0 +1,53 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use embassy_neorv32::bind_interrupts;
+use embassy_neorv32::cfs::{self, Cfs};
+use embassy_neorv32::peripherals;
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32_examples::*;
+use embassy_time::Timer;
+
+bind_interrupts!(struct Irqs {
+    CFS => cfs::InterruptHandler<peripherals::CFS>;
+});
+
+// Register layout of the custom hardware placed in the CFS slot
+// Adjust this to match your own design
+embassy_neorv32::cfs_registers! {
+    /// Control register, writing bit 0 starts an operation.
+    CTRL @ 0: u32, rw;
+    /// Operand register.
+    OPERAND @ 1: u32, w;
+    /// Result register.
+    RESULT @ 2: u32, r;
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    // Setup UART for display purposes
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    // Setup async CFS
+    let mut cfs = Cfs::new_async(p.CFS, Irqs).expect("CFS must be implemented");
+
+    let mut operand = 0;
+    loop {
+        cfs.write::<OPERAND>(operand);
+        cfs.modify::<CTRL>(|ctrl| ctrl | 1);
+
+        // Assumes the custom hardware raises its interrupt when the operation completes
+        cfs.wait_irq().await;
+        let result = cfs.read::<RESULT>();
+        writeln!(uart, "CFS({operand}) = {result}").unwrap();
+
+        // Acknowledge the interrupt in the custom hardware
+        cfs.modify::<CTRL>(|ctrl| ctrl & !1);
+
+        operand += 1;
+        Timer::after_micros(s_to_us(1)).await;
+    }
+}
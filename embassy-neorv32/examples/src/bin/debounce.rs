@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+#[cfg(feature = "sim")]
+compile_error!("Debounce example not available in simulation.");
+
+use embassy_neorv32::gpio::{self, DebounceConfig, Debouncer, Gesture, Gpio};
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32::{bind_interrupts, peripherals};
+use embassy_neorv32_examples::*;
+
+bind_interrupts!(struct Irqs {
+    GPIO => gpio::InterruptHandler<peripherals::GPIO>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    let gpio = Gpio::new_async(p.GPIO, Irqs).expect("GPIO must be supported");
+    let mut button = Debouncer::new(gpio.new_input(p.PORT0), DebounceConfig::default());
+
+    uart.blocking_write(b"Starting debounce example...\n");
+    loop {
+        match button.next_gesture().await {
+            Gesture::ShortPress => uart.blocking_write(b"Short press\n"),
+            Gesture::LongPress => uart.blocking_write(b"Long press\n"),
+            Gesture::Repeat => uart.blocking_write(b"Repeat\n"),
+            Gesture::DoubleClick => uart.blocking_write(b"Double click\n"),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+#![no_std]
+#![no_main]
+
+use embassy_futures::join::join;
+use embassy_neorv32::dma::{self, OnBusy, Priority, SharedDma};
+use embassy_neorv32::uart::{self, UartTx};
+use embassy_neorv32::{bind_interrupts, peripherals};
+use embassy_neorv32_examples::*;
+use embassy_time::Timer;
+
+bind_interrupts!(struct Irqs {
+    UART0 => uart::InterruptHandler<peripherals::UART0>;
+    DMA => dma::InterruptHandler<peripherals::DMA>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    // Share the single DMA channel between UART TX and memory-to-memory copies
+    let dma = SharedDma::new(p.DMA, Irqs).expect("DMA must be supported");
+
+    // UART output is latency sensitive, so it gets priority and falls back to copying bytes
+    // into the FIFO itself rather than waiting whenever the DMA is busy with a copy
+    let mut uart = UartTx::new_async_with_shared_dma(
+        p.UART0,
+        uart_config(),
+        dma.channel(Priority::High, OnBusy::Pio),
+        Irqs,
+    )
+    .expect("UART must be supported");
+
+    let print = async {
+        for _ in 0..5 {
+            uart.write(b"Printing while copying memory with DMA...\n")
+                .await
+                .unwrap();
+            Timer::after_micros(ms_to_us(100)).await;
+        }
+    };
+
+    let copy = async {
+        let src = [0xAAu8; 1024];
+        let mut dst = [0u8; 1024];
+        let mut copies = 0;
+
+        for _ in 0..5 {
+            // Each copy locks the DMA only for the duration of the transfer
+            let mut dma = dma.lock(Priority::Low).await;
+            if dma.copy(&src, &mut dst, false).await.is_ok() && src == dst {
+                copies += 1;
+            }
+            drop(dma);
+            Timer::after_micros(ms_to_us(50)).await;
+        }
+
+        copies
+    };
+
+    let (_, copies) = join(print, copy).await;
+    if copies == 5 {
+        uart.write(b"All DMA copies succeeded\n").await.unwrap();
+    } else {
+        uart.write(b"Some DMA copies failed\n").await.unwrap();
+    }
+}
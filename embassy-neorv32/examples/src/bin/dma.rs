@@ -1,7 +1,8 @@
 #![no_std]
 #![no_main]
 
-use embassy_neorv32::dma::{self, Dma};
+use core::fmt::Write;
+use embassy_neorv32::dma::{self, Dma, TransferDescriptor};
 use embassy_neorv32::uart::UartTx;
 use embassy_neorv32::{bind_interrupts, peripherals};
 use embassy_neorv32_examples::*;
@@ -14,8 +15,7 @@ bind_interrupts!(struct Irqs {
 async fn main(_spawner: embassy_executor::Spawner) {
     let p = embassy_neorv32::init();
 
-    let mut uart = UartTx::new_blocking(p.UART0, UART_BAUD, UART_IS_SIM, false)
-        .expect("UART must be supported");
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
 
     // Note: DMA is single-channel only, so only one driver can own it.
     // Typically you would instantiate the DMA driver instance directly like this
@@ -34,4 +34,50 @@ async fn main(_spawner: embassy_executor::Spawner) {
         Err(_) => uart.blocking_write(b"DMA transfer encountered an error\n"),
         _ => uart.blocking_write(b"DMA transfer failed\n"),
     }
+
+    // Gather several buffers into one with a chain of transfers executed back-to-back by hardware
+    let header = [0x11u8; 16];
+    let payload = [0x22u8; 256];
+    let footer = [0x33u8; 16];
+    let mut packet = [0u8; 288];
+
+    let (dst_header, rest) = packet.split_at_mut(header.len());
+    let (dst_payload, dst_footer) = rest.split_at_mut(payload.len());
+    let descriptors = [
+        TransferDescriptor::copy(&header, dst_header, false),
+        TransferDescriptor::copy(&payload, dst_payload, false),
+        TransferDescriptor::copy(&footer, dst_footer, false),
+    ];
+
+    let res = dma.chain(&descriptors).await;
+    let expected = header.iter().chain(&payload).chain(&footer);
+    match res {
+        Ok(()) if packet.iter().eq(expected) => {
+            uart.blocking_write(b"DMA chained transfer succeeded\n")
+        }
+        Err(dma::Error::ChainBusError { index }) => {
+            writeln!(uart, "DMA chained transfer failed at element {index}").unwrap()
+        }
+        _ => uart.blocking_write(b"DMA chained transfer failed\n"),
+    }
+
+    // Fill a buffer with a constant value (memset)
+    let mut words = [0u32; 64];
+    let res = dma.fill(&0xDEAD_BEEF, &mut words).await;
+    match res {
+        Ok(()) if words.iter().all(|&w| w == 0xDEAD_BEEF) => {
+            uart.blocking_write(b"DMA fill succeeded\n")
+        }
+        _ => uart.blocking_write(b"DMA fill failed\n"),
+    }
+
+    // Convert native (little-endian) words to big-endian
+    let mut be_words = [0u32; 64];
+    let res = dma.swap_bytes(&words, &mut be_words).await;
+    match res {
+        Ok(()) if be_words.iter().all(|&w| w == 0xEFBE_ADDE) => {
+            uart.blocking_write(b"DMA byte swap succeeded\n")
+        }
+        _ => uart.blocking_write(b"DMA byte swap failed\n"),
+    }
 }
@@ -66,8 +66,7 @@ async fn hart1_task(uart: &'static SharedUart, mut trng: Trng<'static, trng::Asy
 async fn main(spawner: embassy_executor::Spawner) {
     let p = embassy_neorv32::init();
 
-    let uart = UartTx::new_async(p.UART0, UART_BAUD, UART_IS_SIM, false, Irqs)
-        .expect("UART must be supported");
+    let uart = UartTx::new_async(p.UART0, uart_config(), Irqs).expect("UART must be supported");
     let uart = UART.get_or_init(|| Mutex::new(uart));
     uart.lock()
         .await
@@ -0,0 +1,72 @@
+#![no_std]
+#![no_main]
+
+#[cfg(feature = "sim")]
+compile_error!("GPIO bus example not available in simulation.");
+
+use embassy_neorv32::gpio::{Gpio, Parallel8080};
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32_examples::*;
+use embassy_time::Timer;
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    let gpio = Gpio::new_blocking(p.GPIO).expect("GPIO must be supported");
+
+    // Ports don't need to be contiguous, bit `i` of the bus is simply the `i`th port given
+    let mut leds = gpio.new_bus([
+        gpio.new_port(p.PORT0),
+        gpio.new_port(p.PORT1),
+        gpio.new_port(p.PORT2),
+        gpio.new_port(p.PORT3),
+        gpio.new_port(p.PORT8),
+        gpio.new_port(p.PORT9),
+        gpio.new_port(p.PORT10),
+        gpio.new_port(p.PORT11),
+    ]);
+
+    uart.blocking_write(b"Counting on LED bus...\n");
+    for count in 0..=u8::MAX {
+        // All 8 LEDs change at the same time
+        leds.write(count as u32);
+        Timer::after_millis(20).await;
+    }
+
+    // Blink the upper nibble while leaving the lower nibble alone
+    leds.write(0x05);
+    for _ in 0..10 {
+        leds.write_masked(!leds.read_output(), 0xf0);
+        Timer::after_millis(100).await;
+    }
+
+    // Reuse the same bus as the data lines of an 8080-style display interface
+    uart.blocking_write(b"Sending display init sequence...\n");
+    let mut display = Parallel8080::new(
+        leds,
+        gpio.new_output(p.PORT16),
+        gpio.new_output(p.PORT17),
+        Some(gpio.new_output(p.PORT18)),
+        Some(gpio.new_output(p.PORT19)),
+    );
+
+    // Software reset, sleep out, then display on
+    display.write_command(0x01u8);
+    Timer::after_millis(120).await;
+    display.write_command(0x11u8);
+    Timer::after_millis(120).await;
+    display.write_command(0x29u8);
+
+    // Read display ID (dummy byte followed by 3 ID bytes)
+    // Note: This needs external buffering so the display can drive the GPIO input lines, and the
+    // read strobe delay should cover the display's read access time plus the buffer's delay
+    display.set_read_delay_ns(500);
+    display.write_command(0x04u8);
+    let mut id = [0u8; 4];
+    display.read_data(&mut id);
+
+    uart.blocking_write(b"Done\n");
+}
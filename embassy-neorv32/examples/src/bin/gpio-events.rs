@@ -0,0 +1,55 @@
+#![no_std]
+#![no_main]
+
+#[cfg(feature = "sim")]
+compile_error!("GPIO events example not available in simulation.");
+
+use core::fmt::Write;
+use embassy_neorv32::gpio::{self, EdgeTrigger, EventChannel, Gpio, Trigger};
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32::{bind_interrupts, peripherals};
+use embassy_neorv32_examples::*;
+
+bind_interrupts!(struct Irqs {
+    GPIO => gpio::InterruptHandler<peripherals::GPIO>;
+});
+
+static EVENTS: EventChannel<16> = EventChannel::new();
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    let gpio = Gpio::new_async(p.GPIO, Irqs).expect("GPIO must be supported");
+    let mut buttons = gpio.new_input_bank([
+        gpio.new_input(p.PORT0),
+        gpio.new_input(p.PORT1),
+        gpio.new_input(p.PORT2),
+        gpio.new_input(p.PORT3),
+    ]);
+
+    // Wait on all buttons at once
+    uart.blocking_write(b"Press any button...\n");
+    let pressed = buttons.wait(Trigger::FallingEdge).await;
+    writeln!(uart, "Buttons pressed: {pressed:#06b}").unwrap();
+
+    // Then record every edge as it happens
+    uart.blocking_write(b"Recording button edges...\n");
+    let mut events = buttons.events(&EVENTS, EdgeTrigger::Any);
+    loop {
+        let event = events.next().await;
+        writeln!(
+            uart,
+            "PORT{} {:?} at cycle {}",
+            event.port, event.edge, event.timestamp
+        )
+        .unwrap();
+
+        let lost = events.take_lost();
+        if lost != 0 {
+            writeln!(uart, "Lost edges on ports: {lost:#06b}").unwrap();
+        }
+    }
+}
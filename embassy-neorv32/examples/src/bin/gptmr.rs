@@ -0,0 +1,42 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use embassy_neorv32::bind_interrupts;
+use embassy_neorv32::gptmr::{self, Gptmr};
+use embassy_neorv32::peripherals;
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32_examples::*;
+
+bind_interrupts!(struct Irqs {
+    GPTMR => gptmr::InterruptHandler<peripherals::GPTMR>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    // Setup GPTMR with a clock prescaler of 64
+    let gptmr =
+        Gptmr::new_async(p.GPTMR, gptmr::ClkPrsc::_64, Irqs).expect("GPTMR must be supported");
+    writeln!(&mut uart, "GPTMR tick frequency: {} Hz", gptmr.tick_freq()).unwrap();
+
+    // Slice 0 fires periodically, slice 1 is used as a one-shot delay
+    let mut periodic = gptmr.new_slice(p.GPTMRSLICE0);
+    let mut delay = gptmr.new_slice(p.GPTMRSLICE1);
+
+    periodic.start_periodic(gptmr.tick_freq() / 10);
+    for i in 0..10 {
+        periodic.wait().await;
+        writeln!(&mut uart, "Periodic tick {i}").unwrap();
+    }
+    periodic.stop();
+
+    loop {
+        // Delay for half a second worth of ticks
+        delay.delay_ticks(gptmr.tick_freq() as u64 / 2).await;
+        uart.blocking_write(b"One-shot delay elapsed\n");
+    }
+}
@@ -0,0 +1,38 @@
+#![no_std]
+#![no_main]
+
+use embassy_neorv32::bind_interrupts;
+use embassy_neorv32::neoled::{self, Neoled, RGB8, Timing};
+use embassy_neorv32::peripherals;
+use embassy_neorv32_examples::*;
+use embassy_time::Timer;
+
+const NUM_LEDS: usize = 8;
+
+bind_interrupts!(struct Irqs {
+    NEOLED => neoled::InterruptHandler<peripherals::NEOLED>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    // Setup async NEOLED for a strip of WS2812 LEDs
+    let mut neoled: Neoled<'_, _, RGB8> =
+        Neoled::new_async(p.NEOLED, Timing::WS2812, Irqs).expect("NEOLED must be supported");
+
+    // Walk a single colored LED along the strip, changing color on each pass
+    let colors = [
+        RGB8::new(32, 0, 0),
+        RGB8::new(0, 32, 0),
+        RGB8::new(0, 0, 32),
+    ];
+    for &color in colors.iter().cycle() {
+        for lit in 0..NUM_LEDS {
+            neoled
+                .write((0..NUM_LEDS).map(|i| if i == lit { color } else { RGB8::default() }))
+                .await;
+            Timer::after_micros(ms_to_us(100)).await;
+        }
+    }
+}
@@ -0,0 +1,56 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use embassy_neorv32::bind_interrupts;
+use embassy_neorv32::onewire::{self, OneWire, Search, SearchKind};
+use embassy_neorv32::peripherals;
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32_examples::*;
+use embassy_time::Timer;
+
+// Base time tick for standard-speed 1-Wire timing
+const BASE_TICK_NS: u32 = 10_000;
+
+bind_interrupts!(struct Irqs {
+    ONEWIRE => onewire::InterruptHandler<peripherals::ONEWIRE>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    // Setup async ONEWIRE
+    let mut onewire =
+        OneWire::new_async(p.ONEWIRE, BASE_TICK_NS, Irqs).expect("ONEWIRE must be supported");
+
+    loop {
+        // Enumerate every device on the bus
+        let mut search = Search::new(SearchKind::All);
+        let mut count = 0;
+        loop {
+            match onewire.search_next(&mut search).await {
+                Ok(Some(rom)) => {
+                    count += 1;
+                    writeln!(
+                        &mut uart,
+                        "Found device: family 0x{:02X}, ROM 0x{:016X}",
+                        rom.family_code(),
+                        u64::from(rom)
+                    )
+                    .unwrap();
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    writeln!(&mut uart, "Search error: {e:?}").unwrap();
+                    break;
+                }
+            }
+        }
+
+        writeln!(&mut uart, "{count} device(s) found").unwrap();
+        Timer::after_micros(s_to_us(1)).await;
+    }
+}
@@ -0,0 +1,56 @@
+#![no_std]
+#![no_main]
+
+#[cfg(feature = "sim")]
+compile_error!("Pulse capture example not available in simulation.");
+
+use core::fmt::Write;
+use embassy_neorv32::gpio::{self, EventChannel, Gpio, Level, cycles_to_nanos};
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32::{bind_interrupts, peripherals};
+use embassy_neorv32_examples::*;
+use embassy_time::{Duration, Timer, with_timeout};
+
+bind_interrupts!(struct Irqs {
+    GPIO => gpio::InterruptHandler<peripherals::GPIO>;
+});
+
+static EDGES: EventChannel<8> = EventChannel::new();
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    let gpio = Gpio::new_async(p.GPIO, Irqs).expect("GPIO must be supported");
+    let mut tach = gpio.new_pulse_capture(gpio.new_input(p.PORT0), &EDGES);
+
+    uart.blocking_write(b"Measuring signal on PORT0...\n");
+    loop {
+        // Give up if the signal stops, e.g. a stalled fan
+        match with_timeout(Duration::from_millis(500), tach.measure()).await {
+            Ok(Ok(m)) => writeln!(
+                uart,
+                "{} mHz, duty {} permille, high for {} ns",
+                m.frequency_millihertz(),
+                m.duty_cycle_permille(),
+                cycles_to_nanos(m.high_cycles)
+            )
+            .unwrap(),
+            Ok(Err(_)) => uart.blocking_write(b"Edges lost, signal too fast\n"),
+            Err(_) => uart.blocking_write(b"No signal\n"),
+        }
+
+        if let Ok(Ok(cycles)) = with_timeout(
+            Duration::from_millis(500),
+            tach.measure_pulse_width(Level::Low),
+        )
+        .await
+        {
+            writeln!(uart, "Low pulse of {} ns", cycles_to_nanos(cycles)).unwrap();
+        }
+
+        Timer::after_millis(1000).await;
+    }
+}
@@ -0,0 +1,83 @@
+#![no_std]
+#![no_main]
+
+#[cfg(feature = "sim")]
+compile_error!("PWM stream example not available in simulation.");
+
+use embassy_neorv32::gptmr::{self, Gptmr};
+use embassy_neorv32::pwm::{self, Pwm, PwmStream};
+use embassy_neorv32::sysinfo::SysInfo;
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32::{bind_interrupts, peripherals};
+use embassy_neorv32_examples::*;
+
+bind_interrupts!(struct Irqs {
+    GPTMR => gptmr::InterruptHandler<peripherals::GPTMR>;
+});
+
+const SAMPLE_RATE: u32 = 8_000;
+
+// One period of a sine wave as unsigned 8-bit PCM
+const SINE: [u8; 32] = [
+    128, 153, 177, 199, 218, 234, 245, 253, 255, 253, 245, 234, 218, 199, 177, 153, 128, 103, 79,
+    57, 38, 22, 11, 3, 1, 3, 11, 22, 38, 57, 79, 103,
+];
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    // Use the fastest prescaler and 8-bit resolution so the PWM frequency is far above the
+    // sample rate, leaving a low-pass filter on the output to recover the audio
+    let pwm = Pwm::new(p.PWM, pwm::ClkPrsc::_2).expect("PWM must be supported");
+    let mut chan0 = pwm
+        .new_channel(
+            p.PWMCHAN0,
+            pwm::Mode::Fast,
+            SysInfo::clock_freq() / 2 / 256,
+            false,
+        )
+        .expect("PWM frequency must be representable");
+
+    let gptmr =
+        Gptmr::new_async(p.GPTMR, gptmr::ClkPrsc::_2, Irqs).expect("GPTMR must be supported");
+    let mut slice = gptmr.new_slice(p.GPTMRSLICE0);
+
+    let mut stream = PwmStream::new_gptmr(&mut chan0, &mut slice, SAMPLE_RATE)
+        .expect("Sample rate must be representable");
+    let encoder = stream.encoder();
+
+    // Play a short 250 Hz beep once from a pre-encoded buffer
+    uart.blocking_write(b"Playing beep...\n");
+    let mut beep = [0u32; 32 * 50];
+    for chunk in beep.chunks_exact_mut(SINE.len()) {
+        encoder.encode_u8(&SINE, chunk);
+    }
+    stream.play(&beep).await;
+
+    // Then play a continuous tone, refilling each half of the buffer while the other one plays
+    uart.blocking_write(b"Playing continuous tone...\n");
+    let mut buffer = [0u32; 256];
+    for chunk in buffer.chunks_exact_mut(SINE.len()) {
+        encoder.encode_u8(&SINE, chunk);
+    }
+
+    let mut samples = [0u8; 128];
+    let mut phase = 0;
+    let mut playback = stream.play_continuous(&mut buffer);
+    loop {
+        // Step through the table twice as fast for a 500 Hz tone
+        for sample in samples.iter_mut() {
+            *sample = SINE[phase];
+            phase = (phase + 2) % SINE.len();
+        }
+
+        match playback.next_half().await {
+            Ok(half) => encoder.encode_u8(&samples, half),
+            Err(pwm::Error::Underrun) => uart.blocking_write(b"Underrun!\n"),
+            Err(err) => panic!("Playback failed: {err:?}"),
+        }
+    }
+}
@@ -4,7 +4,7 @@
 #[cfg(feature = "sim")]
 compile_error!("PWM example not available in simulation.");
 
-use embassy_neorv32::pwm::{self, Percent, Pwm};
+use embassy_neorv32::pwm::{self, Pwm, PwmGroup};
 use embassy_neorv32::uart::UartTx;
 use embassy_neorv32_examples::*;
 use embassy_time::Timer;
@@ -13,21 +13,51 @@ use embassy_time::Timer;
 async fn main(_spawner: embassy_executor::Spawner) {
     let p = embassy_neorv32::init();
 
-    let mut uart = UartTx::new_blocking(p.UART0, UART_BAUD, UART_IS_SIM, false)
-        .expect("UART must be supported");
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
 
     // Setup PWM peripheral with a clock prescaler of 4096
     let pwm = Pwm::new(p.PWM, pwm::ClkPrsc::_4096).expect("PWM must be supported");
 
     // Setup PWM channel 0 with frequency of 42
-    let mut chan0 = pwm.new_channel(p.PWMCHAN0, pwm::Mode::Fast, 42, false);
+    let mut chan0 = pwm
+        .new_channel(p.PWMCHAN0, pwm::Mode::Fast, 42, false)
+        .expect("PWM frequency must be representable");
 
     uart.blocking_write(b"Starting PWM LED breathing example...\n");
+    for _ in 0..3 {
+        // Use 1/256 steps instead of whole percents for a smoother fade
+        for step in (0..=256).chain((0..=256).rev()) {
+            chan0.set_duty_fraction(step, 256).unwrap();
+            Timer::after_millis(4).await;
+        }
+    }
+
+    // Switch to phase-correct mode and a higher frequency without recreating the channel
+    uart.blocking_write(b"Changing frequency and mode...\n");
+    chan0
+        .set_mode(pwm::Mode::PhaseCorrect)
+        .expect("PWM frequency must be representable in phase-correct mode");
+    chan0
+        .set_frequency(10)
+        .expect("PWM frequency must be representable");
+
+    // Drive three channels as the phases of a motor, changing all duty cycles together
+    uart.blocking_write(b"Starting PWM group commutation...\n");
+    let chan1 = pwm
+        .new_channel(p.PWMCHAN1, pwm::Mode::PhaseCorrect, 10, false)
+        .expect("PWM frequency must be representable");
+    let chan2 = pwm
+        .new_channel(p.PWMCHAN2, pwm::Mode::PhaseCorrect, 10, false)
+        .expect("PWM frequency must be representable");
+    let mut phases = PwmGroup::new([chan0, chan1, chan2]);
+    phases.restart();
+
+    let max = phases.channels()[0].max_duty_ticks();
+    let steps = [[max, 0, 0], [0, max, 0], [0, 0, max]];
     loop {
-        for duty in (0..=100).step_by(10).chain((0..=100).rev().step_by(10)) {
-            let percent = Percent::new(duty).unwrap();
-            chan0.set_duty_cycle(percent);
-            Timer::after_millis(100).await;
+        for step in steps {
+            phases.set_duty_ticks(step).unwrap();
+            Timer::after_millis(500).await;
         }
     }
 }
@@ -0,0 +1,42 @@
+#![no_std]
+#![no_main]
+
+#[cfg(feature = "sim")]
+compile_error!("QEI example not available in simulation.");
+
+use core::fmt::Write;
+use embassy_neorv32::gpio::{self, Gpio, QeiMode};
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32::{bind_interrupts, peripherals};
+use embassy_neorv32_examples::*;
+
+bind_interrupts!(struct Irqs {
+    GPIO => gpio::InterruptHandler<peripherals::GPIO>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    let gpio = Gpio::new_async(p.GPIO, Irqs).expect("GPIO must be supported");
+    let mut encoder = gpio
+        .new_qei_decoder(
+            gpio.new_input(p.PORT0),
+            gpio.new_input(p.PORT1),
+            QeiMode::X4,
+        )
+        .expect("Decoder must be available");
+
+    uart.blocking_write(b"Turn the encoder...\n");
+    loop {
+        let position = encoder.wait_for_change().await;
+        writeln!(uart, "Position: {position}").unwrap();
+
+        let invalid = encoder.take_invalid_transitions();
+        if invalid != 0 {
+            writeln!(uart, "{invalid} invalid transitions, turning too fast?").unwrap();
+        }
+    }
+}
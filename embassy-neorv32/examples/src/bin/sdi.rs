@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use embassy_neorv32::bind_interrupts;
+use embassy_neorv32::peripherals;
+use embassy_neorv32::sdi::{self, Sdi};
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32_examples::*;
+
+bind_interrupts!(struct Irqs {
+    SDI => sdi::InterruptHandler<peripherals::SDI>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    // Setup async SDI so an external SPI host can talk to us
+    let mut sdi = Sdi::new_async(p.SDI, Irqs).expect("SDI must be supported");
+    writeln!(&mut uart, "SDI FIFO depth: {}", sdi.fifo_depth()).unwrap();
+
+    // Each frame, echo back the previous frame received from the host
+    let mut rx = [0; 16];
+    let mut tx = [0; 16];
+    loop {
+        let n = sdi.transaction(&mut rx, &tx).await;
+        let n = n.min(rx.len());
+        writeln!(&mut uart, "Received {n} bytes: {:02X?}", &rx[..n]).unwrap();
+        tx = rx;
+    }
+}
@@ -0,0 +1,51 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use embassy_neorv32::bind_interrupts;
+use embassy_neorv32::peripherals;
+use embassy_neorv32::slink::{self, Async, Slink, SlinkRx, SlinkTx};
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32_examples::*;
+use embassy_time::Timer;
+
+bind_interrupts!(struct Irqs {
+    SLINK => slink::InterruptHandler<peripherals::SLINK>;
+});
+
+// Sends a packet every second, assuming the TX link is looped back to the RX link
+#[embassy_executor::task]
+async fn producer(mut tx: SlinkTx<'static, Async>) {
+    tx.set_route(1).unwrap();
+
+    let mut seq = 0;
+    loop {
+        tx.write_packet(&[0xCAFE_0000 | seq, 0xDEAD_BEEF, seq])
+            .await
+            .unwrap();
+        seq += 1;
+        Timer::after_micros(s_to_us(1)).await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    // Setup async SLINK and split so the producer and consumer run independently
+    let slink = Slink::new_async(p.SLINK, Irqs).expect("SLINK must be supported");
+    let (mut rx, tx): (SlinkRx<'static, Async>, _) = slink.split();
+    spawner.must_spawn(producer(tx));
+
+    let mut buf = [0; 8];
+    loop {
+        match rx.read_packet(&mut buf).await {
+            Ok((n, route)) => {
+                writeln!(&mut uart, "Packet from route {route}: {:08X?}", &buf[..n]).unwrap()
+            }
+            Err(e) => writeln!(&mut uart, "Packet error: {e:?}").unwrap(),
+        }
+    }
+}
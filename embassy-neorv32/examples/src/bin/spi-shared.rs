@@ -0,0 +1,66 @@
+#![no_std]
+#![no_main]
+
+#[cfg(feature = "sim")]
+compile_error!("SPI example not available in simulation.");
+
+use core::fmt::Write;
+use embassy_futures::join::join;
+use embassy_neorv32::bind_interrupts;
+use embassy_neorv32::peripherals;
+use embassy_neorv32::spi::{self, ChipSelect, MODE_0, MODE_3, Spi, SpiDevice};
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32_examples::*;
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_sync::mutex::Mutex;
+use embassy_time::Timer;
+use embedded_hal_async::spi::SpiDevice as _;
+
+bind_interrupts!(struct Irqs {
+    SPI => spi::InterruptHandler<peripherals::SPI>;
+});
+
+// Reads the JEDEC ID of a SPI flash
+async fn read_id(dev: &mut SpiDevice<'_, '_, NoopRawMutex>) -> [u8; 3] {
+    let mut buf = [0x9F, 0, 0, 0];
+    dev.transfer_in_place(&mut buf).await.unwrap();
+    [buf[1], buf[2], buf[3]]
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    // Setup UART for display purposes
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    // Share one SPI bus between two flash chips using hardware chip-select lines
+    let spi = Mutex::<NoopRawMutex, _>::new(
+        Spi::new_async(
+            p.SPI,
+            spi::Config {
+                frequency: 1_000_000,
+                mode: MODE_0,
+            },
+            Irqs,
+        )
+        .expect("SPI must be supported"),
+    );
+    let mut flash0 = SpiDevice::new(&spi, ChipSelect::Cs0);
+
+    // The second flash runs at a different speed and mode, so the bus is reconfigured for it
+    let flash1_config = spi::Config {
+        frequency: 500_000,
+        mode: MODE_3,
+    };
+    let mut flash1 = SpiDevice::new_with_config(&spi, ChipSelect::Cs1, flash1_config);
+
+    loop {
+        // Both devices take turns locking the bus
+        let (id0, id1) = join(read_id(&mut flash0), read_id(&mut flash1)).await;
+        writeln!(uart, "CS0 JEDEC ID: {id0:02X?}").unwrap();
+        writeln!(uart, "CS1 JEDEC ID: {id1:02X?}").unwrap();
+
+        Timer::after_micros(s_to_us(1)).await;
+    }
+}
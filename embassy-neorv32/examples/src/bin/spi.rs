@@ -27,7 +27,15 @@ async fn main(_spawner: embassy_executor::Spawner) {
     let dma = Dma::new(p.DMA, Irqs).expect("DMA must be supported");
 
     // Initialize SPI driver and give it the DMA controller
-    let mut spi = Spi::new_async(p.SPI, 1_000_000, MODE_0, Irqs).expect("SPI must be supported");
+    let mut spi = Spi::new_async(
+        p.SPI,
+        spi::Config {
+            frequency: 1_000_000,
+            mode: MODE_0,
+        },
+        Irqs,
+    )
+    .expect("SPI must be supported");
     spi.give_dma(dma);
 
     // Setup CS pin and create an exclusive SPI device with it
@@ -0,0 +1,57 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use embassy_neorv32::bind_interrupts;
+use embassy_neorv32::peripherals;
+use embassy_neorv32::tracer::{self, Hart, TraceRecord, Tracer};
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32_examples::*;
+use embassy_time::Timer;
+
+bind_interrupts!(struct Irqs {
+    TRACER => tracer::InterruptHandler<peripherals::TRACER>;
+});
+
+// Some branchy code worth tracing
+#[inline(never)]
+fn collatz_steps(mut n: u32) -> u32 {
+    let mut steps = 0;
+    while n != 1 {
+        n = if n % 2 == 0 { n / 2 } else { 3 * n + 1 };
+        steps += 1;
+    }
+    steps
+}
+
+// Tracing stops automatically once execution reaches this function
+#[inline(never)]
+fn trace_end() {
+    core::hint::black_box(());
+}
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    // Setup UART for display purposes
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    // Setup async TRACER
+    let mut tracer = Tracer::new_async(p.TRACER, Irqs).expect("TRACER must be supported");
+    writeln!(uart, "Trace buffer depth: {}", tracer.buffer_depth()).unwrap();
+
+    loop {
+        tracer.start(Hart::Hart0, Some(trace_end as *const () as u32));
+        let steps = collatz_steps(core::hint::black_box(7));
+        trace_end();
+        tracer.wait_stopped().await;
+
+        let mut records = [TraceRecord::default(); 64];
+        let n = tracer.drain(&mut records);
+        writeln!(uart, "collatz(7) took {steps} steps, traced {n} branches:").unwrap();
+        tracer::write_history(&mut uart, &records[..n]).unwrap();
+
+        Timer::after_micros(s_to_us(5)).await;
+    }
+}
@@ -0,0 +1,45 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use embassy_neorv32::bind_interrupts;
+use embassy_neorv32::peripherals;
+use embassy_neorv32::twd::{self, RegisterBank, SampleRate, Twd};
+use embassy_neorv32::uart::UartTx;
+use embassy_neorv32_examples::*;
+
+// The I2C address we respond to
+const DEV_ADDR: u8 = 0x42;
+
+bind_interrupts!(struct Irqs {
+    TWD => twd::InterruptHandler<peripherals::TWD>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    // Setup async TWD
+    let mut twd =
+        Twd::new_async(p.TWD, DEV_ADDR, SampleRate::High, Irqs).expect("TWD must be supported");
+
+    // Emulate a small sensor with a read-only "WHO_AM_I" register at 0x00
+    let mut regs = [0; 16];
+    regs[0] = 0xA5;
+    let mut bank = RegisterBank::new(&mut regs);
+
+    loop {
+        let access = bank.serve(&mut twd).await;
+        writeln!(
+            &mut uart,
+            "Access at 0x{:02X}: {} written, {} read",
+            access.start, access.written, access.read
+        )
+        .unwrap();
+
+        // Restore WHO_AM_I in case the controller overwrote it
+        bank.registers_mut()[0] = 0xA5;
+    }
+}
@@ -5,23 +5,35 @@
 compile_error!("TWI example not available in simulation.");
 
 use core::fmt::Write;
-use embassy_neorv32::twi::Twi;
+use embassy_neorv32::bind_interrupts;
+use embassy_neorv32::peripherals;
+use embassy_neorv32::twi::{self, Twi};
 use embassy_neorv32::uart::UartTx;
 use embassy_neorv32_examples::*;
 use embassy_time::Timer;
 use tmp108::Tmp108;
 
+bind_interrupts!(struct Irqs {
+    TWI => twi::InterruptHandler<peripherals::TWI>;
+});
+
 #[embassy_executor::main]
 async fn main(_spawner: embassy_executor::Spawner) {
     let p = embassy_neorv32::init();
 
     // Setup UART for display purposes
-    let mut uart = UartTx::new_blocking(p.UART0, UART_BAUD, UART_IS_SIM, false)
-        .expect("UART must be supported");
-
-    // Setup TWI with frequency of 100 kHz and clock stretching enabled
-    // **Note**: For hardware reasons, unfortuantely an async TWI driver is not available
-    let twi = Twi::new_blocking(p.TWI, 100_000, true).expect("TWI must be supported");
+    let mut uart = UartTx::new_blocking(p.UART0, uart_config()).expect("UART must be supported");
+
+    // Setup async TWI with frequency of 100 kHz and clock stretching enabled
+    let twi = Twi::new_async(
+        p.TWI,
+        twi::Config {
+            frequency: 100_000,
+            clock_stretch: true,
+        },
+        Irqs,
+    )
+    .expect("TWI must be supported");
 
     // Setup and enable TMP108 driver
     // Note: The constructor changes depending on your A0 config
@@ -29,7 +41,7 @@ async fn main(_spawner: embassy_executor::Spawner) {
 
     // Periodically read temperature from TMP108 over TWI
     loop {
-        match sensor.temperature() {
+        match sensor.temperature().await {
             Ok(temp) => writeln!(&mut uart, "Temperature: {} ºC", temp).unwrap(),
             Err(e) => writeln!(&mut uart, "TMP108 error: {e:?}").unwrap(),
         }
@@ -0,0 +1,38 @@
+#![no_std]
+#![no_main]
+
+use embassy_neorv32::uart::{self, BufferedUart};
+use embassy_neorv32::{bind_interrupts, peripherals};
+use embassy_neorv32_examples::*;
+
+bind_interrupts!(struct Irqs {
+    UART0 => uart::BufferedInterruptHandler<peripherals::UART0>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+
+    // Received bytes are buffered in the background, even while we're busy transmitting
+    let mut rx_buf = [0; 64];
+    let mut tx_buf = [0; 64];
+    let mut uart = BufferedUart::new(p.UART0, uart_config(), &mut rx_buf, &mut tx_buf, Irqs)
+        .expect("UART must be supported");
+
+    let greeting = b"Type something and it will be echoed back:\n";
+    let mut written = 0;
+    while written < greeting.len() {
+        written += uart.write(&greeting[written..]).await;
+    }
+
+    // Echo back everything received
+    let mut buf = [0; 16];
+    loop {
+        let n = uart.read(&mut buf).await;
+
+        let mut written = 0;
+        while written < n {
+            written += uart.write(&buf[written..n]).await;
+        }
+    }
+}
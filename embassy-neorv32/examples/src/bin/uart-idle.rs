@@ -0,0 +1,31 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use embassy_neorv32::uart::{self, Error, Uart};
+use embassy_neorv32::{bind_interrupts, peripherals};
+use embassy_neorv32_examples::*;
+
+bind_interrupts!(struct Irqs {
+    UART0 => uart::InterruptHandler<peripherals::UART0>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: embassy_executor::Spawner) {
+    let p = embassy_neorv32::init();
+    let mut uart = Uart::new_async(p.UART0, uart_config(), Irqs).expect("UART must be supported");
+
+    // Receive variable-length frames, each terminated by the line going quiet
+    // for 4 character times (roughly the 3.5 used by Modbus RTU)
+    let mut frame = [0; 64];
+    loop {
+        match uart.read_until_idle(&mut frame, 4).await {
+            Ok(n) => writeln!(uart, "Received {n}-byte frame: {:02X?}", &frame[..n]).unwrap(),
+            Err(Error::Overrun) => {
+                writeln!(uart, "RX overrun, frame dropped").unwrap();
+                uart.clear_overrun();
+            }
+            Err(e) => writeln!(uart, "UART error: {e}").unwrap(),
+        }
+    }
+}
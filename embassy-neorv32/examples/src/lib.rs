@@ -21,6 +21,15 @@ pub const UART_IS_SIM: bool = true;
 #[cfg(feature = "fpga")]
 pub const UART_IS_SIM: bool = false;
 
+/// Returns the UART configuration the host expects.
+pub fn uart_config() -> embassy_neorv32::uart::Config {
+    embassy_neorv32::uart::Config {
+        baud_rate: UART_BAUD,
+        sim: UART_IS_SIM,
+        flow_control: false,
+    }
+}
+
 /// Time is much slower in simulation so this is just a rough scaling to try and get simulation
 /// to match our perception.
 ///
@@ -51,9 +60,7 @@ fn panic_handler(info: &core::panic::PanicInfo) -> ! {
     // SAFETY: Don't have a choice if we want to display the panic message,
     // but worst that can happen is the UART output gets corrupted
     let p = unsafe { embassy_neorv32::Peripherals::steal() };
-    if let Ok(mut uart) =
-        embassy_neorv32::uart::UartTx::new_blocking(p.UART0, UART_BAUD, UART_IS_SIM, false)
-    {
+    if let Ok(mut uart) = embassy_neorv32::uart::UartTx::new_blocking(p.UART0, uart_config()) {
         writeln!(
             &mut uart,
             "\n\nHART {} PANIC: {} at {}",
@@ -0,0 +1,282 @@
+//! Custom Functions Subsystem (CFS)
+//!
+//! The CFS is a slot for user-defined hardware in the NEORV32 SoC, accessed through 64 generic
+//! 32-bit registers. What those registers mean is entirely up to the design placed in the CFS,
+//! so this driver only provides safe access to them plus interrupt handling.
+//!
+//! Downstream crates are expected to describe their register layout with [`cfs_registers!`]
+//! and build their own driver on top of [`Cfs`] using the typed accessors:
+//!
+//! ```rust,ignore
+//! use embassy_neorv32::cfs::{self, Cfs};
+//!
+//! embassy_neorv32::cfs_registers! {
+//!     /// Control register.
+//!     pub CTRL @ 0: u32, rw;
+//!     /// Status register.
+//!     pub STATUS @ 1: u32, r;
+//!     /// Input data register.
+//!     pub DATA_IN @ 2: u32, w;
+//! }
+//!
+//! pub struct Accelerator<'d> {
+//!     cfs: Cfs<'d, cfs::Async>,
+//! }
+//!
+//! impl<'d> Accelerator<'d> {
+//!     pub async fn process(&mut self, word: u32) -> u32 {
+//!         self.cfs.write::<DATA_IN>(word);
+//!         self.cfs.modify::<CTRL>(|ctrl| ctrl | 1);
+//!         self.cfs.wait_irq().await;
+//!         self.cfs.read::<STATUS>()
+//!     }
+//! }
+//! ```
+use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
+use crate::peripherals::CFS;
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::Poll;
+use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::waitqueue::AtomicWaker;
+
+/// Number of generic registers in the CFS register space.
+pub const NUM_REGS: usize = 64;
+
+/// CFS interrupt handler binding.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        // We don't know how the custom hardware clears its interrupt,
+        // so disable it here and leave acknowledging it to the user
+        T::Interrupt::disable();
+        T::fired().store(true, Ordering::Release);
+        T::waker().wake();
+    }
+}
+
+/// CFS error.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The NEORV32 configuration does not implement a CFS.
+    NotSupported,
+}
+
+/// A register in the CFS register space.
+///
+/// Usually implemented with [`cfs_registers!`] rather than by hand.
+pub trait Register {
+    /// Index of the register (must be less than [`NUM_REGS`]).
+    const INDEX: usize;
+
+    /// The type register contents are converted to and from.
+    type Value: From<u32> + Into<u32>;
+}
+
+/// A CFS register that can be read.
+pub trait Readable: Register {}
+
+/// A CFS register that can be written.
+pub trait Writable: Register {}
+
+/// Declares typed CFS registers for use with [`Cfs::read`], [`Cfs::write`] and [`Cfs::modify`].
+///
+/// Each entry is of the form `$vis NAME @ INDEX: ValueType, access;` where access is one of
+/// `r`, `w` or `rw`, and `ValueType` implements `From<u32> + Into<u32>` (e.g. `u32` or a
+/// bitfield newtype). Indices are checked at compile time to be within the CFS register space.
+///
+/// See the [`cfs`](crate::cfs) module docs for an example.
+#[macro_export]
+macro_rules! cfs_registers {
+    ($($(#[$meta:meta])* $vis:vis $name:ident @ $index:literal : $ty:ty, $access:ident;)*) => {
+        $(
+            $(#[$meta])*
+            #[allow(non_camel_case_types)]
+            $vis struct $name;
+
+            impl $crate::cfs::Register for $name {
+                const INDEX: usize = {
+                    assert!($index < $crate::cfs::NUM_REGS, "CFS register index out of range");
+                    $index
+                };
+                type Value = $ty;
+            }
+
+            $crate::cfs_registers!(@access $name, $access);
+        )*
+    };
+    (@access $name:ident, r) => {
+        impl $crate::cfs::Readable for $name {}
+    };
+    (@access $name:ident, w) => {
+        impl $crate::cfs::Writable for $name {}
+    };
+    (@access $name:ident, rw) => {
+        impl $crate::cfs::Readable for $name {}
+        impl $crate::cfs::Writable for $name {}
+    };
+}
+
+/// Custom Functions Subsystem (CFS) driver.
+pub struct Cfs<'d, M: IoMode> {
+    regs: *mut u32,
+    waker: &'static AtomicWaker,
+    fired: &'static AtomicBool,
+    _phantom: PhantomData<&'d M>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d, M: IoMode> Send for Cfs<'d, M> {}
+
+impl<'d, M: IoMode> Cfs<'d, M> {
+    fn new_inner<T: Instance>(_instance: Peri<'d, T>) -> Result<Self, Error> {
+        if !crate::sysinfo::SysInfo::soc_config().has_cfs() {
+            return Err(Error::NotSupported);
+        }
+
+        Ok(Self {
+            regs: T::regs(),
+            waker: T::waker(),
+            fired: T::fired(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Reads the raw value of the register at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`NUM_REGS`].
+    pub fn read_raw(&self, index: usize) -> u32 {
+        assert!(index < NUM_REGS);
+        // SAFETY: We own the CFS and the index is within the register space
+        unsafe { self.regs.add(index).read_volatile() }
+    }
+
+    /// Writes a raw value to the register at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`NUM_REGS`].
+    pub fn write_raw(&mut self, index: usize, value: u32) {
+        assert!(index < NUM_REGS);
+        // SAFETY: We own the CFS and the index is within the register space
+        unsafe { self.regs.add(index).write_volatile(value) }
+    }
+
+    /// Reads a typed register.
+    pub fn read<R: Readable>(&self) -> R::Value {
+        R::Value::from(self.read_raw(R::INDEX))
+    }
+
+    /// Writes a typed register.
+    pub fn write<R: Writable>(&mut self, value: R::Value) {
+        self.write_raw(R::INDEX, value.into());
+    }
+
+    /// Reads a typed register, modifies it with `f`, then writes it back.
+    pub fn modify<R: Readable + Writable>(&mut self, f: impl FnOnce(R::Value) -> R::Value) {
+        let value = f(self.read::<R>());
+        self.write::<R>(value);
+    }
+}
+
+impl<'d> Cfs<'d, Blocking> {
+    /// Create a new instance of a blocking CFS driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if the CFS is not implemented.
+    pub fn new_blocking<T: Instance>(_instance: Peri<'d, T>) -> Result<Self, Error> {
+        Self::new_inner(_instance)
+    }
+}
+
+impl<'d> Cfs<'d, Async> {
+    /// Create a new instance of an async CFS driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if the CFS is not implemented.
+    pub fn new_async<T: Instance>(
+        _instance: Peri<'d, T>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_inner(_instance)
+    }
+
+    /// Waits for the CFS to raise its interrupt.
+    ///
+    /// The CFS interrupt stays disabled once this returns. It is up to the caller to acknowledge
+    /// it in the custom hardware (in whatever way the design requires) before waiting again,
+    /// otherwise this returns immediately.
+    pub async fn wait_irq(&mut self) {
+        self.fired.store(false, Ordering::Release);
+
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            if self.fired.load(Ordering::Acquire) {
+                Poll::Ready(())
+            } else {
+                // SAFETY: It is valid to enable interrupts here, since if the CFS interrupt
+                // is already pending it fires immediately and we won't miss it
+                unsafe { crate::enable_periph_irq!(CFS) }
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+trait SealedIoMode {}
+
+/// CFS IO mode.
+#[allow(private_bounds)]
+pub trait IoMode: SealedIoMode {}
+
+/// Blocking CFS.
+pub struct Blocking;
+impl SealedIoMode for Blocking {}
+impl IoMode for Blocking {}
+
+/// Async CFS.
+pub struct Async;
+impl SealedIoMode for Async {}
+impl IoMode for Async {}
+
+trait SealedInstance {
+    fn regs() -> *mut u32;
+    fn waker() -> &'static AtomicWaker;
+    fn fired() -> &'static AtomicBool;
+}
+
+/// A valid CFS peripheral.
+#[allow(private_bounds)]
+pub trait Instance: SealedInstance + PeripheralType {
+    type Interrupt: Interrupt;
+}
+impl SealedInstance for CFS {
+    fn regs() -> *mut u32 {
+        // The PAC exposes each register individually, but they are laid out contiguously
+        crate::pac::Cfs::ptr() as *mut u32
+    }
+
+    fn waker() -> &'static AtomicWaker {
+        static WAKER: AtomicWaker = AtomicWaker::new();
+        &WAKER
+    }
+
+    fn fired() -> &'static AtomicBool {
+        static FIRED: AtomicBool = AtomicBool::new(false);
+        &FIRED
+    }
+}
+impl Instance for CFS {
+    type Interrupt = crate::interrupt::typelevel::CFS;
+}
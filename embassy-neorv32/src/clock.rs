@@ -0,0 +1,304 @@
+//! Clock divider solver shared by peripherals clocked through the common NEORV32 prescaler.
+//!
+//! Peripherals derive their clock as `f_cpu / (scale * PRSC * div)`, where `PRSC` is one of the
+//! [`PRSC`] values and `div` is a peripheral-specific divider. Given a target rate, [`solve`]
+//! searches every prescaler for the divider producing the closest achievable rate.
+//!
+//! This module has no hardware dependencies so it can be unit tested on the host.
+
+/// Clock prescaler values selected by a 3-bit PRSC field.
+pub(crate) const PRSC: [u32; 8] = [2, 4, 8, 64, 128, 1024, 2048, 4096];
+
+/// Clock solver error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Error {
+    /// The target rate is zero or outside the range the peripheral can produce.
+    OutOfRange,
+    /// The closest achievable rate deviates from the target by more than the allowed tolerance.
+    OutOfTolerance,
+}
+
+/// Describes how a peripheral derives its clock.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Constraints<'a> {
+    /// Fixed divisor applied on top of the prescaler and divider.
+    pub scale: u32,
+    /// Prescaler values to choose from.
+    pub prescalers: &'a [u32],
+    /// Smallest divider value.
+    pub min_div: u32,
+    /// Largest divider value.
+    pub max_div: u32,
+    /// Maximum allowed deviation from the target rate, in parts per thousand.
+    pub tolerance_permille: u32,
+}
+
+/// UART: `baud = f_cpu / (PRSC * div)` with a 10-bit `div - 1` field.
+pub(crate) const UART: Constraints<'static> = Constraints {
+    scale: 1,
+    prescalers: &PRSC,
+    min_div: 1,
+    max_div: 1 << 10,
+    // Receiver sampling tolerates a few percent of combined error between both ends
+    tolerance_permille: 25,
+};
+
+/// SPI: `f_sck = f_cpu / (2 * PRSC * div)` with a 4-bit `div - 1` field.
+pub(crate) const SPI: Constraints<'static> = Constraints {
+    scale: 2,
+    prescalers: &PRSC,
+    min_div: 1,
+    max_div: 1 << 4,
+    tolerance_permille: 100,
+};
+
+/// TWI: `f_scl = f_cpu / (4 * PRSC * div)` with a 4-bit `div - 1` field.
+pub(crate) const TWI: Constraints<'static> = Constraints {
+    scale: 4,
+    prescalers: &PRSC,
+    min_div: 1,
+    max_div: 1 << 4,
+    tolerance_permille: 100,
+};
+
+/// A solved prescaler and divider pair.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Divider {
+    /// Index into [`Constraints::prescalers`] (which for [`PRSC`] is the PRSC field value).
+    pub prsc_sel: u8,
+    /// Divider value (the caller applies any register offset, such as `div - 1`).
+    pub div: u32,
+    /// Rate actually achieved (rounded to the nearest Hz).
+    pub freq: u32,
+}
+
+impl Constraints<'_> {
+    /// Returns the rate produced by the given prescaler index and divider, rounded to nearest.
+    pub(crate) fn rate(&self, cpu_freq: u32, prsc_sel: u8, div: u32) -> u32 {
+        let denom = self.scale as u64 * self.prescalers[prsc_sel as usize] as u64 * div as u64;
+        ((cpu_freq as u64 + denom / 2) / denom) as u32
+    }
+
+    fn max_rate(&self, cpu_freq: u32) -> u64 {
+        let prsc = self.prescalers.iter().copied().min().unwrap_or(1) as u64;
+        cpu_freq as u64 / (self.scale as u64 * prsc * self.min_div as u64)
+    }
+
+    fn min_rate(&self, cpu_freq: u32) -> u64 {
+        let prsc = self.prescalers.iter().copied().max().unwrap_or(1) as u64;
+        cpu_freq as u64 / (self.scale as u64 * prsc * self.max_div as u64)
+    }
+}
+
+// Exact deviation of an achieved rate from the target, as the fraction `num / denom` Hz
+#[derive(Clone, Copy, Debug)]
+struct Deviation {
+    num: u64,
+    denom: u64,
+}
+
+impl Deviation {
+    fn new(cpu_freq: u32, target: u32, c: &Constraints, prsc_sel: u8, div: u32) -> Self {
+        // rate = cpu_freq / denom, so |rate - target| = |cpu_freq - target * denom| / denom
+        let denom = c.scale as u64 * c.prescalers[prsc_sel as usize] as u64 * div as u64;
+        let num = (cpu_freq as u64).abs_diff(target as u64 * denom);
+        Self { num, denom }
+    }
+
+    fn lt(&self, other: &Self) -> bool {
+        (self.num as u128 * other.denom as u128) < (other.num as u128 * self.denom as u128)
+    }
+
+    fn exceeds(&self, target: u32, tolerance_permille: u32) -> bool {
+        self.num as u128 * 1000 > tolerance_permille as u128 * target as u128 * self.denom as u128
+    }
+}
+
+/// Finds the prescaler and divider producing the rate closest to `target` (in Hz).
+///
+/// When several pairs achieve the same rate, the smallest prescaler is preferred since it
+/// leaves the most divider resolution.
+pub(crate) fn solve(cpu_freq: u32, target: u32, c: &Constraints) -> Result<Divider, Error> {
+    if target == 0 || cpu_freq == 0 {
+        return Err(Error::OutOfRange);
+    }
+
+    let mut best: Option<(Deviation, u8, u32)> = None;
+    for (prsc_sel, &prsc) in c.prescalers.iter().enumerate() {
+        let prsc_sel = prsc_sel as u8;
+        let exact = cpu_freq as u64 / (c.scale as u64 * prsc as u64 * target as u64);
+
+        // The rate is monotonic in the divider, so the best divider is one of the two
+        // integers bracketing the exact (fractional) solution
+        for div in [exact, exact + 1] {
+            let div = div.clamp(c.min_div as u64, c.max_div as u64) as u32;
+            let deviation = Deviation::new(cpu_freq, target, c, prsc_sel, div);
+
+            if best.is_none_or(|(best_deviation, ..)| deviation.lt(&best_deviation)) {
+                best = Some((deviation, prsc_sel, div));
+            }
+        }
+    }
+
+    let (deviation, prsc_sel, div) = best.ok_or(Error::OutOfRange)?;
+    if deviation.exceeds(target, c.tolerance_permille) {
+        let target = target as u64;
+        if target > c.max_rate(cpu_freq) || target < c.min_rate(cpu_freq) {
+            return Err(Error::OutOfRange);
+        }
+        return Err(Error::OutOfTolerance);
+    }
+
+    Ok(Divider {
+        prsc_sel,
+        div,
+        freq: c.rate(cpu_freq, prsc_sel, div),
+    })
+}
+
+/// Converts a duration in milliseconds into ticks of a counter clocked at `cpu_freq / prescaler`,
+/// rounded to the nearest tick.
+///
+/// Returns [`Error::OutOfRange`] if the result exceeds `max_ticks`, or if a non-zero duration
+/// is shorter than a single tick.
+pub(crate) fn ms_to_ticks(
+    cpu_freq: u32,
+    prescaler: u32,
+    ms: u32,
+    max_ticks: u32,
+) -> Result<u32, Error> {
+    let denom = prescaler as u64 * 1000;
+    let ticks = (ms as u64 * cpu_freq as u64 + denom / 2) / denom;
+
+    if ticks > max_ticks as u64 || (ticks == 0 && ms != 0) {
+        Err(Error::OutOfRange)
+    } else {
+        Ok(ticks as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CPU_FREQS: [u32; 4] = [12_000_000, 50_000_000, 100_000_000, 150_000_000];
+
+    // Brute force over every prescaler and divider, returning the smallest achievable deviation
+    fn best_deviation(cpu_freq: u32, target: u32, c: &Constraints) -> Deviation {
+        (0..c.prescalers.len() as u8)
+            .flat_map(|prsc_sel| (c.min_div..=c.max_div).map(move |div| (prsc_sel, div)))
+            .map(|(prsc_sel, div)| Deviation::new(cpu_freq, target, c, prsc_sel, div))
+            .reduce(|best, d| if d.lt(&best) { d } else { best })
+            .unwrap()
+    }
+
+    fn check_optimal(c: &Constraints, targets: &[u32]) {
+        for cpu_freq in CPU_FREQS {
+            for &target in targets {
+                let expected = best_deviation(cpu_freq, target, c);
+                match solve(cpu_freq, target, c) {
+                    Ok(d) => {
+                        let deviation = Deviation::new(cpu_freq, target, c, d.prsc_sel, d.div);
+                        assert!(
+                            !expected.lt(&deviation),
+                            "cpu {cpu_freq} target {target}: {d:?} is not optimal"
+                        );
+                        assert!(d.div >= c.min_div && d.div <= c.max_div);
+                        assert_eq!(d.freq, c.rate(cpu_freq, d.prsc_sel, d.div));
+                    }
+                    Err(_) => assert!(
+                        expected.exceeds(target, c.tolerance_permille),
+                        "cpu {cpu_freq} target {target}: rejected but {expected:?} is in tolerance"
+                    ),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn uart_baud_rates_are_optimal() {
+        let bauds = [
+            1200, 9600, 19200, 38400, 57600, 115_200, 230_400, 460_800, 921_600,
+        ];
+        check_optimal(&UART, &bauds);
+    }
+
+    #[test]
+    fn spi_frequencies_are_optimal() {
+        let freqs = [
+            1_000, 100_000, 400_000, 1_000_000, 4_000_000, 10_000_000, 25_000_000,
+        ];
+        check_optimal(&SPI, &freqs);
+    }
+
+    #[test]
+    fn twi_frequencies_are_optimal() {
+        let freqs = [10_000, 100_000, 400_000, 1_000_000];
+        check_optimal(&TWI, &freqs);
+    }
+
+    #[test]
+    fn uart_common_rates_are_accurate() {
+        // PRSC 2 would need a divider of 1302, which doesn't fit in 10 bits
+        let d = solve(50_000_000, 19200, &UART).unwrap();
+        assert_eq!((d.prsc_sel, d.div, d.freq), (1, 651, 19201));
+
+        let d = solve(100_000_000, 115_200, &UART).unwrap();
+        assert_eq!((d.prsc_sel, d.div, d.freq), (0, 434, 115_207));
+    }
+
+    #[test]
+    fn prefers_smallest_prescaler_on_ties() {
+        // 1.5625 MHz is exact for PRSC 2 / div 8, PRSC 4 / div 4 and PRSC 8 / div 2
+        let d = solve(50_000_000, 1_562_500, &SPI).unwrap();
+        assert_eq!((d.prsc_sel, d.div, d.freq), (0, 8, 1_562_500));
+    }
+
+    #[test]
+    fn rejects_zero_and_out_of_range() {
+        assert_eq!(solve(50_000_000, 0, &SPI), Err(Error::OutOfRange));
+        assert_eq!(solve(0, 1_000_000, &SPI), Err(Error::OutOfRange));
+        // Fastest SPI clock at 50 MHz is 12.5 MHz
+        assert_eq!(solve(50_000_000, 20_000_000, &SPI), Err(Error::OutOfRange));
+        // Slowest UART baud at 50 MHz is ~11.9 baud
+        assert_eq!(solve(50_000_000, 5, &UART), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    fn rejects_out_of_tolerance() {
+        // Achievable UART rates near 1.8 Mbaud at 12 MHz are 2 Mbaud and 1.5 Mbaud
+        assert_eq!(
+            solve(12_000_000, 1_800_000, &UART),
+            Err(Error::OutOfTolerance)
+        );
+    }
+
+    #[test]
+    fn single_prescaler_constraints() {
+        let pwm = Constraints {
+            scale: 1,
+            prescalers: &[4096],
+            min_div: 1,
+            max_div: 1 << 16,
+            tolerance_permille: 100,
+        };
+        let d = solve(50_000_000, 42, &pwm).unwrap();
+        assert_eq!((d.prsc_sel, d.div, d.freq), (0, 291, 42));
+        assert_eq!(solve(50_000_000, 1_000_000, &pwm), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    fn ms_to_ticks_rounds_and_checks_range() {
+        // WDT ticks at 50 MHz / 4096 = 12207.03 Hz
+        assert_eq!(ms_to_ticks(50_000_000, 4096, 1, 0xff_ffff), Ok(12));
+        assert_eq!(ms_to_ticks(50_000_000, 4096, 1000, 0xff_ffff), Ok(12207));
+        assert_eq!(ms_to_ticks(50_000_000, 4096, 0, 0xff_ffff), Ok(0));
+        // 24-bit counter overflows after ~1374 s
+        assert_eq!(
+            ms_to_ticks(50_000_000, 4096, 1_400_000, 0xff_ffff),
+            Err(Error::OutOfRange)
+        );
+        // Less than half a tick
+        assert_eq!(ms_to_ticks(100, 4096, 1, 0xff_ffff), Err(Error::OutOfRange));
+    }
+}
@@ -1,12 +1,18 @@
 //! Direct Memory Access (DMA)
 use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
 use crate::peripherals::DMA;
+use core::cell::{Cell, RefCell, UnsafeCell};
+use core::future::poll_fn;
 use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
 use core::pin::Pin;
-use core::sync::atomic::{AtomicBool, Ordering, fence};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering, fence};
 use core::task::{Context, Poll};
+use embassy_hal_internal::drop::OnDrop;
 use embassy_hal_internal::{Peri, PeripheralType};
-use embassy_sync::waitqueue::AtomicWaker;
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::waitqueue::{AtomicWaker, MultiWakerRegistration};
 
 const U23_MAX: u32 = 0xff_ffff;
 
@@ -21,15 +27,22 @@ impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
         // In poll, we can check the BUSY flag to know if we are done,
         // but still need a way to check for bus error.
         //
-        // So we cache the ERROR flag before clearing it.
-        let err = T::info().reg.ctrl().read().dma_ctrl_error().bit_is_set();
-        T::info().err_flag.store(err, Ordering::Release);
-        T::info()
-            .reg
-            .ctrl()
-            .modify(|_, w| w.dma_ctrl_ack().set_bit());
+        // So we cache the ERROR flag before clearing it. The cached flag is only ever set here
+        // (submitting clears it), so a later successful descriptor can't hide an earlier error.
+        //
+        // Only this handler modifies the completion count, so a plain load/store is fine here
+        let info = T::info();
+        let completed = info.completed.load(Ordering::Relaxed);
+        if info.reg.ctrl().read().dma_ctrl_error().bit_is_set()
+            && !info.err_flag.load(Ordering::Relaxed)
+        {
+            info.err_index.store(completed, Ordering::Relaxed);
+            info.err_flag.store(true, Ordering::Release);
+        }
+        info.completed.store(completed + 1, Ordering::Relaxed);
+        info.reg.ctrl().modify(|_, w| w.dma_ctrl_ack().set_bit());
 
-        T::info().waker.wake();
+        info.waker.wake();
     }
 }
 
@@ -41,6 +54,13 @@ pub enum Error {
     NotSupported,
     /// Indicates a bus error occurred during transfer.
     BusError,
+    /// Indicates a bus error occurred during a chained transfer.
+    ///
+    /// All elements before `index` completed successfully.
+    ChainBusError {
+        /// Index of the element of the chain that hit the bus error.
+        index: usize,
+    },
 }
 
 enum DataConfig {
@@ -95,18 +115,153 @@ impl From<TransferConfig> for u32 {
     }
 }
 
-enum Descriptor {
-    BaseAddress(u32),
-    Config(TransferConfig),
+// Each transfer descriptor occupies this many words in the descriptor FIFO
+const DESCRIPTOR_WORDS: usize = 3;
+
+/// A single transfer in a DMA descriptor chain.
+///
+/// Descriptors borrow their buffers for as long as they live, so a chain built from them can be
+/// submitted with [`Dma::chain`] and executed by the hardware back-to-back.
+///
+/// A scatter-gather chain is simply a list of descriptors, for example gathering several
+/// buffers into one contiguous packet:
+///
+/// ```rust,ignore
+/// let (dst_header, dst_payload) = packet.split_at_mut(header.len());
+/// let descriptors = [
+///     TransferDescriptor::copy(&header, dst_header, false),
+///     TransferDescriptor::copy(&payload, dst_payload, false),
+/// ];
+/// dma.chain(&descriptors).await?;
+/// ```
+pub struct TransferDescriptor<'a> {
+    src: u32,
+    dst: u32,
+    config: u32,
+    _phantom: PhantomData<&'a mut ()>,
 }
 
-impl From<Descriptor> for u32 {
-    fn from(descriptor: Descriptor) -> Self {
-        match descriptor {
-            Descriptor::BaseAddress(addr) => addr,
-            Descriptor::Config(cfg) => cfg.into(),
+impl<'a> TransferDescriptor<'a> {
+    fn new<S, D>(
+        src: *const S,
+        src_cfg: DataConfig,
+        dst: *mut D,
+        dst_cfg: DataConfig,
+        len: usize,
+        swap_byte_order: bool,
+    ) -> Self {
+        let config = TransferConfig::new(len as u32, swap_byte_order, src_cfg, dst_cfg);
+        Self {
+            src: src as u32,
+            dst: dst as u32,
+            config: config.into(),
+            _phantom: PhantomData,
         }
     }
+
+    /// Creates a descriptor which reads from `src` until the `dst` buffer is filled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `dst` buffer length can not be represented in 23 bits.
+    pub fn read<W: Word>(src: &'a W, dst: &'a mut [W], swap_byte_order: bool) -> Self {
+        Self::new(
+            src,
+            W::cfg_constant(),
+            dst.as_mut_ptr(),
+            W::cfg_increment(),
+            dst.len(),
+            swap_byte_order,
+        )
+    }
+
+    /// Creates a descriptor which writes all elements from the `src` buffer to `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `src` buffer length can not be represented in 23 bits.
+    pub fn write<W: Word>(src: &'a [W], dst: &'a mut W, swap_byte_order: bool) -> Self {
+        Self::new(
+            src.as_ptr(),
+            W::cfg_increment(),
+            dst,
+            W::cfg_constant(),
+            src.len(),
+            swap_byte_order,
+        )
+    }
+
+    /// Creates a descriptor which copies all elements from the `src` buffer to the `dst` buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `src` buffer length does not match the `dst` buffer length,
+    /// or if the buffer length can not be represented in 23 bits.
+    pub fn copy<W: Word>(src: &'a [W], dst: &'a mut [W], swap_byte_order: bool) -> Self {
+        assert!(src.len() == dst.len());
+        Self::new(
+            src.as_ptr(),
+            W::cfg_increment(),
+            dst.as_mut_ptr(),
+            W::cfg_increment(),
+            src.len(),
+            swap_byte_order,
+        )
+    }
+
+    /// Creates a descriptor which fills the `dst` buffer with `value` (memset).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `dst` buffer length can not be represented in 23 bits.
+    pub fn fill<W: Word>(value: &'a W, dst: &'a mut [W]) -> Self {
+        Self::new(
+            value,
+            W::cfg_constant(),
+            dst.as_mut_ptr(),
+            W::cfg_increment(),
+            dst.len(),
+            false,
+        )
+    }
+
+    /// Creates a descriptor which writes all bytes from the `src` buffer to the 32-bit `dst`,
+    /// zero-extending each byte to a word.
+    ///
+    /// This is useful for feeding 32-bit peripheral registers from byte buffers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `src` buffer length can not be represented in 23 bits.
+    pub fn write_extended(src: &'a [u8], dst: &'a mut u32) -> Self {
+        Self::new(
+            src.as_ptr(),
+            DataConfig::IncrementingByte,
+            dst,
+            DataConfig::ConstantWord,
+            src.len(),
+            false,
+        )
+    }
+
+    /// Creates a descriptor which copies all bytes from the `src` buffer to the `dst` buffer,
+    /// zero-extending each byte to a word.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `src` buffer length does not match the `dst` buffer length,
+    /// or if the buffer length can not be represented in 23 bits.
+    pub fn copy_extended(src: &'a [u8], dst: &'a mut [u32]) -> Self {
+        assert!(src.len() == dst.len());
+        Self::new(
+            src.as_ptr(),
+            DataConfig::IncrementingByte,
+            dst.as_mut_ptr(),
+            DataConfig::IncrementingWord,
+            src.len(),
+            false,
+        )
+    }
 }
 
 /// DMA driver.
@@ -142,12 +297,33 @@ impl<'d> Dma<'d> {
             .modify(|_, w| w.dma_ctrl_start().set_bit());
     }
 
-    fn write_descriptor(&mut self, descriptor: Descriptor) {
-        // SAFETY: We are writing a valid descriptor
-        self.info
-            .reg
-            .desc()
-            .write(|w| unsafe { w.bits(descriptor.into()) });
+    fn write_descriptor(&mut self, descriptor: &TransferDescriptor) {
+        for word in [descriptor.src, descriptor.dst, descriptor.config] {
+            // SAFETY: TransferDescriptor ensures we are writing a valid descriptor
+            self.info.reg.desc().write(|w| unsafe { w.bits(word) });
+        }
+    }
+
+    fn descriptor_capacity(&self) -> usize {
+        // Value in register is log2 of fifo depth (in words)
+        let fifo_depth = 1 << self.info.reg.ctrl().read().dma_ctrl_fifo().bits();
+        (fifo_depth / DESCRIPTOR_WORDS).max(1)
+    }
+
+    // Queues descriptors into the (assumed empty) descriptor FIFO and starts processing them
+    fn submit(&mut self, descriptors: &[TransferDescriptor]) {
+        // Clear error flag and completion count, and enable DMA
+        self.info.err_flag.store(false, Ordering::Release);
+        self.info.completed.store(0, Ordering::Relaxed);
+        self.enable();
+
+        for descriptor in descriptors {
+            self.write_descriptor(descriptor);
+        }
+
+        // Flush cache to ensure DMA sees most recent main memory, then start transfer
+        fence(Ordering::SeqCst);
+        self.start();
     }
 
     fn busy(&self) -> bool {
@@ -193,15 +369,7 @@ impl<'d> Dma<'d> {
         dst: &mut [W],
         swap_byte_order: bool,
     ) -> Transfer<'d, 't> {
-        Transfer::new(
-            self,
-            src as *const W as *const u32,
-            W::cfg_constant(),
-            dst.as_mut_ptr() as *mut u32,
-            W::cfg_increment(),
-            dst.len() as u32,
-            swap_byte_order,
-        )
+        Transfer::new(self, TransferDescriptor::read(src, dst, swap_byte_order))
     }
 
     /// Starts a transfer which writes all elements from the `src` buffer to `dst`.
@@ -215,15 +383,7 @@ impl<'d> Dma<'d> {
         dst: &mut W,
         swap_byte_order: bool,
     ) -> Transfer<'d, 't> {
-        Transfer::new(
-            self,
-            src.as_ptr() as *const u32,
-            W::cfg_increment(),
-            dst as *mut W as *mut u32,
-            W::cfg_constant(),
-            src.len() as u32,
-            swap_byte_order,
-        )
+        Transfer::new(self, TransferDescriptor::write(src, dst, swap_byte_order))
     }
 
     /// Starts a transfer which copies all elements from the `src` buffer to the `dst` buffer.
@@ -238,16 +398,118 @@ impl<'d> Dma<'d> {
         dst: &mut [W],
         swap_byte_order: bool,
     ) -> Transfer<'d, 't> {
+        Transfer::new(self, TransferDescriptor::copy(src, dst, swap_byte_order))
+    }
+
+    /// Starts a transfer which fills the `dst` buffer with `value` (memset).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `dst` buffer length can not be represented in 23 bits.
+    pub fn fill<'t, W: Word>(&'t mut self, value: &W, dst: &mut [W]) -> Transfer<'d, 't> {
+        Transfer::new(self, TransferDescriptor::fill(value, dst))
+    }
+
+    /// Starts a transfer which writes all bytes from the `src` buffer to the 32-bit `dst`,
+    /// zero-extending each byte to a word.
+    ///
+    /// This is useful for feeding 32-bit peripheral registers from byte buffers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `src` buffer length can not be represented in 23 bits.
+    pub fn write_extended<'t>(&'t mut self, src: &[u8], dst: &mut u32) -> Transfer<'d, 't> {
+        Transfer::new(self, TransferDescriptor::write_extended(src, dst))
+    }
+
+    /// Starts a transfer which copies all bytes from the `src` buffer to the `dst` buffer,
+    /// zero-extending each byte to a word.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `src` buffer length does not match the `dst` buffer length,
+    /// or if the buffer length can not be represented in 23 bits.
+    pub fn copy_extended<'t>(&'t mut self, src: &[u8], dst: &mut [u32]) -> Transfer<'d, 't> {
+        Transfer::new(self, TransferDescriptor::copy_extended(src, dst))
+    }
+
+    /// Starts a transfer which copies all words from the `src` buffer to the `dst` buffer,
+    /// reversing the byte order of each word.
+    ///
+    /// This converts between little-endian (native) and big-endian words.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `src` buffer length does not match the `dst` buffer length,
+    /// or if the buffer length can not be represented in 23 bits.
+    pub fn swap_bytes<'t>(&'t mut self, src: &[u32], dst: &mut [u32]) -> Transfer<'d, 't> {
+        self.copy(src, dst, true)
+    }
+
+    /// Copies all halfwords from the `src` buffer to the `dst` buffer,
+    /// reversing the byte order of each halfword.
+    ///
+    /// This converts between little-endian (native) and big-endian halfwords.
+    ///
+    /// **Note**: The hardware can only reverse the byte order of whole words, which also swaps
+    /// the two halfwords packed in each word. So after the transfer completes, the halfwords are
+    /// put back in order by the CPU. The transfer itself is aborted if cancelled/dropped.
+    ///
+    /// Only the word-aligned middle of the buffers is transferred by DMA. A halfword before or
+    /// after it is swapped by the CPU, as is everything if `src` and `dst` differ in alignment.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BusError`] if a bus error occurred during transfer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `src` buffer length does not match the `dst` buffer length,
+    /// or if the buffer length can not be represented in 23 bits.
+    pub async fn swap_bytes_u16(&mut self, src: &[u16], dst: &mut [u16]) -> Result<(), Error> {
         assert!(src.len() == dst.len());
-        Transfer::new(
-            self,
-            src.as_ptr() as *const u32,
-            W::cfg_increment(),
-            dst.as_mut_ptr() as *mut u32,
-            W::cfg_increment(),
-            src.len() as u32,
-            swap_byte_order,
-        )
+
+        // Words of the two buffers only line up if they share the same alignment
+        let word_offset = |ptr: *const u16| ptr as usize % align_of::<u32>();
+        if word_offset(src.as_ptr()) != word_offset(dst.as_ptr()) {
+            for (dst, src) in dst.iter_mut().zip(src) {
+                *dst = src.swap_bytes();
+            }
+            return Ok(());
+        }
+
+        // SAFETY: Any pair of halfwords is a valid word
+        let (src_head, src_words, src_tail) = unsafe { src.align_to::<u32>() };
+        // SAFETY: Any pair of halfwords is a valid word and any word is a valid pair of halfwords
+        let (dst_head, dst_words, dst_tail) = unsafe { dst.align_to_mut::<u32>() };
+
+        for (dst, src) in dst_head.iter_mut().zip(src_head) {
+            *dst = src.swap_bytes();
+        }
+        for (dst, src) in dst_tail.iter_mut().zip(src_tail) {
+            *dst = src.swap_bytes();
+        }
+
+        if !src_words.is_empty() {
+            self.swap_bytes(src_words, dst_words).await?;
+            for word in dst_words {
+                *word = word.rotate_left(16);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Starts a chain of transfers which the hardware executes back-to-back, in order.
+    ///
+    /// Descriptors are queued into the hardware descriptor FIFO as many at a time as it can hold,
+    /// so the CPU is only involved once per FIFO-full of descriptors rather than once per transfer.
+    /// The chain stops at the first bus error, which is reported as [`Error::ChainBusError`].
+    pub fn chain<'t>(
+        &'t mut self,
+        descriptors: &'t [TransferDescriptor<'_>],
+    ) -> ChainTransfer<'d, 't> {
+        ChainTransfer::new(self, descriptors)
     }
 }
 
@@ -268,36 +530,9 @@ pub struct Transfer<'d, 't> {
 }
 
 impl<'d, 't> Transfer<'d, 't> {
-    fn new(
-        dma: &'t mut Dma<'d>,
-        src: *const u32,
-        src_cfg: DataConfig,
-        dst: *mut u32,
-        dst_cfg: DataConfig,
-        len: u32,
-        swap_byte_order: bool,
-    ) -> Self {
-        // Clear error flag and enable DMA
-        dma.info.err_flag.store(false, Ordering::Release);
-        dma.enable();
-
-        // Configure the transfer
-        let config = TransferConfig::new(len, swap_byte_order, src_cfg, dst_cfg);
-        let descriptors = [
-            Descriptor::BaseAddress(src as u32),
-            Descriptor::BaseAddress(dst as u32),
-            Descriptor::Config(config),
-        ];
-
-        // Write each descriptor
+    fn new(dma: &'t mut Dma<'d>, descriptor: TransferDescriptor) -> Self {
         // We are assuming the descriptor FIFO is empty because this HAL does not allow partial transfers in the FIFO
-        for descriptor in descriptors {
-            dma.write_descriptor(descriptor);
-        }
-
-        // Flush cache to ensure DMA sees most recent main memory, then start transfer
-        fence(Ordering::SeqCst);
-        dma.start();
+        dma.submit(core::slice::from_ref(&descriptor));
         Self { dma }
     }
 }
@@ -325,6 +560,318 @@ impl<'d, 't> Future for Transfer<'d, 't> {
     }
 }
 
+/// A chain of DMA transfers.
+///
+/// The chain should be awaited to ensure completion.
+///
+/// **Note**: The chain will be aborted if cancelled/dropped before completion.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ChainTransfer<'d, 't> {
+    dma: &'t mut Dma<'d>,
+    descriptors: &'t [TransferDescriptor<'t>],
+    // Index of the first element in the batch currently queued in hardware
+    batch_start: usize,
+    // Index of the first element not yet queued
+    next: usize,
+}
+
+impl<'d, 't> ChainTransfer<'d, 't> {
+    fn new(dma: &'t mut Dma<'d>, descriptors: &'t [TransferDescriptor<'t>]) -> Self {
+        let mut chain = Self {
+            dma,
+            descriptors,
+            batch_start: 0,
+            next: 0,
+        };
+        chain.submit_batch();
+        chain
+    }
+
+    fn submit_batch(&mut self) {
+        let end = (self.next + self.dma.descriptor_capacity()).min(self.descriptors.len());
+        if self.next < end {
+            self.batch_start = self.next;
+            self.dma.submit(&self.descriptors[self.next..end]);
+            self.next = end;
+        }
+    }
+}
+
+impl<'d, 't> Drop for ChainTransfer<'d, 't> {
+    fn drop(&mut self) {
+        self.dma.abort();
+    }
+}
+
+impl<'d, 't> Future for ChainTransfer<'d, 't> {
+    type Output = Result<(), Error>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.dma.info.waker.register(cx.waker());
+
+        if self.descriptors.is_empty() {
+            Poll::Ready(Ok(()))
+        } else if self.dma.busy() {
+            Poll::Pending
+        } else if self.dma.info.err_flag.load(Ordering::Acquire) {
+            // The interrupt handler records which descriptor of the current batch faulted
+            let index = self.batch_start + self.dma.info.err_index.load(Ordering::Relaxed);
+            Poll::Ready(Err(Error::ChainBusError { index }))
+        } else if self.next == self.descriptors.len() {
+            Poll::Ready(Ok(()))
+        } else {
+            // Previous batch completed, so the descriptor FIFO is empty again
+            self.submit_batch();
+            Poll::Pending
+        }
+    }
+}
+
+/// Priority hint used when arbitrating access to a [`SharedDma`].
+///
+/// When the DMA is released, waiters of a higher priority are always granted access before
+/// waiters of a lower priority. Waiters of equal priority are granted access in no particular order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Priority {
+    /// Low priority.
+    Low,
+    /// Normal priority.
+    Normal,
+    /// High priority.
+    High,
+}
+
+/// What a driver using a [`SharedChannel`] does when the DMA is in use by someone else.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OnBusy {
+    /// Wait for the DMA to become available.
+    Wait,
+    /// Don't wait and transfer the data manually (PIO) instead.
+    Pio,
+}
+
+struct SharedState {
+    locked: bool,
+    // Number of waiters at each priority level
+    waiting: [usize; 3],
+    wakers: MultiWakerRegistration<4>,
+}
+
+impl SharedState {
+    fn can_lock(&self, priority: Priority) -> bool {
+        !self.locked
+            && self.waiting[priority as usize + 1..]
+                .iter()
+                .all(|&n| n == 0)
+    }
+}
+
+/// A DMA engine shared between multiple drivers.
+///
+/// The DMA is single-channel, so [`Dma`] may only have a single owner. `SharedDma` instead
+/// arbitrates access to it per transfer, so multiple drivers (and tasks) can each hold a
+/// [`SharedChannel`] and take turns using it.
+pub struct SharedDma<'d> {
+    dma: UnsafeCell<Dma<'d>>,
+    state: Mutex<CriticalSectionRawMutex, RefCell<SharedState>>,
+}
+
+// SAFETY: Access to the inner DMA is only ever granted to a single guard at a time
+unsafe impl<'d> Send for SharedDma<'d> {}
+unsafe impl<'d> Sync for SharedDma<'d> {}
+
+impl<'d> SharedDma<'d> {
+    /// Creates a new instance of a shared DMA driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if DMA is not supported.
+    pub fn new<T: Instance>(
+        _instance: Peri<'d, T>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            dma: UnsafeCell::new(Dma::new(_instance, _irq)?),
+            state: Mutex::new(RefCell::new(SharedState {
+                locked: false,
+                waiting: [0; 3],
+                wakers: MultiWakerRegistration::new(),
+            })),
+        })
+    }
+
+    /// Returns a channel drivers can use to share this DMA.
+    ///
+    /// `priority` applies to each transfer the driver performs, and `on_busy` decides whether the
+    /// driver waits for the DMA or falls back to transferring data manually while it is in use.
+    pub fn channel(&'d self, priority: Priority, on_busy: OnBusy) -> SharedChannel<'d> {
+        SharedChannel {
+            shared: self,
+            priority,
+            on_busy,
+        }
+    }
+
+    /// Attempts to get exclusive access to the DMA without waiting.
+    ///
+    /// Returns `None` if the DMA is in use, or if a waiter of higher priority is queued.
+    pub fn try_lock(&self, priority: Priority) -> Option<SharedDmaGuard<'_, 'd>> {
+        self.state.lock(|state| {
+            let mut state = state.borrow_mut();
+            if state.can_lock(priority) {
+                state.locked = true;
+                Some(SharedDmaGuard { shared: self })
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Waits for exclusive access to the DMA.
+    ///
+    /// Access is released when the returned guard is dropped.
+    pub async fn lock(&self, priority: Priority) -> SharedDmaGuard<'_, 'd> {
+        let queued = Cell::new(false);
+
+        // If cancelled while queued, leave the queue so lower priority waiters aren't held up
+        let _dequeue = OnDrop::new(|| {
+            if queued.get() {
+                self.state.lock(|state| {
+                    let mut state = state.borrow_mut();
+                    state.waiting[priority as usize] -= 1;
+                    state.wakers.wake();
+                });
+            }
+        });
+
+        poll_fn(|cx| {
+            self.state.lock(|state| {
+                let mut state = state.borrow_mut();
+                if queued.replace(false) {
+                    state.waiting[priority as usize] -= 1;
+                }
+
+                if state.can_lock(priority) {
+                    state.locked = true;
+                    Poll::Ready(SharedDmaGuard { shared: self })
+                } else {
+                    state.waiting[priority as usize] += 1;
+                    queued.set(true);
+                    state.wakers.register(cx.waker());
+                    Poll::Pending
+                }
+            })
+        })
+        .await
+    }
+}
+
+/// Exclusive access to a [`SharedDma`], released when dropped.
+///
+/// Dereferences to [`Dma`] so any transfer can be started with it.
+pub struct SharedDmaGuard<'a, 'd> {
+    shared: &'a SharedDma<'d>,
+}
+
+impl<'a, 'd> Deref for SharedDmaGuard<'a, 'd> {
+    type Target = Dma<'d>;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: Only a single guard exists at a time
+        unsafe { &*self.shared.dma.get() }
+    }
+}
+
+impl<'a, 'd> DerefMut for SharedDmaGuard<'a, 'd> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: Only a single guard exists at a time
+        unsafe { &mut *self.shared.dma.get() }
+    }
+}
+
+impl<'a, 'd> Drop for SharedDmaGuard<'a, 'd> {
+    fn drop(&mut self) {
+        self.shared.state.lock(|state| {
+            let mut state = state.borrow_mut();
+            state.locked = false;
+            state.wakers.wake();
+        });
+    }
+}
+
+/// A driver's handle to a [`SharedDma`], created with [`SharedDma::channel`].
+#[derive(Clone, Copy)]
+pub struct SharedChannel<'d> {
+    shared: &'d SharedDma<'d>,
+    priority: Priority,
+    on_busy: OnBusy,
+}
+
+// The DMA as held by peripheral drivers, either owned outright or shared
+pub(crate) enum Channel<'d> {
+    Exclusive(Dma<'d>),
+    Shared(SharedChannel<'d>),
+}
+
+impl<'d> Channel<'d> {
+    // Returns access to the DMA for a single transfer, or None if the caller should use PIO instead
+    async fn acquire(&mut self) -> Option<ChannelGuard<'_, 'd>> {
+        match self {
+            Self::Exclusive(dma) => Some(ChannelGuard::Exclusive(dma)),
+            Self::Shared(channel) => match channel.on_busy {
+                OnBusy::Wait => {
+                    let guard = channel.shared.lock(channel.priority).await;
+                    Some(ChannelGuard::Shared(guard))
+                }
+                OnBusy::Pio => channel
+                    .shared
+                    .try_lock(channel.priority)
+                    .map(ChannelGuard::Shared),
+            },
+        }
+    }
+
+    // Reads from `src` until `dst` is filled, returning false if the caller should use PIO instead
+    pub(crate) async fn read<W: Word>(&mut self, src: &W, dst: &mut [W]) -> Result<bool, Error> {
+        match self.acquire().await {
+            Some(mut dma) => dma.read(src, dst, false).await.map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    // Writes all of `src` to `dst`, returning false if the caller should use PIO instead
+    pub(crate) async fn write<W: Word>(&mut self, src: &[W], dst: &mut W) -> Result<bool, Error> {
+        match self.acquire().await {
+            Some(mut dma) => dma.write(src, dst, false).await.map(|_| true),
+            None => Ok(false),
+        }
+    }
+}
+
+enum ChannelGuard<'a, 'd> {
+    Exclusive(&'a mut Dma<'d>),
+    Shared(SharedDmaGuard<'a, 'd>),
+}
+
+impl<'a, 'd> Deref for ChannelGuard<'a, 'd> {
+    type Target = Dma<'d>;
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Exclusive(dma) => dma,
+            Self::Shared(guard) => guard,
+        }
+    }
+}
+
+impl<'a, 'd> DerefMut for ChannelGuard<'a, 'd> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Exclusive(dma) => dma,
+            Self::Shared(guard) => guard,
+        }
+    }
+}
+
 trait SealedWord {
     fn cfg_constant() -> DataConfig;
     fn cfg_increment() -> DataConfig;
@@ -335,7 +882,11 @@ trait SealedWord {
 /// **Note**: The hardware supports transferring `u8` to `u32` (by zero-extending the `u8`),
 /// but does not seem to support transferring `u32` to `u8` (ideally it would truncate the 24 MSB).
 ///
-/// So, for ease of use, the driver only supports `u8` <-> `u8` and `u32` <-> `u32` transfers.
+/// So, for ease of use, generic transfers only support `u8` <-> `u8` and `u32` <-> `u32`.
+/// Zero-extending transfers are available via [`Dma::write_extended`] and [`Dma::copy_extended`].
+///
+/// Byte order swapping operates on whole words, so `swap_byte_order` should only be set for
+/// `u32` transfers (see also [`Dma::swap_bytes`] and [`Dma::swap_bytes_u16`]).
 #[allow(private_bounds)]
 pub trait Word: SealedWord {}
 
@@ -369,6 +920,9 @@ struct Info {
     reg: &'static crate::pac::dma::RegisterBlock,
     waker: &'static AtomicWaker,
     err_flag: &'static AtomicBool,
+    // Number of descriptors completed since the last submission, and which of them hit an error
+    completed: &'static AtomicUsize,
+    err_index: &'static AtomicUsize,
 }
 
 trait SealedInstance {
@@ -385,15 +939,116 @@ impl SealedInstance for DMA {
     fn info() -> Info {
         static WAKER: AtomicWaker = AtomicWaker::new();
         static ERR_FLAG: AtomicBool = AtomicBool::new(false);
+        static COMPLETED: AtomicUsize = AtomicUsize::new(0);
+        static ERR_INDEX: AtomicUsize = AtomicUsize::new(0);
 
         Info {
             // SAFETY: We are the sole users of the pointer and are sure to use it safely
             reg: unsafe { &*crate::pac::Dma::ptr() },
             waker: &WAKER,
             err_flag: &ERR_FLAG,
+            completed: &COMPLETED,
+            err_index: &ERR_INDEX,
         }
     }
 }
 impl Instance for DMA {
     type Interrupt = crate::interrupt::typelevel::DMA;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DST_SHIFT: u32 = 30;
+    const SRC_SHIFT: u32 = 28;
+    const BSWAP: u32 = 1 << 27;
+
+    const CONSTANT_BYTE: u32 = 0b00;
+    const CONSTANT_WORD: u32 = 0b01;
+    const INCREMENTING_BYTE: u32 = 0b10;
+    const INCREMENTING_WORD: u32 = 0b11;
+
+    fn config(src: u32, dst: u32, swap_byte_order: bool, num_elems: u32) -> u32 {
+        (dst << DST_SHIFT)
+            | (src << SRC_SHIFT)
+            | if swap_byte_order { BSWAP } else { 0 }
+            | num_elems
+    }
+
+    #[test]
+    fn transfer_config_encoding() {
+        let cfg = TransferConfig::new(
+            U23_MAX - 1,
+            true,
+            DataConfig::ConstantByte,
+            DataConfig::IncrementingWord,
+        );
+        assert_eq!(u32::from(cfg), 0xc8ff_fffe);
+
+        let cfg = TransferConfig::new(
+            1,
+            false,
+            DataConfig::IncrementingWord,
+            DataConfig::ConstantByte,
+        );
+        assert_eq!(u32::from(cfg), 0x3000_0001);
+    }
+
+    #[test]
+    #[should_panic]
+    fn transfer_config_rejects_empty() {
+        TransferConfig::new(0, false, DataConfig::ConstantByte, DataConfig::ConstantByte);
+    }
+
+    #[test]
+    fn read_write_copy_encoding() {
+        let src = [0u32; 4];
+        let mut dst = [0u32; 4];
+        let mut reg = 0u32;
+
+        let d = TransferDescriptor::read(&src[0], &mut dst, false);
+        assert_eq!(d.config, config(CONSTANT_WORD, INCREMENTING_WORD, false, 4));
+        let d = TransferDescriptor::write(&src, &mut reg, true);
+        assert_eq!(d.config, config(INCREMENTING_WORD, CONSTANT_WORD, true, 4));
+        let d = TransferDescriptor::copy(&src[..3], &mut dst[..3], false);
+        assert_eq!(
+            d.config,
+            config(INCREMENTING_WORD, INCREMENTING_WORD, false, 3)
+        );
+    }
+
+    #[test]
+    fn fill_encoding() {
+        let mut bytes = [0u8; 7];
+        let d = TransferDescriptor::fill(&0xa5, &mut bytes);
+        assert_eq!(d.config, config(CONSTANT_BYTE, INCREMENTING_BYTE, false, 7));
+
+        let mut words = [0u32; 5];
+        let d = TransferDescriptor::fill(&0xdead_beef, &mut words);
+        assert_eq!(d.config, config(CONSTANT_WORD, INCREMENTING_WORD, false, 5));
+    }
+
+    #[test]
+    fn extended_encoding() {
+        let src = [0u8; 6];
+        let mut reg = 0u32;
+        let mut dst = [0u32; 6];
+
+        let d = TransferDescriptor::write_extended(&src, &mut reg);
+        assert_eq!(d.config, config(INCREMENTING_BYTE, CONSTANT_WORD, false, 6));
+        let d = TransferDescriptor::copy_extended(&src, &mut dst);
+        assert_eq!(
+            d.config,
+            config(INCREMENTING_BYTE, INCREMENTING_WORD, false, 6)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn copy_extended_rejects_length_mismatch() {
+        let src = [0u8; 4];
+        let mut dst = [0u32; 3];
+        let _ = TransferDescriptor::copy_extended(&src, &mut dst);
+    }
+}
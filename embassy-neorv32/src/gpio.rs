@@ -1,17 +1,28 @@
 //! General-Purpose Input/Output (GPIO)
 use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
 use crate::peripherals::GPIO;
+use core::cell::RefCell;
 use core::convert::Infallible;
 use core::future::poll_fn;
 use core::marker::PhantomData;
+use core::sync::atomic::{AtomicI32, Ordering};
 use core::task::Poll;
 use critical_section::CriticalSection;
+use embassy_futures::select::{Either, select};
+use embassy_hal_internal::drop::OnDrop;
 use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::{Channel, SendDynamicReceiver, SendDynamicSender};
 use embassy_sync::waitqueue::AtomicWaker;
+use embassy_time::{Duration, Instant, Timer, with_timeout};
 
 // Max number of GPIO ports available
 const MAX_PORTS: usize = 32;
 
+// Max number of quadrature decoders active at once
+const MAX_QEI_DECODERS: usize = 4;
+
 /// GPIO interrupt handler binding.
 pub struct InterruptHandler<T: Instance> {
     _phantom: PhantomData<T>,
@@ -19,21 +30,29 @@ pub struct InterruptHandler<T: Instance> {
 
 impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
     unsafe fn on_interrupt() {
+        let timestamp = riscv::register::mcycle::read64();
         let pending = T::info().reg.irq_pending().read().bits();
         let mut disabled = T::info().reg.irq_enable().read().bits();
 
-        // Wake and disable every port that has IRQ pending
+        // Ports in event mode stay enabled and have their events queued instead of being woken
+        let queued = T::info().events.queue(T::info().reg, pending, timestamp);
+
+        // Ports of quadrature decoders also stay enabled, and are decoded right here
+        let decoded = T::info().qei.decode(T::info().reg, pending);
+
+        // Wake and disable every other port that has IRQ pending
         for (i, waker) in T::info().wakers.iter().enumerate() {
             let port_bit = 1 << i;
-            if (pending & port_bit) != 0 {
+            if (pending & !queued & !decoded & port_bit) != 0 {
                 waker.wake();
                 disabled &= !port_bit;
             }
         }
 
-        // Clear pending
+        // Clear pending (ports in event mode and of decoders were already cleared above)
         // SAFETY: Register is write 0 to clear, so we bitwise not `pending` to clear only those,
         // assuring if a port becomes pending in the meantime we don't clobber it
+        let pending = pending & !queued & !decoded;
         T::info()
             .reg
             .irq_pending()
@@ -54,6 +73,11 @@ impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
 pub enum Error {
     /// The NEORV32 configuration does not support GPIO.
     NotSupported,
+    /// Recorded edges were dropped because the event channel was full, or were missed because
+    /// a pulse was shorter than the interrupt latency.
+    EventsLost,
+    /// The maximum number of quadrature decoders are already in use.
+    NoFreeDecoder,
 }
 
 /// GPIO driver.
@@ -88,6 +112,48 @@ impl<'d, M: IoMode> Gpio<'d, M> {
     pub fn new_output<T: PortInstance>(&self, _instance: Peri<'d, T>) -> Output<'d> {
         Output::new(T::PORT, self.info.reg)
     }
+
+    /// Create a new instance of an input bank driver from a group of input-only ports.
+    ///
+    /// The ports can then be read and waited on together.
+    pub fn new_input_bank<const N: usize>(&self, inputs: [Input<'d, M>; N]) -> InputBank<'d, M> {
+        let mask = inputs
+            .iter()
+            .fold(0, |mask, input| mask | input.info.port_mask);
+
+        // The bank takes over disabling the ports' interrupts when dropped
+        for input in inputs {
+            core::mem::forget(input);
+        }
+
+        InputBank {
+            mask,
+            reg: self.info.reg,
+            wakers: self.info.wakers,
+            events: self.info.events,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a new instance of a bus driver from a group of ports.
+    ///
+    /// Bit `i` of values read from and written to the bus corresponds to `ports[i]`, so the ports
+    /// need not be contiguous nor in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the same port is given more than once.
+    pub fn new_bus<const N: usize>(&self, ports: [Port<'d, M>; N]) -> GpioBus<'d, N> {
+        let pins = ports.map(|port| {
+            let pin = port.input.info.port_mask.trailing_zeros() as u8;
+
+            // The bus takes over disabling the port's interrupt when dropped
+            core::mem::forget(port);
+            pin
+        });
+
+        GpioBus::new(pins, self.info.reg)
+    }
 }
 
 impl<'d> Gpio<'d, Blocking> {
@@ -116,6 +182,45 @@ impl<'d> Gpio<'d, Async> {
         unsafe { T::Interrupt::enable() }
         Ok(gpio)
     }
+
+    /// Create a new instance of a pulse capture driver from an input-only port.
+    ///
+    /// Edges are timestamped by the interrupt handler and queued into `channel`, which must be
+    /// large enough to hold the edges that can occur before the driver is polled again.
+    pub fn new_pulse_capture<const N: usize>(
+        &self,
+        input: Input<'d, Async>,
+        channel: &'static EventChannel<N>,
+    ) -> PulseCapture<'d> {
+        let mask = input.info.port_mask;
+
+        // The event stream takes over disabling the port's interrupt when dropped
+        core::mem::forget(input);
+
+        PulseCapture {
+            stream: EventStream::new(
+                mask,
+                self.info.reg,
+                self.info.events,
+                channel,
+                EdgeTrigger::Any,
+            ),
+        }
+    }
+
+    /// Create a new instance of a quadrature decoder from the A and B input-only ports of an encoder.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoFreeDecoder`] if the maximum number of decoders (4) are already in use.
+    pub fn new_qei_decoder(
+        &self,
+        a: Input<'d, Async>,
+        b: Input<'d, Async>,
+        mode: QeiMode,
+    ) -> Result<QeiDecoder<'d>, Error> {
+        QeiDecoder::new(a, b, mode, self.info.qei)
+    }
 }
 
 /// A GPIO port.
@@ -388,6 +493,290 @@ impl<'d, M: IoMode> Drop for Input<'d, M> {
     }
 }
 
+/// The condition an [InputBank] waits for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Trigger {
+    /// The input signal is low.
+    Low,
+    /// The input signal is high.
+    High,
+    /// The input signal transitions from high to low.
+    FallingEdge,
+    /// The input signal transitions from low to high.
+    RisingEdge,
+    /// The input signal undergoes any state transition.
+    AnyEdge,
+}
+
+/// The edges recorded by an [EventStream].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EdgeTrigger {
+    /// Record only falling edges.
+    Falling,
+    /// Record only rising edges.
+    Rising,
+    /// Record both falling and rising edges.
+    Any,
+}
+
+/// A signal edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Edge {
+    /// The input signal transitioned from high to low.
+    Falling,
+    /// The input signal transitioned from low to high.
+    Rising,
+}
+
+/// An edge recorded by an [EventStream].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Event {
+    /// The port the edge occurred on.
+    pub port: u8,
+    /// The edge that occurred.
+    pub edge: Edge,
+    /// The CPU cycle count (`mcycle`) when the interrupt handler recorded the edge.
+    pub timestamp: u64,
+}
+
+/// The channel type an [EventStream] queues events into.
+pub type EventChannel<const N: usize> = Channel<CriticalSectionRawMutex, Event, N>;
+
+/// A group of input-only ports which can be read and waited on together.
+///
+/// Ports are identified by their bit in a mask, where bit `i` corresponds to `PORTi`.
+pub struct InputBank<'d, M: IoMode> {
+    mask: u32,
+    reg: &'static crate::pac::gpio::RegisterBlock,
+    wakers: &'static [AtomicWaker; MAX_PORTS],
+    events: &'static EventState,
+    _phantom: PhantomData<&'d M>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d, M: IoMode> Send for InputBank<'d, M> {}
+
+impl<'d, M: IoMode> InputBank<'d, M> {
+    fn irq_disable(&mut self, mask: u32, _cs: CriticalSection) {
+        // SAFETY: We only clear our bits. This is only called in a critical section so no risk of clobbering others.
+        self.reg
+            .irq_enable()
+            .modify(|r, w| unsafe { w.bits(r.bits() & !mask) });
+    }
+
+    /// Returns the mask of ports in the bank.
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Returns the input signals of all ports in the bank, read at the same time.
+    ///
+    /// Bits of ports not in the bank are always cleared.
+    pub fn levels(&self) -> u32 {
+        self.reg.port_in().read().bits() & self.mask
+    }
+}
+
+impl<'d> InputBank<'d, Async> {
+    /// Wait until `trigger` occurs on any port in the bank, returning the mask of triggered ports.
+    ///
+    /// See [Self::wait_masked] for details.
+    pub async fn wait(&mut self, trigger: Trigger) -> u32 {
+        self.wait_masked(self.mask, trigger).await
+    }
+
+    /// Wait until `trigger` occurs on any port in `mask`, returning the mask of triggered ports.
+    ///
+    /// Level triggers return immediately if any port is already at that level, with the mask of
+    /// all such ports. For [Trigger::AnyEdge], each port waits for the opposite of its current level.
+    ///
+    /// More than one bit is set in the returned mask if several ports triggered before the task
+    /// got to run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mask` is empty or contains ports not in the bank.
+    pub async fn wait_masked(&mut self, mask: u32, trigger: Trigger) -> u32 {
+        assert!(mask != 0 && (mask & !self.mask) == 0);
+
+        let levels = self.reg.port_in().read().bits();
+        let (edge, polarity_high) = match trigger {
+            Trigger::Low => (false, 0),
+            Trigger::High => (false, u32::MAX),
+            Trigger::FallingEdge => (true, 0),
+            Trigger::RisingEdge => (true, u32::MAX),
+            Trigger::AnyEdge => (true, !levels),
+        };
+
+        // Level triggers are already satisfied by ports at that level
+        if !edge {
+            let reached = !(levels ^ polarity_high) & mask;
+            if reached != 0 {
+                return reached;
+            }
+        }
+
+        critical_section::with(|cs| configure_irq(self.reg, mask, edge, polarity_high, cs));
+
+        // If cancelled, don't leave the remaining ports' interrupts enabled
+        let reg = self.reg;
+        let on_drop = OnDrop::new(|| {
+            // SAFETY: We only clear our bits. This is called in a critical section so no risk of clobbering others.
+            critical_section::with(|_| {
+                reg.irq_enable()
+                    .modify(|r, w| unsafe { w.bits(r.bits() & !mask) })
+            });
+        });
+
+        let triggered = poll_fn(|cx| {
+            for (i, waker) in self.wakers.iter().enumerate() {
+                if (mask & (1 << i)) != 0 {
+                    waker.register(cx.waker());
+                }
+            }
+
+            // Ports whose irq was disabled by the interrupt handler have triggered
+            critical_section::with(|cs| {
+                let triggered = mask & !self.reg.irq_enable().read().bits();
+                if triggered != 0 {
+                    self.irq_disable(mask, cs);
+                    Poll::Ready(triggered)
+                } else {
+                    Poll::Pending
+                }
+            })
+        })
+        .await;
+
+        on_drop.defuse();
+        triggered
+    }
+
+    /// Starts recording edges on all ports in the bank into `channel`.
+    ///
+    /// Unlike [Self::wait], edges are recorded by the interrupt handler as they occur, so fast
+    /// edges are not lost between polls as long as `channel` has room. Events that don't fit are
+    /// dropped and reported by [EventStream::take_lost].
+    ///
+    /// **Note**: Hardware can only trigger on one edge at a time, so for [EdgeTrigger::Any] the
+    /// interrupt handler re-arms each port for the opposite of its current level after every
+    /// edge. If a pulse is shorter than the interrupt latency, the edge ending it can't be
+    /// recorded. This is detected from the port level and also reported by
+    /// [EventStream::take_lost].
+    ///
+    /// Recording stops when the returned stream is dropped.
+    pub fn events<const N: usize>(
+        &mut self,
+        channel: &'static EventChannel<N>,
+        edges: EdgeTrigger,
+    ) -> EventStream<'_> {
+        EventStream::new(self.mask, self.reg, self.events, channel, edges)
+    }
+}
+
+impl<'d, M: IoMode> Drop for InputBank<'d, M> {
+    fn drop(&mut self) {
+        critical_section::with(|cs| self.irq_disable(self.mask, cs));
+    }
+}
+
+// Sets the trigger type and polarity of the ports in `mask` and enables their interrupts
+fn configure_irq(
+    reg: &crate::pac::gpio::RegisterBlock,
+    mask: u32,
+    edge: bool,
+    polarity_high: u32,
+    _cs: CriticalSection,
+) {
+    // SAFETY: We only modify our bits. This is only called in a critical section so no risk of clobbering others.
+    reg.irq_type().modify(|r, w| unsafe {
+        w.bits(if edge {
+            r.bits() | mask
+        } else {
+            r.bits() & !mask
+        })
+    });
+    // SAFETY: As above
+    reg.irq_polarity()
+        .modify(|r, w| unsafe { w.bits((r.bits() & !mask) | (polarity_high & mask)) });
+    // SAFETY: As above
+    reg.irq_enable()
+        .modify(|r, w| unsafe { w.bits(r.bits() | mask) });
+}
+
+/// A stream of edges recorded from an [InputBank], created with [InputBank::events].
+pub struct EventStream<'a> {
+    mask: u32,
+    reg: &'static crate::pac::gpio::RegisterBlock,
+    events: &'static EventState,
+    receiver: SendDynamicReceiver<'static, Event>,
+    _phantom: PhantomData<&'a mut ()>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'a> Send for EventStream<'a> {}
+
+impl<'a> EventStream<'a> {
+    fn new<const N: usize>(
+        mask: u32,
+        reg: &'static crate::pac::gpio::RegisterBlock,
+        events: &'static EventState,
+        channel: &'static EventChannel<N>,
+        edges: EdgeTrigger,
+    ) -> Self {
+        let polarity_high = match edges {
+            EdgeTrigger::Falling => 0,
+            EdgeTrigger::Rising => u32::MAX,
+            EdgeTrigger::Any => !reg.port_in().read().bits(),
+        };
+
+        critical_section::with(|cs| {
+            events.register(mask, edges == EdgeTrigger::Any, channel.sender().into(), cs);
+            configure_irq(reg, mask, true, polarity_high, cs);
+        });
+
+        Self {
+            mask,
+            reg,
+            events,
+            receiver: channel.receiver().into(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Wait for the next recorded edge.
+    pub async fn next(&mut self) -> Event {
+        self.receiver.receive().await
+    }
+
+    /// Returns the next recorded edge, or `None` if none is queued.
+    pub fn try_next(&mut self) -> Option<Event> {
+        self.receiver.try_receive().ok()
+    }
+
+    /// Returns the mask of ports which had edges dropped because the channel was full, or
+    /// missed because a pulse was too short to be recorded, and clears it.
+    pub fn take_lost(&mut self) -> u32 {
+        critical_section::with(|cs| self.events.take_lost(self.mask, cs))
+    }
+}
+
+impl<'a> Drop for EventStream<'a> {
+    fn drop(&mut self) {
+        critical_section::with(|cs| {
+            // SAFETY: We only clear our bits. This is only called in a critical section so no risk of clobbering others.
+            self.reg
+                .irq_enable()
+                .modify(|r, w| unsafe { w.bits(r.bits() & !self.mask) });
+            self.events.unregister(self.mask, cs);
+        });
+    }
+}
+
 pub struct Output<'d> {
     info: OutputInfo,
     _phantom: PhantomData<&'d ()>,
@@ -450,6 +839,921 @@ impl<'d> Output<'d> {
     }
 }
 
+/// A group of ports read and written together as a parallel bus.
+///
+/// Every read and write accesses all of the bus ports in a single register access,
+/// so there are no intermediate states where only some of the ports have changed.
+pub struct GpioBus<'d, const N: usize> {
+    // Port driven by each bit of the bus
+    pins: [u8; N],
+    // Mask of all ports in the bus
+    mask: u32,
+    // Shift from bus bits to ports if the ports are contiguous and in order
+    shift: Option<u8>,
+    reg: &'static crate::pac::gpio::RegisterBlock,
+    _phantom: PhantomData<&'d ()>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d, const N: usize> Send for GpioBus<'d, N> {}
+
+impl<'d, const N: usize> GpioBus<'d, N> {
+    fn new(pins: [u8; N], reg: &'static crate::pac::gpio::RegisterBlock) -> Self {
+        let mask = pins.iter().fold(0u32, |mask, &pin| {
+            assert!((mask & (1 << pin)) == 0, "port given more than once");
+            mask | (1 << pin)
+        });
+        let contiguous = pins
+            .iter()
+            .enumerate()
+            .all(|(i, &pin)| pin == pins[0] + i as u8);
+
+        Self {
+            pins,
+            mask,
+            shift: (N > 0 && contiguous).then(|| pins[0]),
+            reg,
+            _phantom: PhantomData,
+        }
+    }
+
+    // Maps bus bits to port bits
+    fn scatter(&self, value: u32) -> u32 {
+        match self.shift {
+            Some(shift) => (value << shift) & self.mask,
+            None => scatter(&self.pins, value),
+        }
+    }
+
+    // Maps port bits to bus bits
+    fn gather(&self, bits: u32) -> u32 {
+        match self.shift {
+            Some(shift) => (bits & self.mask) >> shift,
+            None => gather(&self.pins, bits),
+        }
+    }
+
+    // Writes `bits` to the ports in `mask` in a single register access
+    fn write_port_out(&mut self, bits: u32, mask: u32) {
+        critical_section::with(|_| {
+            // SAFETY: We only modify our bits. This is only called in a critical section so no risk of clobbering others.
+            self.reg
+                .port_out()
+                .modify(|r, w| unsafe { w.bits((r.bits() & !mask) | (bits & mask)) })
+        });
+    }
+
+    /// Returns the mask of ports in the bus.
+    pub fn port_mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Returns the value of the bus's input signals.
+    pub fn read(&self) -> u32 {
+        self.gather(self.reg.port_in().read().bits())
+    }
+
+    /// Returns the value the bus's output signals are set to.
+    pub fn read_output(&self) -> u32 {
+        self.gather(self.reg.port_out().read().bits())
+    }
+
+    /// Sets the bus's output signals to `value`.
+    ///
+    /// Bits of `value` beyond the width of the bus are ignored.
+    pub fn write(&mut self, value: u32) {
+        self.write_port_out(self.scatter(value), self.mask);
+    }
+
+    /// Sets only the bus's output signals whose bit is set in `mask` to `value`,
+    /// leaving the others unchanged.
+    ///
+    /// Bits of `value` and `mask` beyond the width of the bus are ignored.
+    pub fn write_masked(&mut self, value: u32, mask: u32) {
+        self.write_port_out(self.scatter(value), self.scatter(mask));
+    }
+
+    /// Sets the bus's output signals whose bit is set in `mask` high.
+    pub fn set_bits(&mut self, mask: u32) {
+        self.write_masked(u32::MAX, mask);
+    }
+
+    /// Sets the bus's output signals whose bit is set in `mask` low.
+    pub fn clear_bits(&mut self, mask: u32) {
+        self.write_masked(0, mask);
+    }
+}
+
+impl<'d, const N: usize> Drop for GpioBus<'d, N> {
+    fn drop(&mut self) {
+        critical_section::with(|_| {
+            // SAFETY: We only clear our bits. This is only called in a critical section so no risk of clobbering others.
+            self.reg
+                .irq_enable()
+                .modify(|r, w| unsafe { w.bits(r.bits() & !self.mask) })
+        });
+    }
+}
+
+fn scatter(pins: &[u8], value: u32) -> u32 {
+    pins.iter()
+        .enumerate()
+        .filter(|&(i, _)| (value & (1 << i)) != 0)
+        .fold(0, |bits, (_, &pin)| bits | (1 << pin))
+}
+
+fn gather(pins: &[u8], bits: u32) -> u32 {
+    pins.iter()
+        .enumerate()
+        .filter(|&(_, &pin)| (bits & (1 << pin)) != 0)
+        .fold(0, |value, (i, _)| value | (1 << i))
+}
+
+/// A word transferred over a [Parallel8080] bus.
+///
+/// Words wider than the bus are truncated, and words read from a bus wider than them are truncated.
+#[allow(private_bounds)]
+pub trait BusWord: SealedBusWord + Copy {}
+
+trait SealedBusWord {
+    fn to_bits(self) -> u32;
+    fn from_bits(bits: u32) -> Self;
+}
+
+macro_rules! impl_bus_word {
+    ($ty:ty) => {
+        impl SealedBusWord for $ty {
+            fn to_bits(self) -> u32 {
+                self as u32
+            }
+
+            fn from_bits(bits: u32) -> Self {
+                bits as $ty
+            }
+        }
+        impl BusWord for $ty {}
+    };
+}
+
+impl_bus_word!(u8);
+impl_bus_word!(u16);
+impl_bus_word!(u32);
+
+/// A bit-banged Intel 8080-style parallel interface, as used by many LCD controllers.
+///
+/// Data is latched by the display on the rising edge of WR (and driven by it while RD is low),
+/// with DC selecting between command (low) and data (high) words. Chip select, if given,
+/// is held low for the duration of each call.
+///
+/// Each data word and the falling edge of WR are driven in a single register access, with no
+/// extra delay between write edges. This suits the short write cycles of common display
+/// controllers, but check your controller's datasheet at high CPU clock speeds.
+///
+/// Reads are slower: the controller only drives the bus a read access time (`tRDAT`, often tens
+/// to hundreds of nanoseconds) after RD falls. So the bus is sampled after a configurable read
+/// strobe delay, which defaults to [`Self::DEFAULT_READ_DELAY_NS`] (see
+/// [`Self::set_read_delay_ns`]).
+///
+/// **Note**: NEORV32 GPIO has separate input and output lines and no tristate control, so the
+/// output lines always drive the data bus. Reading requires external bus buffering, such as a
+/// tristate buffer on the output lines disabled while RD is low, with the data bus also wired to
+/// the corresponding input lines.
+pub struct Parallel8080<'d, const N: usize> {
+    bus: GpioBus<'d, N>,
+    dc: Output<'d>,
+    wr: Output<'d>,
+    rd: Option<Output<'d>>,
+    cs: Option<Output<'d>>,
+    read_delay_cycles: u32,
+}
+
+impl<'d, const N: usize> Parallel8080<'d, N> {
+    /// Default delay between the falling edge of RD and sampling the bus, in nanoseconds.
+    pub const DEFAULT_READ_DELAY_NS: u32 = 400;
+
+    /// Creates a new 8080-style parallel interface driver.
+    ///
+    /// `rd` is only needed for reading from the display, and `cs` only if chip select is not tied low.
+    pub fn new(
+        bus: GpioBus<'d, N>,
+        mut dc: Output<'d>,
+        mut wr: Output<'d>,
+        mut rd: Option<Output<'d>>,
+        mut cs: Option<Output<'d>>,
+    ) -> Self {
+        // Strobes and chip select are active low, so start idle
+        dc.set_high();
+        wr.set_high();
+        if let Some(rd) = &mut rd {
+            rd.set_high();
+        }
+        if let Some(cs) = &mut cs {
+            cs.set_high();
+        }
+
+        Self {
+            bus,
+            dc,
+            wr,
+            rd,
+            cs,
+            read_delay_cycles: ns_to_cycles(Self::DEFAULT_READ_DELAY_NS),
+        }
+    }
+
+    /// Sets the delay between the falling edge of RD and sampling the bus, in nanoseconds.
+    ///
+    /// This should be at least the controller's read access time (`tRDAT`) plus the propagation
+    /// delay of any bus buffering. The delay is busy-waited and rounded up to whole CPU cycles.
+    pub fn set_read_delay_ns(&mut self, ns: u32) {
+        self.read_delay_cycles = ns_to_cycles(ns);
+    }
+
+    /// Releases the bus and control ports.
+    pub fn release(
+        self,
+    ) -> (
+        GpioBus<'d, N>,
+        Output<'d>,
+        Output<'d>,
+        Option<Output<'d>>,
+        Option<Output<'d>>,
+    ) {
+        (self.bus, self.dc, self.wr, self.rd, self.cs)
+    }
+
+    fn select(&mut self) {
+        if let Some(cs) = &mut self.cs {
+            cs.set_low();
+        }
+    }
+
+    fn deselect(&mut self) {
+        if let Some(cs) = &mut self.cs {
+            cs.set_high();
+        }
+    }
+
+    fn write_words<W: BusWord>(&mut self, words: &[W]) {
+        let wr_mask = self.wr.info.port_mask;
+        let mask = self.bus.mask | wr_mask;
+
+        for word in words {
+            // Drive data and pull WR low together, then latch with WR rising edge
+            let bits = self.bus.scatter(word.to_bits());
+            self.bus.write_port_out(bits, mask);
+            self.wr.set_high();
+        }
+    }
+
+    /// Writes a command word (DC low).
+    pub fn write_command<W: BusWord>(&mut self, command: W) {
+        self.select();
+        self.dc.set_low();
+        self.write_words(core::slice::from_ref(&command));
+        self.dc.set_high();
+        self.deselect();
+    }
+
+    /// Writes data words (DC high).
+    pub fn write_data<W: BusWord>(&mut self, data: &[W]) {
+        self.select();
+        self.write_words(data);
+        self.deselect();
+    }
+
+    /// Writes a command word followed by its parameter data words.
+    pub fn write_command_data<W: BusWord>(&mut self, command: W, data: &[W]) {
+        self.select();
+        self.dc.set_low();
+        self.write_words(core::slice::from_ref(&command));
+        self.dc.set_high();
+        self.write_words(data);
+        self.deselect();
+    }
+
+    /// Reads data words (DC high) until `buf` is filled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no RD port was given.
+    pub fn read_data<W: BusWord>(&mut self, buf: &mut [W]) {
+        assert!(self.rd.is_some(), "reading requires an RD port");
+        self.select();
+
+        for word in buf {
+            // Display drives the bus while RD is low, so sample it before the rising edge
+            if let Some(rd) = &mut self.rd {
+                rd.set_low();
+            }
+            if self.read_delay_cycles != 0 {
+                riscv::asm::delay(self.read_delay_cycles);
+            }
+            *word = W::from_bits(self.bus.read());
+            if let Some(rd) = &mut self.rd {
+                rd.set_high();
+            }
+        }
+
+        self.deselect();
+    }
+}
+
+// Converts a duration in nanoseconds to CPU cycles, rounding up
+fn ns_to_cycles(ns: u32) -> u32 {
+    let cycles = (ns as u64 * crate::sysinfo::SysInfo::clock_freq() as u64).div_ceil(1_000_000_000);
+    cycles.min(u32::MAX as u64) as u32
+}
+
+/// A signal level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Level {
+    /// The signal is low.
+    Low,
+    /// The signal is high.
+    High,
+}
+
+/// One measured period of a signal, in CPU cycles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PulseMeasurement {
+    /// CPU cycles the signal was high.
+    pub high_cycles: u64,
+    /// CPU cycles the signal was low.
+    pub low_cycles: u64,
+}
+
+impl PulseMeasurement {
+    /// Returns the period in CPU cycles.
+    pub fn period_cycles(&self) -> u64 {
+        self.high_cycles + self.low_cycles
+    }
+
+    /// Returns the frequency in Hz, rounded to the nearest Hz.
+    pub fn frequency_hz(&self) -> u32 {
+        let millihertz = self.frequency_millihertz();
+        ((millihertz + 500) / 1000) as u32
+    }
+
+    /// Returns the frequency in millihertz, for better precision at low frequencies.
+    pub fn frequency_millihertz(&self) -> u64 {
+        cycles_to_millihertz(self.period_cycles(), crate::sysinfo::SysInfo::clock_freq())
+    }
+
+    /// Returns the fraction of the period the signal was high, in permille (0 to 1000).
+    pub fn duty_cycle_permille(&self) -> u16 {
+        match self.period_cycles() {
+            0 => 0,
+            period => ((self.high_cycles * 1000 + period / 2) / period) as u16,
+        }
+    }
+}
+
+/// Converts a number of CPU cycles, such as a pulse width, to nanoseconds.
+pub fn cycles_to_nanos(cycles: u64) -> u64 {
+    cycles_to_nanos_at(cycles, crate::sysinfo::SysInfo::clock_freq())
+}
+
+fn cycles_to_nanos_at(cycles: u64, cpu_freq: u32) -> u64 {
+    ((cycles as u128 * 1_000_000_000 + cpu_freq as u128 / 2) / cpu_freq as u128) as u64
+}
+
+fn cycles_to_millihertz(period_cycles: u64, cpu_freq: u32) -> u64 {
+    match period_cycles {
+        0 => 0,
+        period => (cpu_freq as u64 * 1000 + period / 2) / period,
+    }
+}
+
+/// Input capture of a port's edges, for measuring pulses and frequencies.
+///
+/// Each edge is timestamped with the `mcycle` CPU cycle counter on entry to the GPIO interrupt
+/// handler, so timestamps have a resolution of one CPU clock cycle, i.e. `1 / clock_freq` as
+/// reported by [SysInfo::clock_freq](crate::sysinfo::SysInfo::clock_freq) (10 ns at 100 MHz).
+///
+/// Accuracy is limited by interrupt latency rather than resolution: other interrupts and
+/// critical sections delay the handler by a varying number of cycles, which shows up as jitter
+/// in the measurements. Also, since the handler re-arms the trigger polarity after each edge,
+/// the edge ending a pulse shorter than the interrupt latency can't be recorded. The handler
+/// detects this from the port level, and the measurements then return [Error::EventsLost]
+/// rather than a result built from an incomplete set of edges.
+///
+/// Measurements wait for the signal indefinitely, so use a timeout (such as
+/// `embassy_time::with_timeout`) when the signal may stop, like a stalled fan's tachometer.
+///
+/// **Note**: Requires the `Zicntr` ISA extension for `mcycle`.
+pub struct PulseCapture<'d> {
+    stream: EventStream<'d>,
+}
+
+impl<'d> PulseCapture<'d> {
+    /// Wait for the next recorded edge.
+    ///
+    /// This allows decoding arbitrary signals, such as IR remote frames, from the edge timestamps.
+    pub async fn next_edge(&mut self) -> Event {
+        self.stream.next().await
+    }
+
+    /// Returns the next recorded edge, or `None` if none is queued.
+    pub fn try_next_edge(&mut self) -> Option<Event> {
+        self.stream.try_next()
+    }
+
+    /// Returns true if edges were dropped because the channel was full, or missed because a pulse
+    /// was too short to be recorded, and clears it.
+    pub fn take_lost(&mut self) -> bool {
+        self.stream.take_lost() != 0
+    }
+
+    /// Discards all recorded edges so that following edges are known to be recent.
+    pub fn clear(&mut self) {
+        while self.stream.try_next().is_some() {}
+        self.stream.take_lost();
+    }
+
+    async fn next_edge_checked(&mut self) -> Result<Event, Error> {
+        let event = self.stream.next().await;
+        if self.take_lost() {
+            Err(Error::EventsLost)
+        } else {
+            Ok(event)
+        }
+    }
+
+    /// Measures one full period of the signal, starting from the next rising edge.
+    ///
+    /// Edges recorded before this is called are discarded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EventsLost`] if edges were dropped or missed during the measurement.
+    pub async fn measure(&mut self) -> Result<PulseMeasurement, Error> {
+        self.clear();
+
+        let mut rise = None;
+        let mut fall = None;
+        loop {
+            let event = self.next_edge_checked().await?;
+            match (event.edge, rise, fall) {
+                (Edge::Rising, Some(rise), Some(fall)) => {
+                    return Ok(PulseMeasurement {
+                        high_cycles: u64::wrapping_sub(fall, rise),
+                        low_cycles: event.timestamp.wrapping_sub(fall),
+                    });
+                }
+                (Edge::Rising, _, _) => {
+                    rise = Some(event.timestamp);
+                    fall = None;
+                }
+                (Edge::Falling, Some(_), None) => fall = Some(event.timestamp),
+                // Falling edge before any rising edge, start over
+                (Edge::Falling, _, _) => rise = None,
+            }
+        }
+    }
+
+    /// Measures the signal's frequency in Hz over one period.
+    ///
+    /// For better precision at low frequencies, see [PulseMeasurement::frequency_millihertz].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EventsLost`] if edges were dropped or missed during the measurement.
+    pub async fn measure_frequency(&mut self) -> Result<u32, Error> {
+        Ok(self.measure().await?.frequency_hz())
+    }
+
+    /// Measures the signal's duty cycle over one period, in permille (0 to 1000).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EventsLost`] if edges were dropped or missed during the measurement.
+    pub async fn measure_duty_cycle(&mut self) -> Result<u16, Error> {
+        Ok(self.measure().await?.duty_cycle_permille())
+    }
+
+    /// Measures the width of the next pulse at `level`, in CPU cycles.
+    ///
+    /// Use [cycles_to_nanos] to convert the result to nanoseconds.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EventsLost`] if edges were dropped or missed during the measurement.
+    pub async fn measure_pulse_width(&mut self, level: Level) -> Result<u64, Error> {
+        let start_edge = match level {
+            Level::High => Edge::Rising,
+            Level::Low => Edge::Falling,
+        };
+
+        self.clear();
+        let mut start = None;
+        loop {
+            let event = self.next_edge_checked().await?;
+            if event.edge == start_edge {
+                start = Some(event.timestamp);
+            } else if let Some(start) = start {
+                return Ok(event.timestamp.wrapping_sub(start));
+            }
+        }
+    }
+}
+
+/// Quadrature decoding resolution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum QeiMode {
+    /// Count once per full quadrature cycle (every fourth transition).
+    X1,
+    /// Count twice per full quadrature cycle (every other transition).
+    X2,
+    /// Count every transition of either signal.
+    X4,
+}
+
+impl QeiMode {
+    fn divisor(self) -> i32 {
+        match self {
+            Self::X1 => 4,
+            Self::X2 => 2,
+            Self::X4 => 1,
+        }
+    }
+}
+
+// Returns the quarter steps moved from one AB state (A in bit 1, B in bit 0) to another,
+// or None if both signals changed at once and the direction is unknown
+fn qei_step(from: u8, to: u8) -> Option<i32> {
+    // Forward is A leading B: 00 -> 10 -> 11 -> 01 -> 00
+    const FORWARD: [u8; 4] = [0b10, 0b00, 0b11, 0b01];
+    if from == to {
+        Some(0)
+    } else if FORWARD[from as usize] == to {
+        Some(1)
+    } else if FORWARD[to as usize] == from {
+        Some(-1)
+    } else {
+        None
+    }
+}
+
+/// A quadrature encoder decoder.
+///
+/// Transitions are decoded by the GPIO interrupt handler, which reconfigures each port's trigger
+/// polarity after every edge so that every transition of both signals is tracked. The position is
+/// kept as a signed count, which wraps on overflow.
+///
+/// A transition where both signals changed at once, such as when the encoder turns faster than
+/// the interrupt handler can keep up, has no known direction. It is not counted and instead
+/// reported by [Self::take_invalid_transitions].
+pub struct QeiDecoder<'d> {
+    slot: usize,
+    mask: u32,
+    reg: &'static crate::pac::gpio::RegisterBlock,
+    qei: &'static QeiState,
+    last_seen: i32,
+    _phantom: PhantomData<&'d ()>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d> Send for QeiDecoder<'d> {}
+
+impl<'d> QeiDecoder<'d> {
+    fn new(
+        a: Input<'d, Async>,
+        b: Input<'d, Async>,
+        mode: QeiMode,
+        qei: &'static QeiState,
+    ) -> Result<Self, Error> {
+        let reg = a.info.reg;
+        let pin_a = a.info.port_mask.trailing_zeros() as u8;
+        let pin_b = b.info.port_mask.trailing_zeros() as u8;
+        let mask = a.info.port_mask | b.info.port_mask;
+
+        let slot = critical_section::with(|cs| {
+            let mut slots = qei.slots.borrow(cs).borrow_mut();
+            let slot = slots.iter().position(Option::is_none)?;
+
+            let levels = reg.port_in().read().bits();
+            let mut state = QeiSlot {
+                pin_a,
+                pin_b,
+                divisor: mode.divisor(),
+                ab: 0,
+                quarters: 0,
+                invalid: 0,
+            };
+            state.ab = state.levels_to_ab(levels);
+            slots[slot] = Some(state);
+            qei.positions[slot].store(0, Ordering::Relaxed);
+
+            // Trigger on the next change of either signal
+            configure_irq(reg, mask, true, !levels, cs);
+            Some(slot)
+        })
+        .ok_or(Error::NoFreeDecoder)?;
+
+        // The decoder takes over disabling the ports' interrupts when dropped
+        core::mem::forget(a);
+        core::mem::forget(b);
+
+        Ok(Self {
+            slot,
+            mask,
+            reg,
+            qei,
+            last_seen: 0,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the current position.
+    pub fn position(&self) -> i32 {
+        self.qei.positions[self.slot].load(Ordering::Relaxed)
+    }
+
+    /// Sets the current position.
+    pub fn set_position(&mut self, position: i32) {
+        critical_section::with(|cs| {
+            if let Some(slot) = &mut self.qei.slots.borrow(cs).borrow_mut()[self.slot] {
+                slot.quarters = position.wrapping_mul(slot.divisor);
+            }
+            self.qei.positions[self.slot].store(position, Ordering::Relaxed);
+        });
+        self.last_seen = position;
+    }
+
+    /// Returns the number of invalid transitions since last called, and clears it.
+    pub fn take_invalid_transitions(&mut self) -> u32 {
+        critical_section::with(|cs| {
+            self.qei.slots.borrow(cs).borrow_mut()[self.slot]
+                .as_mut()
+                .map_or(0, |slot| core::mem::take(&mut slot.invalid))
+        })
+    }
+
+    /// Wait for the position to change, returning the new position.
+    ///
+    /// Returns immediately if the position changed since it was last returned by this method
+    /// (or set via [Self::set_position]).
+    pub async fn wait_for_change(&mut self) -> i32 {
+        poll_fn(|cx| {
+            self.qei.wakers[self.slot].register(cx.waker());
+
+            let position = self.position();
+            if position != self.last_seen {
+                self.last_seen = position;
+                Poll::Ready(position)
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<'d> Drop for QeiDecoder<'d> {
+    fn drop(&mut self) {
+        critical_section::with(|cs| {
+            // SAFETY: We only clear our bits. This is only called in a critical section so no risk of clobbering others.
+            self.reg
+                .irq_enable()
+                .modify(|r, w| unsafe { w.bits(r.bits() & !self.mask) });
+            self.qei.slots.borrow(cs).borrow_mut()[self.slot] = None;
+        });
+    }
+}
+
+/// [Debouncer] configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DebounceConfig {
+    /// How long the input signal must be stable before a change is accepted.
+    pub settle_time: Duration,
+    /// Whether the input is pressed when its signal is low, such as a button to ground with a pull-up.
+    pub active_low: bool,
+    /// How long the input must be held for a press to be a [Gesture::LongPress].
+    pub long_press_time: Duration,
+    /// Interval between [Gesture::Repeat]s while the input is held after a long press,
+    /// or `None` to not repeat.
+    pub repeat_interval: Option<Duration>,
+    /// Longest time between a release and the next press for them to form a [Gesture::DoubleClick],
+    /// or `None` to not detect double clicks.
+    ///
+    /// While enabled, a [Gesture::ShortPress] is only reported once this time passes without
+    /// another press.
+    pub double_click_time: Option<Duration>,
+}
+
+impl Default for DebounceConfig {
+    fn default() -> Self {
+        Self {
+            settle_time: Duration::from_millis(20),
+            active_low: true,
+            long_press_time: Duration::from_millis(500),
+            repeat_interval: Some(Duration::from_millis(250)),
+            double_click_time: Some(Duration::from_millis(250)),
+        }
+    }
+}
+
+/// A debounced change of a [Debouncer]'s input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ButtonEvent {
+    /// The input was pressed.
+    Pressed,
+    /// The input was released.
+    Released,
+}
+
+/// A press gesture recognized by a [Debouncer].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Gesture {
+    /// The input was pressed and released before the long press time.
+    ShortPress,
+    /// The input has been held for the long press time.
+    LongPress,
+    /// The input is still held after a long press, reported every repeat interval.
+    Repeat,
+    /// The input was pressed again shortly after a short press.
+    DoubleClick,
+}
+
+#[derive(Clone, Copy)]
+enum GestureState {
+    Idle,
+    // Held after a long press, with the time of the next repeat
+    Repeating(Instant),
+    // Held after a gesture which doesn't repeat
+    AwaitingRelease,
+}
+
+/// A debounced button input.
+///
+/// Edges are detected with the input's interrupt and the settle time is measured with
+/// `embassy-time`, so no polling is involved and each debouncer adds no interrupt load beyond
+/// the edges themselves. Multiple debouncers can therefore be run from separate tasks, and a
+/// debouncer can be moved into (or shared through an async mutex with) whichever task handles it.
+///
+/// [Self::next_event] and [Self::next_gesture] share the debounced state, so typically only one
+/// of them is used for a given input.
+pub struct Debouncer<'d> {
+    input: Input<'d, Async>,
+    config: DebounceConfig,
+    pressed: bool,
+    gesture: GestureState,
+}
+
+impl<'d> Debouncer<'d> {
+    /// Creates a new debouncer for `input` with given config.
+    pub fn new(input: Input<'d, Async>, config: DebounceConfig) -> Self {
+        let mut debouncer = Self {
+            input,
+            config,
+            pressed: false,
+            gesture: GestureState::Idle,
+        };
+        debouncer.pressed = debouncer.raw_pressed();
+        debouncer
+    }
+
+    /// Releases the input.
+    pub fn release(self) -> Input<'d, Async> {
+        self.input
+    }
+
+    /// Returns the current config.
+    pub fn config(&self) -> DebounceConfig {
+        self.config
+    }
+
+    /// Replaces the config.
+    pub fn set_config(&mut self, config: DebounceConfig) {
+        self.config = config;
+    }
+
+    /// Returns true if the input is pressed, according to the last debounced change.
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    fn raw_pressed(&self) -> bool {
+        self.input.is_low() == self.config.active_low
+    }
+
+    async fn wait_for_raw(&mut self, pressed: bool) {
+        if pressed == self.config.active_low {
+            self.input.wait_for_low().await
+        } else {
+            self.input.wait_for_high().await
+        }
+    }
+
+    // Waits until the input signal has not changed for the settle time
+    async fn settle(&mut self) {
+        let settle_time = self.config.settle_time;
+        while let Either::Second(()) =
+            select(Timer::after(settle_time), self.input.wait_for_any_edge()).await
+        {}
+    }
+
+    /// Waits for the input to be pressed or released.
+    ///
+    /// A change is only reported once the input signal has been stable for the settle time,
+    /// so contact bounce never produces an event.
+    pub async fn next_event(&mut self) -> ButtonEvent {
+        loop {
+            self.wait_for_raw(!self.pressed).await;
+            self.settle().await;
+
+            if self.raw_pressed() != self.pressed {
+                self.pressed = !self.pressed;
+                return if self.pressed {
+                    ButtonEvent::Pressed
+                } else {
+                    ButtonEvent::Released
+                };
+            }
+        }
+    }
+
+    /// Waits for the input to be pressed, returning immediately if it already is.
+    pub async fn wait_for_press(&mut self) {
+        while !self.pressed {
+            self.next_event().await;
+        }
+    }
+
+    /// Waits for the input to be released, returning immediately if it already is.
+    pub async fn wait_for_release(&mut self) {
+        while self.pressed {
+            self.next_event().await;
+        }
+    }
+
+    /// Waits for the next press gesture.
+    ///
+    /// A press held for the long press time is reported as [Gesture::LongPress] while it is still
+    /// held, followed by a [Gesture::Repeat] every repeat interval until released. A shorter press
+    /// is reported as [Gesture::ShortPress] once released, unless the input is pressed again within
+    /// the double click time, which is reported as [Gesture::DoubleClick] on the second press.
+    pub async fn next_gesture(&mut self) -> Gesture {
+        loop {
+            match self.gesture {
+                GestureState::Idle => {}
+                GestureState::Repeating(at) => {
+                    match select(Timer::at(at), self.wait_for_release()).await {
+                        Either::First(()) => {
+                            self.gesture = match self.config.repeat_interval {
+                                Some(interval) => GestureState::Repeating(at + interval),
+                                None => GestureState::AwaitingRelease,
+                            };
+                            return Gesture::Repeat;
+                        }
+                        Either::Second(()) => self.gesture = GestureState::Idle,
+                    }
+                    continue;
+                }
+                GestureState::AwaitingRelease => {
+                    self.wait_for_release().await;
+                    self.gesture = GestureState::Idle;
+                }
+            }
+
+            // Only an input released at the start of a gesture can start a new one
+            self.wait_for_release().await;
+            self.wait_for_press().await;
+
+            let long_press_time = self.config.long_press_time;
+            if with_timeout(long_press_time, self.wait_for_release())
+                .await
+                .is_err()
+            {
+                self.gesture = match self.config.repeat_interval {
+                    Some(interval) => GestureState::Repeating(Instant::now() + interval),
+                    None => GestureState::AwaitingRelease,
+                };
+                return Gesture::LongPress;
+            }
+
+            let Some(double_click_time) = self.config.double_click_time else {
+                return Gesture::ShortPress;
+            };
+            if with_timeout(double_click_time, self.wait_for_press())
+                .await
+                .is_ok()
+            {
+                self.gesture = GestureState::AwaitingRelease;
+                return Gesture::DoubleClick;
+            }
+
+            return Gesture::ShortPress;
+        }
+    }
+}
+
 trait SealedIoMode {}
 
 /// GPIO IO mode.
@@ -466,9 +1770,249 @@ pub struct Async;
 impl SealedIoMode for Async {}
 impl IoMode for Async {}
 
+// Ports in event mode and where the interrupt handler queues their events
+struct EventInner {
+    mask: u32,
+    any_edge: u32,
+    lost: u32,
+    senders: [Option<SendDynamicSender<'static, Event>>; MAX_PORTS],
+}
+
+struct EventState {
+    inner: Mutex<CriticalSectionRawMutex, RefCell<EventInner>>,
+}
+
+impl EventState {
+    const fn new() -> Self {
+        Self {
+            inner: Mutex::new(RefCell::new(EventInner {
+                mask: 0,
+                any_edge: 0,
+                lost: 0,
+                senders: [const { None }; MAX_PORTS],
+            })),
+        }
+    }
+
+    fn register(
+        &self,
+        mask: u32,
+        any_edge: bool,
+        sender: SendDynamicSender<'static, Event>,
+        cs: CriticalSection,
+    ) {
+        let mut inner = self.inner.borrow(cs).borrow_mut();
+        inner.mask |= mask;
+        inner.lost &= !mask;
+        if any_edge {
+            inner.any_edge |= mask;
+        } else {
+            inner.any_edge &= !mask;
+        }
+        for (i, slot) in inner.senders.iter_mut().enumerate() {
+            if (mask & (1 << i)) != 0 {
+                *slot = Some(sender);
+            }
+        }
+    }
+
+    fn unregister(&self, mask: u32, cs: CriticalSection) {
+        let mut inner = self.inner.borrow(cs).borrow_mut();
+        inner.mask &= !mask;
+        inner.any_edge &= !mask;
+        for (i, slot) in inner.senders.iter_mut().enumerate() {
+            if (mask & (1 << i)) != 0 {
+                *slot = None;
+            }
+        }
+    }
+
+    fn take_lost(&self, mask: u32, cs: CriticalSection) -> u32 {
+        let mut inner = self.inner.borrow(cs).borrow_mut();
+        let lost = inner.lost & mask;
+        inner.lost &= !mask;
+        lost
+    }
+
+    // Queues an event for every pending port in event mode and clears their pending bits,
+    // returning the mask of those ports
+    fn queue(&self, reg: &crate::pac::gpio::RegisterBlock, pending: u32, timestamp: u64) -> u32 {
+        self.inner.lock(|inner| {
+            let mut inner = inner.borrow_mut();
+            let queued = pending & inner.mask;
+            if queued == 0 {
+                return 0;
+            }
+
+            // Clear before re-arming below, so an edge arriving meanwhile isn't wiped out
+            // SAFETY: Register is write 0 to clear, so only the queued ports are cleared
+            reg.irq_pending().write(|w| unsafe { w.bits(!queued) });
+
+            // The edge that fired is the one the trigger polarity was set to
+            let polarity = reg.irq_polarity().read().bits();
+            for i in 0..MAX_PORTS {
+                let port_bit = 1 << i;
+                if (queued & port_bit) == 0 {
+                    continue;
+                }
+
+                let edge = if (polarity & port_bit) != 0 {
+                    Edge::Rising
+                } else {
+                    Edge::Falling
+                };
+                let event = Event {
+                    port: i as u8,
+                    edge,
+                    timestamp,
+                };
+
+                let sent = inner.senders[i].is_some_and(|sender| sender.try_send(event).is_ok());
+                if !sent {
+                    inner.lost |= port_bit;
+                }
+            }
+
+            let any_edge = queued & inner.any_edge;
+            if any_edge != 0 {
+                inner.lost |= rearm_any_edge(reg, any_edge, polarity);
+            }
+
+            queued
+        })
+    }
+}
+
+// Arms ports queueing both edges for the opposite of their current level, returning the mask of
+// ports which missed an edge
+fn rearm_any_edge(reg: &crate::pac::gpio::RegisterBlock, mask: u32, polarity: u32) -> u32 {
+    // Each port should now be at the level of the edge that fired (high after a rising edge),
+    // otherwise the pulse already ended and its second edge was missed
+    let mut levels = reg.port_in().read().bits();
+    let mut missed = (levels ^ polarity) & mask;
+    let mut rearm = mask;
+
+    loop {
+        // SAFETY: We only modify the bits of ports in event mode, which no one else modifies
+        reg.irq_polarity()
+            .modify(|r, w| unsafe { w.bits((r.bits() & !rearm) | (!levels & rearm)) });
+
+        // A port changing before it was re-armed didn't raise an interrupt, so its edge was
+        // missed. Ports which did raise one are left armed so the next interrupt labels it right
+        let now = reg.port_in().read().bits();
+        rearm = (now ^ levels) & rearm & !reg.irq_pending().read().bits();
+        if rearm == 0 {
+            return missed;
+        }
+
+        missed |= rearm;
+        levels = now;
+    }
+}
+
+// Decoding state of an active quadrature decoder
+#[derive(Clone, Copy)]
+struct QeiSlot {
+    pin_a: u8,
+    pin_b: u8,
+    divisor: i32,
+    ab: u8,
+    quarters: i32,
+    invalid: u32,
+}
+
+impl QeiSlot {
+    fn mask(&self) -> u32 {
+        (1 << self.pin_a) | (1 << self.pin_b)
+    }
+
+    fn levels_to_ab(&self, levels: u32) -> u8 {
+        let a = (levels >> self.pin_a) & 1;
+        let b = (levels >> self.pin_b) & 1;
+        ((a << 1) | b) as u8
+    }
+
+    // Records a transition to the given AB state
+    fn step(&mut self, ab: u8) {
+        match qei_step(self.ab, ab) {
+            Some(step) => self.quarters = self.quarters.wrapping_add(step),
+            None => self.invalid = self.invalid.saturating_add(1),
+        }
+        self.ab = ab;
+    }
+
+    fn position(&self) -> i32 {
+        self.quarters.div_euclid(self.divisor)
+    }
+}
+
+struct QeiState {
+    slots: Mutex<CriticalSectionRawMutex, RefCell<[Option<QeiSlot>; MAX_QEI_DECODERS]>>,
+    positions: [AtomicI32; MAX_QEI_DECODERS],
+    wakers: [AtomicWaker; MAX_QEI_DECODERS],
+}
+
+impl QeiState {
+    const fn new() -> Self {
+        Self {
+            slots: Mutex::new(RefCell::new([None; MAX_QEI_DECODERS])),
+            positions: [const { AtomicI32::new(0) }; MAX_QEI_DECODERS],
+            wakers: [const { AtomicWaker::new() }; MAX_QEI_DECODERS],
+        }
+    }
+
+    // Decodes every decoder with a pending port and clears their pending bits, returning the mask
+    // of those ports
+    fn decode(&self, reg: &crate::pac::gpio::RegisterBlock, pending: u32) -> u32 {
+        self.slots.lock(|slots| {
+            let mut slots = slots.borrow_mut();
+            let mut decoded = 0;
+
+            for (i, slot) in slots.iter_mut().enumerate() {
+                let Some(slot) = slot else { continue };
+                let mask = slot.mask();
+                if (pending & mask) == 0 {
+                    continue;
+                }
+                decoded |= pending & mask;
+
+                loop {
+                    // Clear before sampling the levels, so an edge arriving from here on stays
+                    // pending (or is caught by the re-read below) rather than being wiped out
+                    // SAFETY: Register is write 0 to clear, so only the decoder's ports are cleared
+                    reg.irq_pending().write(|w| unsafe { w.bits(!mask) });
+
+                    let levels = reg.port_in().read().bits();
+                    slot.step(slot.levels_to_ab(levels));
+
+                    // Trigger on the next change of either signal
+                    // SAFETY: We only modify the bits of the decoder's ports, which no one else modifies
+                    reg.irq_polarity()
+                        .modify(|r, w| unsafe { w.bits((r.bits() & !mask) | (!levels & mask)) });
+
+                    // If a signal changed in the meantime its edge may have been missed, so decode again
+                    if (reg.port_in().read().bits() & mask) == (levels & mask) {
+                        break;
+                    }
+                }
+
+                let position = slot.position();
+                if self.positions[i].load(Ordering::Relaxed) != position {
+                    self.positions[i].store(position, Ordering::Relaxed);
+                    self.wakers[i].wake();
+                }
+            }
+
+            decoded
+        })
+    }
+}
+
 struct Info {
     reg: &'static crate::pac::gpio::RegisterBlock,
     wakers: &'static [AtomicWaker; MAX_PORTS],
+    events: &'static EventState,
+    qei: &'static QeiState,
 }
 
 struct InputInfo {
@@ -495,11 +2039,15 @@ pub trait Instance: SealedInstance + PeripheralType {
 impl SealedInstance for GPIO {
     fn info() -> Info {
         static WAKERS: [AtomicWaker; MAX_PORTS] = [const { AtomicWaker::new() }; MAX_PORTS];
+        static EVENTS: EventState = EventState::new();
+        static QEI: QeiState = QeiState::new();
 
         Info {
             // SAFETY: We have exclusive access to the GPIO register block
             reg: unsafe { &*crate::pac::Gpio::ptr() },
             wakers: &WAKERS,
+            events: &EVENTS,
+            qei: &QEI,
         }
     }
 }
@@ -776,3 +2324,113 @@ impl<'d> embedded_hal_1::digital::StatefulOutputPin for Output<'d> {
         Ok((*self).is_set_low())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pulse_measurement_results() {
+        let m = PulseMeasurement {
+            high_cycles: 250,
+            low_cycles: 750,
+        };
+        assert_eq!(m.period_cycles(), 1000);
+        assert_eq!(m.duty_cycle_permille(), 250);
+        assert_eq!(
+            cycles_to_millihertz(m.period_cycles(), 100_000_000),
+            100_000_000
+        );
+
+        let idle = PulseMeasurement {
+            high_cycles: 0,
+            low_cycles: 0,
+        };
+        assert_eq!(idle.duty_cycle_permille(), 0);
+        assert_eq!(cycles_to_millihertz(idle.period_cycles(), 100_000_000), 0);
+    }
+
+    #[test]
+    fn cycle_conversions_round() {
+        // A 30 Hz tachometer at 50 MHz
+        assert_eq!(cycles_to_millihertz(1_666_667, 50_000_000), 30_000);
+        // Not an integer number of Hz
+        assert_eq!(cycles_to_millihertz(40_000_000, 100_000_000), 2_500);
+        // NEC IR leading pulse of 9 ms at 100 MHz
+        assert_eq!(cycles_to_nanos_at(900_000, 100_000_000), 9_000_000);
+        assert_eq!(cycles_to_nanos_at(1, 150_000_000), 7);
+    }
+
+    #[test]
+    fn qei_steps() {
+        let forward = [0b00, 0b10, 0b11, 0b01];
+        for i in 0..4 {
+            let (from, to) = (forward[i], forward[(i + 1) % 4]);
+            assert_eq!(qei_step(from, to), Some(1));
+            assert_eq!(qei_step(to, from), Some(-1));
+            assert_eq!(qei_step(from, from), Some(0));
+        }
+
+        // Both signals changing at once has no known direction
+        assert_eq!(qei_step(0b00, 0b11), None);
+        assert_eq!(qei_step(0b11, 0b00), None);
+        assert_eq!(qei_step(0b01, 0b10), None);
+        assert_eq!(qei_step(0b10, 0b01), None);
+    }
+
+    #[test]
+    fn qei_slot_tracks_transitions() {
+        // Forward five quarters, back two, one invalid jump, then back across zero
+        let states = [
+            0b10, 0b11, 0b01, 0b00, 0b10, 0b00, 0b01, 0b10, 0b00, 0b01, 0b11, 0b10, 0b00,
+        ];
+        let x1 = [0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, -1, -1];
+        let x2 = [0, 1, 1, 2, 2, 2, 1, 1, 1, 0, 0, -1, -1];
+        let x4 = [1, 2, 3, 4, 5, 4, 3, 3, 2, 1, 0, -1, -2];
+
+        for (mode, expected) in [(QeiMode::X1, x1), (QeiMode::X2, x2), (QeiMode::X4, x4)] {
+            let mut slot = QeiSlot {
+                pin_a: 0,
+                pin_b: 1,
+                divisor: mode.divisor(),
+                ab: 0b00,
+                quarters: 0,
+                invalid: 0,
+            };
+
+            let positions = states.map(|ab| {
+                slot.step(ab);
+                slot.position()
+            });
+            assert_eq!(positions, expected, "{mode:?}");
+            assert_eq!(slot.invalid, 1);
+        }
+    }
+
+    #[test]
+    fn scatter_gather_roundtrip() {
+        let pins = [3, 0, 17, 31, 8];
+        for value in 0..(1 << pins.len()) {
+            let bits = scatter(&pins, value);
+            assert_eq!(bits.count_ones(), value.count_ones());
+            assert_eq!(gather(&pins, bits), value);
+        }
+    }
+
+    #[test]
+    fn scatter_maps_bits_to_pins() {
+        let pins = [5, 1, 30];
+        assert_eq!(scatter(&pins, 0b001), 1 << 5);
+        assert_eq!(scatter(&pins, 0b010), 1 << 1);
+        assert_eq!(scatter(&pins, 0b100), 1 << 30);
+        // Bits beyond the bus width are ignored
+        assert_eq!(scatter(&pins, 0xffff_fff8), 0);
+    }
+
+    #[test]
+    fn gather_ignores_other_ports() {
+        let pins = [5, 1, 30];
+        assert_eq!(gather(&pins, !((1 << 5) | (1 << 1) | (1 << 30))), 0);
+        assert_eq!(gather(&pins, u32::MAX), 0b111);
+    }
+}
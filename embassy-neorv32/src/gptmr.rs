@@ -0,0 +1,517 @@
+//! General Purpose Timer (GPTMR)
+use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
+use crate::peripherals::GPTMR;
+pub use crate::pwm::ClkPrsc;
+use crate::sysinfo::SysInfo;
+use core::cell::Cell;
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::Poll;
+use critical_section::CriticalSection;
+use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::waitqueue::AtomicWaker;
+
+// Max number of timer slices available
+const MAX_SLICES: usize = 16;
+
+/// GPTMR interrupt handler binding.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        let info = T::info();
+        let pending = info.reg.csr1().read().irq().bits();
+
+        // Slices in one-shot mode are stopped so they don't fire again on counter wrap
+        let oneshot = pending & !info.reg.csr0().read().mode().bits();
+        if oneshot != 0 {
+            // SAFETY: We only clear the enable bits of slices that just fired in one-shot mode
+            info.reg
+                .csr0()
+                .modify(|r, w| unsafe { w.enable().bits(r.enable().bits() & !oneshot) });
+        }
+
+        // Clear pending
+        // SAFETY: Register is write 0 to clear, so we bitwise not `pending` to clear only those,
+        // assuring if a slice becomes pending in the meantime we don't clobber it
+        info.reg
+            .csr1()
+            .modify(|_, w| unsafe { w.irq().bits(!pending) });
+
+        // Run hooks, then mark and wake every slice that fired
+        let hooks = info.hooks.lock(|hooks| hooks.get());
+        for (i, hook) in hooks.iter().enumerate() {
+            if (pending & (1 << i)) != 0 {
+                if let Some(hook) = hook {
+                    hook();
+                }
+                info.fired[i].store(true, Ordering::Release);
+                info.wakers[i].wake();
+            }
+        }
+    }
+}
+
+/// GPTMR error.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The NEORV32 configuration does not support GPTMR.
+    NotSupported,
+}
+
+/// Timer slice operation mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Mode {
+    /// The slice fires once when the threshold is reached and then stops.
+    OneShot,
+    /// The slice fires every time the threshold is reached, restarting from zero each time.
+    Continuous,
+}
+
+/// General Purpose Timer (GPTMR) driver.
+///
+/// Must be initialized first with a chosen clock prescaler before initializing individual slices.
+pub struct Gptmr<'d, M: IoMode> {
+    info: Info,
+    _phantom: PhantomData<&'d M>,
+}
+
+impl<'d, M: IoMode> Gptmr<'d, M> {
+    fn new_inner<T: Instance>(_instance: Peri<'d, T>, clkprsc: ClkPrsc) -> Result<Self, Error> {
+        if !SysInfo::soc_config().has_gptmr() {
+            return Err(Error::NotSupported);
+        }
+
+        // Stop all slices and clear any stale pending interrupts before applying the prescaler
+        // SAFETY: Disabling every slice and clearing every pending bit is always valid
+        T::info().reg.csr0().write(|w| unsafe { w.bits(0) });
+        T::info()
+            .reg
+            .csr1()
+            .write(|w| unsafe { w.prsc().bits(clkprsc.bits() as u8) });
+
+        Ok(Self {
+            info: T::info(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Create a new instance of a timer slice driver.
+    ///
+    /// The slice is initially stopped.
+    ///
+    /// **Note**: The number of implemented slices is configurable (up to 16), so ensure the
+    /// given slice is actually implemented by your NEORV32 configuration.
+    pub fn new_slice<T: SliceInstance>(&self, _instance: Peri<'d, T>) -> GptmrSlice<'d, M> {
+        GptmrSlice::new(T::SLICE, &self.info)
+    }
+
+    /// Returns the frequency (in Hz) at which every slice counter is incremented.
+    pub fn tick_freq(&self) -> u32 {
+        tick_freq(self.info.reg)
+    }
+}
+
+impl<'d> Gptmr<'d, Blocking> {
+    /// Create a new instance of a blocking GPTMR driver.
+    ///
+    /// The given clock prescaler will be applied to all slices and determines the timer resolution.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if GPTMR is not supported.
+    pub fn new_blocking<T: Instance>(
+        _instance: Peri<'d, T>,
+        clkprsc: ClkPrsc,
+    ) -> Result<Self, Error> {
+        Self::new_inner(_instance, clkprsc)
+    }
+}
+
+impl<'d> Gptmr<'d, Async> {
+    /// Create a new instance of an async GPTMR driver.
+    ///
+    /// The given clock prescaler will be applied to all slices and determines the timer resolution.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if GPTMR is not supported.
+    pub fn new_async<T: Instance>(
+        _instance: Peri<'d, T>,
+        clkprsc: ClkPrsc,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        let gptmr = Self::new_inner(_instance, clkprsc)?;
+        // SAFETY: It is valid to enable GPTMR interrupt here
+        unsafe { T::Interrupt::enable() }
+        Ok(gptmr)
+    }
+}
+
+/// GPTMR slice driver.
+///
+/// Each slice has its own 32-bit counter and threshold, and raises an interrupt when the
+/// counter reaches the threshold.
+///
+/// **Note**: The slice will be stopped when dropped.
+pub struct GptmrSlice<'d, M: IoMode> {
+    reg: &'static crate::pac::gptmr::RegisterBlock,
+    slice: usize,
+    waker: &'static AtomicWaker,
+    fired: &'static AtomicBool,
+    hooks: &'static Hooks,
+    _phantom: PhantomData<&'d M>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d, M: IoMode> Send for GptmrSlice<'d, M> {}
+
+impl<'d, M: IoMode> GptmrSlice<'d, M> {
+    fn new(slice: usize, info: &Info) -> Self {
+        let mut gptmr_slice = Self {
+            reg: info.reg,
+            slice,
+            waker: &info.wakers[slice],
+            fired: &info.fired[slice],
+            hooks: info.hooks,
+            _phantom: PhantomData,
+        };
+
+        gptmr_slice.stop();
+        gptmr_slice
+    }
+
+    fn slice_mask(&self) -> u16 {
+        1 << self.slice
+    }
+
+    fn enable(&mut self, _cs: CriticalSection) {
+        // SAFETY: Bit mask preserves other slice values
+        self.reg
+            .csr0()
+            .modify(|r, w| unsafe { w.enable().bits(r.enable().bits() | self.slice_mask()) });
+    }
+
+    fn disable(&mut self, _cs: CriticalSection) {
+        // SAFETY: Bit mask preserves other slice values
+        self.reg
+            .csr0()
+            .modify(|r, w| unsafe { w.enable().bits(r.enable().bits() & !self.slice_mask()) });
+    }
+
+    fn set_mode(&mut self, _cs: CriticalSection, mode: Mode) {
+        // SAFETY: Bit mask preserves other slice values
+        self.reg.csr0().modify(|r, w| unsafe {
+            w.mode().bits(match mode {
+                Mode::OneShot => r.mode().bits() & !self.slice_mask(),
+                Mode::Continuous => r.mode().bits() | self.slice_mask(),
+            })
+        });
+    }
+
+    fn clear_pending(&mut self, _cs: CriticalSection) {
+        // SAFETY: Register is write 0 to clear, so only our bit is cleared
+        self.reg
+            .csr1()
+            .modify(|_, w| unsafe { w.irq().bits(!self.slice_mask()) });
+    }
+
+    fn pending(&self) -> bool {
+        (self.reg.csr1().read().irq().bits() & self.slice_mask()) != 0
+    }
+
+    // Returns true (and resets the flag) if the interrupt handler observed a threshold match
+    fn take_fired(&self) -> bool {
+        critical_section::with(|_| {
+            let fired = self.fired.load(Ordering::Acquire);
+            self.fired.store(false, Ordering::Release);
+            fired
+        })
+    }
+
+    // Sets a function the interrupt handler calls each time the slice fires, before waking it.
+    //
+    // This lets other drivers react with minimal latency, without waiting for a task to be polled.
+    pub(crate) fn set_hook(&mut self, hook: Option<fn()>) {
+        self.hooks.lock(|hooks| {
+            let mut all = hooks.get();
+            all[self.slice] = hook;
+            hooks.set(all);
+        });
+    }
+
+    /// Start the slice in the given mode, firing after `ticks` timer ticks.
+    ///
+    /// In [`Mode::Continuous`], the slice keeps firing every `ticks` timer ticks until stopped.
+    ///
+    /// The duration of a tick is determined by the prescaler (see [`Self::tick_freq`]).
+    /// A `ticks` value of `0` is treated as `1`.
+    pub fn start(&mut self, mode: Mode, ticks: u32) {
+        let threshold = ticks.saturating_sub(1);
+
+        critical_section::with(|cs| {
+            // Stop the slice while it is being reconfigured
+            self.disable(cs);
+            self.clear_pending(cs);
+            self.fired.store(false, Ordering::Release);
+
+            // SAFETY: Any u32 is a valid threshold
+            self.reg
+                .slice(self.slice)
+                .thr()
+                .write(|w| unsafe { w.bits(threshold) });
+            // SAFETY: Any u32 is a valid counter value
+            self.reg
+                .slice(self.slice)
+                .cnt()
+                .write(|w| unsafe { w.bits(0) });
+
+            self.set_mode(cs, mode);
+            self.enable(cs);
+        });
+    }
+
+    /// Start the slice in one-shot mode, firing once after `ticks` timer ticks.
+    pub fn start_oneshot(&mut self, ticks: u32) {
+        self.start(Mode::OneShot, ticks);
+    }
+
+    /// Start the slice in continuous mode, firing every `ticks` timer ticks.
+    pub fn start_periodic(&mut self, ticks: u32) {
+        self.start(Mode::Continuous, ticks);
+    }
+
+    /// Stop the slice.
+    ///
+    /// Any pending threshold match is discarded.
+    pub fn stop(&mut self) {
+        critical_section::with(|cs| {
+            self.disable(cs);
+            self.clear_pending(cs);
+        });
+        self.fired.store(false, Ordering::Release);
+    }
+
+    /// Returns true if the slice is currently running.
+    pub fn is_running(&self) -> bool {
+        (self.reg.csr0().read().enable().bits() & self.slice_mask()) != 0
+    }
+
+    /// Returns the current value of the slice counter.
+    pub fn counter(&self) -> u32 {
+        self.reg.slice(self.slice).cnt().read().bits()
+    }
+
+    /// Returns the frequency (in Hz) at which the slice counter is incremented.
+    pub fn tick_freq(&self) -> u32 {
+        tick_freq(self.reg)
+    }
+
+    /// Blocks until the slice counter reaches its threshold.
+    ///
+    /// If the slice is stopped and no match is pending, this will never return.
+    pub fn blocking_wait(&mut self) {
+        loop {
+            // In async mode the interrupt handler may have already cleared the pending bit
+            if self.take_fired() {
+                return;
+            }
+
+            if self.pending() {
+                critical_section::with(|cs| self.clear_pending(cs));
+                // Mirror what the interrupt handler does for one-shot slices
+                if self.reg.csr0().read().mode().bits() & self.slice_mask() == 0 {
+                    critical_section::with(|cs| self.disable(cs));
+                }
+                return;
+            }
+        }
+    }
+
+    /// Blocks for the given number of timer ticks using the slice in one-shot mode.
+    pub fn blocking_delay_ticks(&mut self, ticks: u64) {
+        for chunk in TickChunks::new(ticks) {
+            self.start_oneshot(chunk);
+            self.blocking_wait();
+        }
+    }
+}
+
+impl<'d> GptmrSlice<'d, Async> {
+    /// Waits until the slice counter reaches its threshold.
+    ///
+    /// In [`Mode::Continuous`], this can be called in a loop to wait for each period.
+    ///
+    /// If the slice is stopped and no match is pending, this will never return.
+    pub async fn wait(&mut self) {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            if self.take_fired() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Waits for the given number of timer ticks using the slice in one-shot mode.
+    pub async fn delay_ticks(&mut self, ticks: u64) {
+        for chunk in TickChunks::new(ticks) {
+            self.start_oneshot(chunk);
+            self.wait().await;
+        }
+    }
+}
+
+impl<'d, M: IoMode> Drop for GptmrSlice<'d, M> {
+    fn drop(&mut self) {
+        self.stop();
+        self.set_hook(None);
+    }
+}
+
+// Splits a 64-bit tick count into chunks that fit in the 32-bit threshold register
+struct TickChunks {
+    remaining: u64,
+}
+
+impl TickChunks {
+    fn new(ticks: u64) -> Self {
+        Self { remaining: ticks }
+    }
+}
+
+impl Iterator for TickChunks {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            None
+        } else {
+            let chunk = self.remaining.min(u32::MAX as u64);
+            self.remaining -= chunk;
+            Some(chunk as u32)
+        }
+    }
+}
+
+fn tick_freq(reg: &'static crate::pac::gptmr::RegisterBlock) -> u32 {
+    let clkprsc = ClkPrsc::from_bits(reg.csr1().read().prsc().bits() as u32);
+    SysInfo::clock_freq() / u16::from(clkprsc) as u32
+}
+
+// Converts a duration in nanoseconds to timer ticks, rounding up so we never delay too short
+fn ns_to_ticks(ns: u64, tick_freq: u32) -> u64 {
+    (ns * tick_freq as u64).div_ceil(1_000_000_000)
+}
+
+trait SealedIoMode {}
+
+/// GPTMR IO mode.
+#[allow(private_bounds)]
+pub trait IoMode: SealedIoMode {}
+
+/// Blocking GPTMR.
+pub struct Blocking;
+impl SealedIoMode for Blocking {}
+impl IoMode for Blocking {}
+
+/// Async GPTMR.
+pub struct Async;
+impl SealedIoMode for Async {}
+impl IoMode for Async {}
+
+type Hooks = Mutex<CriticalSectionRawMutex, Cell<[Option<fn()>; MAX_SLICES]>>;
+
+struct Info {
+    reg: &'static crate::pac::gptmr::RegisterBlock,
+    wakers: &'static [AtomicWaker; MAX_SLICES],
+    fired: &'static [AtomicBool; MAX_SLICES],
+    hooks: &'static Hooks,
+}
+
+trait SealedInstance {
+    fn info() -> Info;
+}
+
+/// A valid GPTMR peripheral.
+#[allow(private_bounds)]
+pub trait Instance: SealedInstance + PeripheralType {
+    type Interrupt: Interrupt;
+}
+
+impl SealedInstance for GPTMR {
+    fn info() -> Info {
+        static WAKERS: [AtomicWaker; MAX_SLICES] = [const { AtomicWaker::new() }; MAX_SLICES];
+        static FIRED: [AtomicBool; MAX_SLICES] = [const { AtomicBool::new(false) }; MAX_SLICES];
+        static HOOKS: Hooks = Mutex::new(Cell::new([None; MAX_SLICES]));
+
+        Info {
+            // SAFETY: We own the GPTMR peripheral and use it safely
+            reg: unsafe { &*crate::pac::Gptmr::ptr() },
+            wakers: &WAKERS,
+            fired: &FIRED,
+            hooks: &HOOKS,
+        }
+    }
+}
+impl Instance for GPTMR {
+    type Interrupt = crate::interrupt::typelevel::GPTMR;
+}
+
+trait SealedSliceInstance {}
+
+/// A valid GPTMR slice.
+#[allow(private_bounds)]
+pub trait SliceInstance: SealedSliceInstance + PeripheralType {
+    const SLICE: usize;
+}
+
+macro_rules! impl_slice {
+    ($periph:ident, $slice:expr) => {
+        impl SealedSliceInstance for crate::peripherals::$periph {}
+        impl SliceInstance for crate::peripherals::$periph {
+            const SLICE: usize = $slice;
+        }
+    };
+}
+
+impl_slice!(GPTMRSLICE0, 0);
+impl_slice!(GPTMRSLICE1, 1);
+impl_slice!(GPTMRSLICE2, 2);
+impl_slice!(GPTMRSLICE3, 3);
+impl_slice!(GPTMRSLICE4, 4);
+impl_slice!(GPTMRSLICE5, 5);
+impl_slice!(GPTMRSLICE6, 6);
+impl_slice!(GPTMRSLICE7, 7);
+impl_slice!(GPTMRSLICE8, 8);
+impl_slice!(GPTMRSLICE9, 9);
+impl_slice!(GPTMRSLICE10, 10);
+impl_slice!(GPTMRSLICE11, 11);
+impl_slice!(GPTMRSLICE12, 12);
+impl_slice!(GPTMRSLICE13, 13);
+impl_slice!(GPTMRSLICE14, 14);
+impl_slice!(GPTMRSLICE15, 15);
+
+impl<'d, M: IoMode> embedded_hal_1::delay::DelayNs for GptmrSlice<'d, M> {
+    fn delay_ns(&mut self, ns: u32) {
+        let ticks = ns_to_ticks(ns as u64, self.tick_freq());
+        self.blocking_delay_ticks(ticks);
+    }
+}
+
+impl<'d> embedded_hal_async::delay::DelayNs for GptmrSlice<'d, Async> {
+    async fn delay_ns(&mut self, ns: u32) {
+        let ticks = ns_to_ticks(ns as u64, self.tick_freq());
+        self.delay_ticks(ticks).await;
+    }
+}
@@ -1,20 +1,35 @@
 #![doc = include_str!("../README.md")]
 #![no_std]
+pub mod cfs;
+mod clock;
 pub mod dma;
 #[cfg(feature = "dual-hart")]
 pub mod dual_hart;
 pub mod gpio;
+#[cfg(not(feature = "time-driver-gptmr"))]
+pub mod gptmr;
 pub mod interrupts;
+pub mod neoled;
+pub mod onewire;
 pub mod pwm;
+pub mod sdi;
+pub mod slink;
 pub mod spi;
 pub mod sysinfo;
 #[cfg(feature = "time-driver")]
 mod time_driver;
+#[cfg(feature = "time-driver-gptmr")]
+mod time_driver_gptmr;
+pub mod tracer;
 pub mod trng;
+pub mod twd;
 pub mod twi;
 pub mod uart;
 pub mod wdt;
 
+#[cfg(all(feature = "time-driver", feature = "time-driver-gptmr"))]
+compile_error!("Only one of `time-driver` or `time-driver-gptmr` features may be enabled.");
+
 // Peripherals and interrupts supported by the NEORV32 chip
 mod chip {
     #[rustfmt::skip]
@@ -26,6 +41,7 @@ mod chip {
         DMA,
         SPI,
         TWI,
+        TWD,
         GPIO,
         PORT0, PORT1, PORT2, PORT3, PORT4, PORT5, PORT6, PORT7,
         PORT8, PORT9, PORT10, PORT11, PORT12, PORT13, PORT14, PORT15,
@@ -36,9 +52,36 @@ mod chip {
         PWMCHAN8, PWMCHAN9, PWMCHAN10, PWMCHAN11, PWMCHAN12, PWMCHAN13, PWMCHAN14, PWMCHAN15,
         PWMCHAN16, PWMCHAN17, PWMCHAN18, PWMCHAN19, PWMCHAN20, PWMCHAN21, PWMCHAN22, PWMCHAN23,
         PWMCHAN24, PWMCHAN25, PWMCHAN26, PWMCHAN27, PWMCHAN28, PWMCHAN29, PWMCHAN30, PWMCHAN31,
+        NEOLED,
+        ONEWIRE,
+        SDI,
+        SLINK,
+        TRACER,
+        CFS,
+        // GPTMR is reserved for time-keeping when `time-driver-gptmr` is enabled
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMR,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE0,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE1,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE2,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE3,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE4,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE5,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE6,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE7,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE8,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE9,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE10,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE11,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE12,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE13,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE14,
+        #[cfg(not(feature = "time-driver-gptmr"))] GPTMRSLICE15,
     );
     pub mod interrupts {
-        crate::interrupt_mod!(UART0, UART1, TRNG, DMA, GPIO, SPI);
+        crate::interrupt_mod!(
+            UART0, UART1, TRNG, DMA, GPIO, SPI, TWI, GPTMR, NEOLED, ONEWIRE, SDI, TWD, SLINK,
+            TRACER, CFS
+        );
     }
 }
 
@@ -52,6 +95,9 @@ pub use neorv32_pac as pac;
 /// Panics if this has already been called once before or not called from hart 0.
 ///
 /// Panics if `time-driver` feature is enabled but `CLINT` is not supported.
+///
+/// Panics if `time-driver-gptmr` feature is enabled but `GPTMR` is not supported,
+/// or if the `embassy-time` tick rate can't be derived from the CPU clock by a GPTMR prescaler.
 pub fn init() -> Peripherals {
     // Attempt to take first so we panic before doing anything else
     let p = Peripherals::take();
@@ -67,6 +113,9 @@ pub fn init() -> Peripherals {
     #[cfg(feature = "time-driver")]
     time_driver::init();
 
+    #[cfg(feature = "time-driver-gptmr")]
+    time_driver_gptmr::init();
+
     p
 }
 
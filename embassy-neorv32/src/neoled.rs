@@ -0,0 +1,431 @@
+//! Smart LED Interface (NEOLED)
+//!
+//! Drives WS2812/SK6812-compatible smart LED strips ("NeoPixels") in either 24-bit RGB
+//! or 32-bit RGBW mode, selected by the color type the driver is instantiated with.
+use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
+use crate::peripherals::NEOLED;
+use crate::pwm::ClkPrsc;
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::task::Poll;
+use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::waitqueue::AtomicWaker;
+use embassy_time::{Duration, Timer};
+pub use smart_leds_trait::{RGB8, RGBW, White};
+
+// Each timing field of the CTRL register is 5 bits wide
+const U5_MAX: u32 = 0x1f;
+
+// A strobe holds the line low for this many bit periods, longer than it takes to shift out a word
+const STROBE_BITS: u32 = 127;
+
+/// NEOLED interrupt handler binding.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        // We disable the interrupt since it is level triggered and there is no apparent way to acknowledge it
+        T::Interrupt::disable();
+        T::waker().wake();
+    }
+}
+
+/// NEOLED error.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The NEORV32 configuration does not support NEOLED.
+    NotSupported,
+    /// The requested bit timing can not be represented at the current CPU clock frequency.
+    InvalidTiming,
+}
+
+/// Smart LED bit timing (in nanoseconds).
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Timing {
+    /// Total duration of a single data bit.
+    pub t_total_ns: u32,
+    /// High-time of a `0` data bit.
+    pub t_0h_ns: u32,
+    /// High-time of a `1` data bit.
+    pub t_1h_ns: u32,
+}
+
+impl Timing {
+    /// Timing for WS2812 (and compatible) LEDs.
+    pub const WS2812: Self = Self {
+        t_total_ns: 1250,
+        t_0h_ns: 400,
+        t_1h_ns: 800,
+    };
+
+    /// Timing for SK6812 (and compatible) LEDs.
+    pub const SK6812: Self = Self {
+        t_total_ns: 1250,
+        t_0h_ns: 300,
+        t_1h_ns: 600,
+    };
+}
+
+// Register values for the requested timing
+struct RawTiming {
+    clkprsc: ClkPrsc,
+    t_tot: u8,
+    t_0h: u8,
+    t_1h: u8,
+}
+
+impl RawTiming {
+    fn new(cpu_freq: u32, timing: Timing) -> Result<Self, Error> {
+        // Converts nanoseconds to pulse-clock ticks, rounding to nearest
+        fn ns_to_ticks(ns: u32, pulse_freq: u64) -> u32 {
+            ((ns as u64 * pulse_freq + 500_000_000) / 1_000_000_000) as u32
+        }
+
+        // Pick the smallest prescaler (so best resolution) where the total bit time fits in 5 bits
+        let (clkprsc, pulse_freq) = [
+            ClkPrsc::_2,
+            ClkPrsc::_4,
+            ClkPrsc::_8,
+            ClkPrsc::_64,
+            ClkPrsc::_128,
+            ClkPrsc::_1024,
+            ClkPrsc::_2048,
+            ClkPrsc::_4096,
+        ]
+        .into_iter()
+        .map(|clkprsc| (clkprsc, cpu_freq as u64 / u16::from(clkprsc) as u64))
+        .find(|(_, pulse_freq)| ns_to_ticks(timing.t_total_ns, *pulse_freq) <= U5_MAX)
+        .ok_or(Error::InvalidTiming)?;
+
+        let t_tot = ns_to_ticks(timing.t_total_ns, pulse_freq);
+        let t_0h = ns_to_ticks(timing.t_0h_ns, pulse_freq);
+        let t_1h = ns_to_ticks(timing.t_1h_ns, pulse_freq);
+
+        // Both high-times must be distinguishable and fit within the total bit time
+        if t_0h == 0 || t_0h >= t_1h || t_1h >= t_tot {
+            return Err(Error::InvalidTiming);
+        }
+
+        Ok(Self {
+            clkprsc,
+            t_tot: t_tot as u8,
+            t_0h: t_0h as u8,
+            t_1h: t_1h as u8,
+        })
+    }
+}
+
+/// Smart LED Interface (NEOLED) driver.
+///
+/// The color type `C` determines whether LEDs are driven in 24-bit ([`RGB8`]) or 32-bit ([`RGBW`]) mode.
+///
+/// **Note**: Colors are sent in GRB(W) order as expected by WS2812/SK6812 LEDs.
+pub struct Neoled<'d, M: IoMode, C: Color> {
+    reg: &'static crate::pac::neoled::RegisterBlock,
+    waker: &'static AtomicWaker,
+    _phantom: PhantomData<&'d (M, C)>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d, M: IoMode, C: Color> Send for Neoled<'d, M, C> {}
+
+impl<'d, M: IoMode, C: Color> Neoled<'d, M, C> {
+    fn new_inner<T: Instance>(_instance: Peri<'d, T>, timing: Timing) -> Result<Self, Error> {
+        if !crate::sysinfo::SysInfo::soc_config().has_neoled() {
+            return Err(Error::NotSupported);
+        }
+
+        let raw = RawTiming::new(crate::sysinfo::SysInfo::clock_freq(), timing)?;
+
+        // Configure timing and enable NEOLED
+        // SAFETY: We've ensured the prescaler is valid and all timing values fit in 5 bits
+        T::reg().ctrl().write(|w| unsafe {
+            w.neoled_ctrl_prsc()
+                .bits(raw.clkprsc.bits() as u8)
+                .neoled_ctrl_t_tot()
+                .bits(raw.t_tot)
+                .neoled_ctrl_t_0h()
+                .bits(raw.t_0h)
+                .neoled_ctrl_t_1h()
+                .bits(raw.t_1h)
+                .neoled_ctrl_en()
+                .set_bit()
+        });
+
+        Ok(Self {
+            reg: T::reg(),
+            waker: T::waker(),
+            _phantom: PhantomData,
+        })
+    }
+
+    fn tx_full(&self) -> bool {
+        self.reg.ctrl().read().neoled_ctrl_tx_full().bit_is_set()
+    }
+
+    fn tx_empty(&self) -> bool {
+        self.reg.ctrl().read().neoled_ctrl_tx_empty().bit_is_set()
+    }
+
+    fn busy(&self) -> bool {
+        self.reg.ctrl().read().neoled_ctrl_tx_busy().bit_is_set()
+    }
+
+    fn write_color(&mut self, color: C) {
+        let raw = color.to_raw();
+        // SAFETY: Any u32 is valid data, and the register determines how many bits are sent
+        if C::IS_32BIT {
+            self.reg.data32().write(|w| unsafe { w.bits(raw) });
+        } else {
+            self.reg.data24().write(|w| unsafe { w.bits(raw) });
+        }
+    }
+
+    fn strobe(&mut self) {
+        // SAFETY: Any value written sends the STROBE command
+        self.reg.strobe().write(|w| unsafe { w.bits(0) });
+    }
+
+    /// Returns the depth of the TX FIFO.
+    pub fn fifo_depth(&self) -> usize {
+        // Value in register is log2 of fifo depth
+        1 << self.reg.ctrl().read().neoled_ctrl_fifo().bits()
+    }
+
+    /// Writes colors to the LED strip then latches them, blocking until all data has been sent.
+    pub fn blocking_write(&mut self, colors: impl IntoIterator<Item = C>) {
+        for color in colors {
+            while self.tx_full() {}
+            self.write_color(color);
+        }
+
+        // Strobe is queued in the FIFO like data, and holds the line low to latch the colors
+        while self.tx_full() {}
+        self.strobe();
+        self.blocking_flush();
+    }
+
+    /// Blocks until all queued data has been sent.
+    pub fn blocking_flush(&self) {
+        while !self.tx_empty() || self.busy() {}
+    }
+}
+
+impl<'d, C: Color> Neoled<'d, Blocking, C> {
+    /// Returns a new instance of a blocking NEOLED driver with given bit timing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if NEOLED is not supported.
+    ///
+    /// Returns [`Error::InvalidTiming`] if the timing can't be represented at the current CPU clock frequency.
+    pub fn new_blocking<T: Instance>(
+        _instance: Peri<'d, T>,
+        timing: Timing,
+    ) -> Result<Self, Error> {
+        Self::new_inner(_instance, timing)
+    }
+}
+
+impl<'d, C: Color> Neoled<'d, Async, C> {
+    /// Returns a new instance of an async NEOLED driver with given bit timing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if NEOLED is not supported.
+    ///
+    /// Returns [`Error::InvalidTiming`] if the timing can't be represented at the current CPU clock frequency.
+    pub fn new_async<T: Instance>(
+        _instance: Peri<'d, T>,
+        timing: Timing,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_inner(_instance, timing)
+    }
+
+    async fn wait_tx_nfull(&mut self) {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            if !self.tx_full() {
+                Poll::Ready(())
+            } else {
+                // SAFETY: It is valid to enable interrupts here, since it is level triggered
+                // and if the FIFO drains between the above check and here, we won't miss it
+                unsafe { crate::enable_periph_irq!(NEOLED) }
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    async fn wait_tx_empty(&mut self) {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            if self.tx_empty() {
+                Poll::Ready(())
+            } else {
+                // The interrupt fires whenever the FIFO has room rather than only once it is empty,
+                // so we may be woken a few times before the FIFO fully drains
+                //
+                // SAFETY: It is valid to enable interrupts here, since it is level triggered
+                // and if the FIFO drains between the above check and here, we won't miss it
+                unsafe { crate::enable_periph_irq!(NEOLED) }
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Writes colors to the LED strip then latches them.
+    ///
+    /// The TX FIFO is refilled from the NEOLED interrupt whenever it has room,
+    /// so other tasks can run while the strip is being updated.
+    pub async fn write(&mut self, colors: impl IntoIterator<Item = C>) {
+        for color in colors {
+            self.wait_tx_nfull().await;
+            self.write_color(color);
+        }
+
+        // Strobe is queued in the FIFO like data, and holds the line low to latch the colors
+        self.wait_tx_nfull().await;
+        self.strobe();
+        self.flush().await;
+    }
+
+    /// Waits until all queued data has been sent.
+    pub async fn flush(&mut self) {
+        self.wait_tx_empty().await;
+
+        // Once the FIFO is empty, at most the final word or strobe is still being sent.
+        // There is no interrupt for that completing, so wait out its worst-case duration
+        if self.busy() {
+            let bit_time = self.bit_time();
+            Timer::after(bit_time * STROBE_BITS).await;
+            while self.busy() {
+                Timer::after(bit_time).await;
+            }
+        }
+    }
+
+    // Duration of a single data bit, rounded up
+    fn bit_time(&self) -> Duration {
+        let ctrl = self.reg.ctrl().read();
+        let clkprsc = ClkPrsc::from_bits(ctrl.neoled_ctrl_prsc().bits() as u32);
+        let cycles = ctrl.neoled_ctrl_t_tot().bits() as u64 * u16::from(clkprsc) as u64;
+        let cpu_freq = crate::sysinfo::SysInfo::clock_freq() as u64;
+        Duration::from_nanos((cycles * 1_000_000_000).div_ceil(cpu_freq))
+    }
+}
+
+impl<'d, M: IoMode, C: Color> Drop for Neoled<'d, M, C> {
+    fn drop(&mut self) {
+        self.blocking_flush();
+        self.reg
+            .ctrl()
+            .modify(|_, w| w.neoled_ctrl_en().clear_bit());
+    }
+}
+
+trait SealedColor: Copy {
+    const IS_32BIT: bool;
+    fn to_raw(self) -> u32;
+}
+
+/// A color which can be sent to the LED strip.
+///
+/// [`RGB8`] is sent in 24-bit mode and [`RGBW`] (with `u8` components) is sent in 32-bit mode.
+#[allow(private_bounds)]
+pub trait Color: SealedColor {}
+
+impl SealedColor for RGB8 {
+    const IS_32BIT: bool = false;
+
+    fn to_raw(self) -> u32 {
+        ((self.g as u32) << 16) | ((self.r as u32) << 8) | self.b as u32
+    }
+}
+impl Color for RGB8 {}
+
+impl SealedColor for RGBW<u8> {
+    const IS_32BIT: bool = true;
+
+    fn to_raw(self) -> u32 {
+        ((self.g as u32) << 24) | ((self.r as u32) << 16) | ((self.b as u32) << 8) | self.a.0 as u32
+    }
+}
+impl Color for RGBW<u8> {}
+
+trait SealedIoMode {}
+
+/// NEOLED IO mode.
+#[allow(private_bounds)]
+pub trait IoMode: SealedIoMode {}
+
+/// Blocking NEOLED.
+pub struct Blocking;
+impl SealedIoMode for Blocking {}
+impl IoMode for Blocking {}
+
+/// Async NEOLED.
+pub struct Async;
+impl SealedIoMode for Async {}
+impl IoMode for Async {}
+
+trait SealedInstance {
+    fn reg() -> &'static crate::pac::neoled::RegisterBlock;
+    fn waker() -> &'static AtomicWaker;
+}
+
+/// A valid NEOLED peripheral.
+#[allow(private_bounds)]
+pub trait Instance: SealedInstance + PeripheralType {
+    type Interrupt: Interrupt;
+}
+impl SealedInstance for NEOLED {
+    fn reg() -> &'static crate::pac::neoled::RegisterBlock {
+        // SAFETY: We own the NEOLED peripheral and are sure to use it safely
+        unsafe { &*crate::pac::Neoled::ptr() }
+    }
+
+    fn waker() -> &'static AtomicWaker {
+        static WAKER: AtomicWaker = AtomicWaker::new();
+        &WAKER
+    }
+}
+impl Instance for NEOLED {
+    type Interrupt = crate::interrupt::typelevel::NEOLED;
+}
+
+impl<'d, M: IoMode, C: Color> smart_leds_trait::SmartLedsWrite for Neoled<'d, M, C> {
+    type Error = Error;
+    type Color = C;
+
+    fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        self.blocking_write(iterator.into_iter().map(Into::into));
+        Ok(())
+    }
+}
+
+impl<'d, C: Color> smart_leds_trait::SmartLedsWriteAsync for Neoled<'d, Async, C> {
+    type Error = Error;
+    type Color = C;
+
+    async fn write<T, I>(&mut self, iterator: T) -> Result<(), Self::Error>
+    where
+        T: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        (*self).write(iterator.into_iter().map(Into::into)).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,858 @@
+//! 1-Wire Interface (ONEWIRE)
+//!
+//! The controller generates all bus timing from a single base time tick, so the driver is
+//! configured with the duration of that tick. A base time of 10 µs gives standard-speed timing.
+//!
+//! Commands are queued through the hardware FIFO, so multi-byte reads and writes are batched
+//! and only wait for the bus once per FIFO-sized chunk.
+use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
+use crate::peripherals::ONEWIRE;
+use crate::pwm::ClkPrsc;
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::task::Poll;
+use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::waitqueue::AtomicWaker;
+
+// Sent as data when we are only interested in the read value (reads are done by releasing the bus)
+const DUMMY: u8 = 0xFF;
+
+// Standard ROM command codes
+const READ_ROM: u8 = 0x33;
+const MATCH_ROM: u8 = 0x55;
+const SKIP_ROM: u8 = 0xCC;
+const SEARCH_ROM: u8 = 0xF0;
+const ALARM_SEARCH: u8 = 0xEC;
+
+/// ONEWIRE interrupt handler binding.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        // We disable the interrupt since it is level triggered and there is no apparent way to acknowledge it
+        T::Interrupt::disable();
+        T::waker().wake();
+    }
+}
+
+/// ONEWIRE error.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The NEORV32 configuration does not support ONEWIRE.
+    NotSupported,
+    /// The requested base time tick can not be represented at the current CPU clock frequency.
+    InvalidTiming,
+    /// No device responded with a presence pulse after bus reset.
+    NoPresence,
+    /// The CRC of a received ROM ID is invalid.
+    CrcMismatch,
+}
+
+enum Command {
+    _Nop,
+    Bit,
+    Byte,
+    Reset,
+}
+
+impl From<Command> for u8 {
+    fn from(cmd: Command) -> Self {
+        match cmd {
+            Command::_Nop => 0b00,
+            Command::Bit => 0b01,
+            Command::Byte => 0b10,
+            Command::Reset => 0b11,
+        }
+    }
+}
+
+/// Computes the Dallas/Maxim CRC8 (polynomial `x^8 + x^5 + x^4 + 1`) of given data.
+pub fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |mut crc, &byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if (crc & 1) != 0 {
+                (crc >> 1) ^ 0x8C
+            } else {
+                crc >> 1
+            };
+        }
+        crc
+    })
+}
+
+/// A 64-bit 1-Wire device ROM ID.
+///
+/// Consists of an 8-bit family code, a 48-bit serial number and an 8-bit CRC (sent in that order).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RomId([u8; 8]);
+
+impl RomId {
+    /// Creates a ROM ID from its bytes (in bus order).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CrcMismatch`] if the CRC byte does not match the first 7 bytes.
+    pub fn from_bytes(bytes: [u8; 8]) -> Result<Self, Error> {
+        if crc8(&bytes[..7]) == bytes[7] {
+            Ok(Self(bytes))
+        } else {
+            Err(Error::CrcMismatch)
+        }
+    }
+
+    /// Returns the bytes of the ROM ID (in bus order).
+    pub fn to_bytes(self) -> [u8; 8] {
+        self.0
+    }
+
+    /// Returns the family code of the device.
+    pub fn family_code(&self) -> u8 {
+        self.0[0]
+    }
+
+    /// Returns the 48-bit serial number of the device.
+    pub fn serial_number(&self) -> [u8; 6] {
+        // Unwrap is safe since the slice is exactly 6 bytes
+        self.0[1..7].try_into().unwrap()
+    }
+
+    /// Returns the CRC byte of the ROM ID.
+    pub fn crc(&self) -> u8 {
+        self.0[7]
+    }
+}
+
+impl From<RomId> for u64 {
+    fn from(rom: RomId) -> Self {
+        u64::from_le_bytes(rom.0)
+    }
+}
+
+/// SEARCH ROM kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SearchKind {
+    /// Enumerates all devices on the bus (SEARCH ROM).
+    All,
+    /// Enumerates only devices with an active alarm condition (ALARM SEARCH).
+    Alarm,
+}
+
+/// State of a SEARCH ROM enumeration.
+///
+/// Pass the same `Search` to repeated calls of [`OneWire::blocking_search_next`] (or
+/// [`OneWire::search_next`]) to discover one device per call until `None` is returned.
+#[derive(Clone, Debug)]
+pub struct Search {
+    kind: SearchKind,
+    rom: [u8; 8],
+    // Bit position (1-indexed, 0 meaning none) where the previous pass last took the 0 branch
+    last_discrepancy: usize,
+    // Same as above, but for the pass currently in progress
+    last_zero: usize,
+    done: bool,
+}
+
+impl Search {
+    /// Creates a new search of given kind.
+    pub fn new(kind: SearchKind) -> Self {
+        Self {
+            kind,
+            rom: [0; 8],
+            last_discrepancy: 0,
+            last_zero: 0,
+            done: false,
+        }
+    }
+
+    /// Returns true if all devices have been enumerated.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    // Returns the ROM command to start the next pass, or None if the search is complete
+    fn begin(&mut self) -> Option<u8> {
+        if self.done {
+            return None;
+        }
+
+        self.last_zero = 0;
+        Some(match self.kind {
+            SearchKind::All => SEARCH_ROM,
+            SearchKind::Alarm => ALARM_SEARCH,
+        })
+    }
+
+    // Given the bit and its complement read from the bus, returns the direction to take,
+    // or None (and ends the search) if no device participated
+    fn step(&mut self, bit: usize, id_bit: bool, cmp_bit: bool) -> Option<bool> {
+        let (byte, mask) = (bit / 8, 1 << (bit % 8));
+        let pos = bit + 1;
+
+        let dir = match (id_bit, cmp_bit) {
+            (true, true) => {
+                self.done = true;
+                return None;
+            }
+            // All participating devices agree on this bit
+            (id_bit, _) if id_bit != cmp_bit => id_bit,
+            // Discrepancy, so follow the previous path until the last discrepancy, then take the 1 branch
+            _ => {
+                let dir = if pos < self.last_discrepancy {
+                    (self.rom[byte] & mask) != 0
+                } else {
+                    pos == self.last_discrepancy
+                };
+
+                if !dir {
+                    self.last_zero = pos;
+                }
+                dir
+            }
+        };
+
+        if dir {
+            self.rom[byte] |= mask;
+        } else {
+            self.rom[byte] &= !mask;
+        }
+        Some(dir)
+    }
+
+    // Completes the current pass and returns the discovered ROM ID
+    fn finish(&mut self) -> Result<RomId, Error> {
+        self.last_discrepancy = self.last_zero;
+        if self.last_discrepancy == 0 {
+            self.done = true;
+        }
+
+        RomId::from_bytes(self.rom)
+    }
+}
+
+/// 1-Wire Interface (ONEWIRE) driver.
+pub struct OneWire<'d, M: IoMode> {
+    reg: &'static crate::pac::onewire::RegisterBlock,
+    waker: &'static AtomicWaker,
+    _phantom: PhantomData<&'d M>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d, M: IoMode> Send for OneWire<'d, M> {}
+
+impl<'d, M: IoMode> OneWire<'d, M> {
+    fn new_inner<T: Instance>(_instance: Peri<'d, T>, base_tick_ns: u32) -> Result<Self, Error> {
+        if !crate::sysinfo::SysInfo::soc_config().has_onewire() {
+            return Err(Error::NotSupported);
+        }
+
+        // Number of CPU clock cycles per base time tick
+        let cpu_freq = crate::sysinfo::SysInfo::clock_freq() as u64;
+        let cycles = (cpu_freq * base_tick_ns as u64) / 1_000_000_000;
+
+        // Pick the smallest prescaler (so best resolution) where the divider fits in 8 bits
+        // ONEWIRE only supports the first 4 of the common prescalers
+        let (clkprsc, cdiv) = [ClkPrsc::_2, ClkPrsc::_4, ClkPrsc::_8, ClkPrsc::_64]
+            .into_iter()
+            .map(|clkprsc| {
+                let prsc = u16::from(clkprsc) as u64;
+                (clkprsc, (cycles + prsc / 2) / prsc)
+            })
+            .find(|(_, div)| (1..=256).contains(div))
+            .ok_or(Error::InvalidTiming)?;
+
+        // Set prescaler and divider, enable ONEWIRE, and clear any stale RX data
+        // SAFETY: We've ensured the prescaler is valid and the divider fits in 8 bits
+        T::reg().ctrl().write(|w| unsafe {
+            w.onewire_ctrl_prsc()
+                .bits(clkprsc.bits() as u8)
+                .onewire_ctrl_clkdiv()
+                .bits((cdiv - 1) as u8)
+                .onewire_ctrl_en()
+                .set_bit()
+                .onewire_ctrl_clear()
+                .set_bit()
+        });
+
+        Ok(Self {
+            reg: T::reg(),
+            waker: T::waker(),
+            _phantom: PhantomData,
+        })
+    }
+
+    fn tx_full(&self) -> bool {
+        self.reg.ctrl().read().onewire_ctrl_tx_full().bit_is_set()
+    }
+
+    fn rx_avail(&self) -> bool {
+        self.reg.ctrl().read().onewire_ctrl_rx_avail().bit_is_set()
+    }
+
+    fn busy(&self) -> bool {
+        self.reg.ctrl().read().onewire_ctrl_busy().bit_is_set()
+    }
+
+    fn fifo_depth(&self) -> usize {
+        // Value in register is log2 of fifo depth
+        1 << self.reg.ctrl().read().onewire_ctrl_fifo().bits()
+    }
+
+    fn clear_rx(&mut self) {
+        // Clear bit auto-clears so we don't need to clear it ourselves
+        self.reg
+            .ctrl()
+            .modify(|_, w| w.onewire_ctrl_clear().set_bit());
+    }
+
+    fn push(&mut self, cmd: Command, data: u8) {
+        while self.tx_full() {}
+        // SAFETY: Command enum ensures we are writing valid command, and any data byte is valid
+        self.reg.dcmd().write(|w| unsafe {
+            w.onewire_dcmd_cmd()
+                .bits(cmd.into())
+                .onewire_dcmd_data()
+                .bits(data)
+        });
+    }
+
+    // Every command pushes its result into the RX FIFO once complete
+    fn pop(&mut self) -> u8 {
+        self.reg.dcmd().read().onewire_dcmd_data().bits()
+    }
+
+    fn presence(&self) -> bool {
+        self.reg.dcmd().read().onewire_dcmd_presence().bit_is_set()
+    }
+
+    // The sampled bit of a single-bit operation is shifted into the data MSB
+    fn pop_bit(&mut self) -> bool {
+        (self.pop() & 0x80) != 0
+    }
+
+    /// Returns the current state of the bus line (`true` if high).
+    pub fn sense(&self) -> bool {
+        self.reg.ctrl().read().onewire_ctrl_sense().bit_is_set()
+    }
+
+    /// Blocks until the bus is idle and all queued operations have completed.
+    pub fn blocking_flush(&self) {
+        while self.busy() {}
+    }
+
+    /// Generates a bus reset pulse and checks for a device presence pulse.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoPresence`] if no device responded.
+    pub fn blocking_reset(&mut self) -> Result<(), Error> {
+        self.clear_rx();
+        self.push(Command::Reset, 0);
+        self.blocking_flush();
+
+        if self.presence() {
+            Ok(())
+        } else {
+            Err(Error::NoPresence)
+        }
+    }
+
+    /// Writes a single bit to the bus.
+    pub fn blocking_write_bit(&mut self, bit: bool) {
+        self.clear_rx();
+        self.push(Command::Bit, bit as u8);
+        self.blocking_flush();
+    }
+
+    /// Reads a single bit from the bus.
+    pub fn blocking_read_bit(&mut self) -> bool {
+        self.clear_rx();
+        self.push(Command::Bit, 1);
+        self.blocking_flush();
+        self.pop_bit()
+    }
+
+    /// Writes a single byte to the bus (LSB first).
+    pub fn blocking_write_byte(&mut self, byte: u8) {
+        self.blocking_write(&[byte]);
+    }
+
+    /// Reads a single byte from the bus (LSB first).
+    pub fn blocking_read_byte(&mut self) -> u8 {
+        let mut byte = [0];
+        self.blocking_read(&mut byte);
+        byte[0]
+    }
+
+    /// Writes all bytes to the bus.
+    pub fn blocking_write(&mut self, data: &[u8]) {
+        // Write in FIFO-sized chunks so the RX FIFO never overflows with results we don't care about
+        for chunk in data.chunks(self.fifo_depth()) {
+            self.clear_rx();
+            for &byte in chunk {
+                self.push(Command::Byte, byte);
+            }
+            self.blocking_flush();
+        }
+    }
+
+    /// Reads bytes from the bus until the buffer is filled.
+    pub fn blocking_read(&mut self, data: &mut [u8]) {
+        for chunk in data.chunks_mut(self.fifo_depth()) {
+            self.clear_rx();
+            for _ in 0..chunk.len() {
+                self.push(Command::Byte, DUMMY);
+            }
+            self.blocking_flush();
+
+            for byte in chunk.iter_mut() {
+                while !self.rx_avail() {}
+                *byte = self.pop();
+            }
+        }
+    }
+
+    /// Reads the ROM ID of the only device on the bus (READ ROM).
+    ///
+    /// **Note**: If multiple devices are present, the result will be a garbled (and
+    /// most likely CRC-invalid) combination of their IDs.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoPresence`] if no device responded to reset.
+    ///
+    /// Returns [`Error::CrcMismatch`] if the received ROM ID is invalid.
+    pub fn blocking_read_rom(&mut self) -> Result<RomId, Error> {
+        self.blocking_reset()?;
+        self.blocking_write_byte(READ_ROM);
+
+        let mut rom = [0; 8];
+        self.blocking_read(&mut rom);
+        RomId::from_bytes(rom)
+    }
+
+    /// Resets the bus and selects the device with given ROM ID (MATCH ROM).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoPresence`] if no device responded to reset.
+    pub fn blocking_match_rom(&mut self, rom: &RomId) -> Result<(), Error> {
+        self.blocking_reset()?;
+        self.blocking_write_byte(MATCH_ROM);
+        self.blocking_write(&rom.0);
+        Ok(())
+    }
+
+    /// Resets the bus and selects all devices (SKIP ROM).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoPresence`] if no device responded to reset.
+    pub fn blocking_skip_rom(&mut self) -> Result<(), Error> {
+        self.blocking_reset()?;
+        self.blocking_write_byte(SKIP_ROM);
+        Ok(())
+    }
+
+    /// Performs a single pass of the search, returning the next discovered ROM ID.
+    ///
+    /// Returns `None` once all devices have been enumerated, or if no device responded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CrcMismatch`] if the discovered ROM ID is invalid (such as due to bus noise).
+    pub fn blocking_search_next(&mut self, search: &mut Search) -> Result<Option<RomId>, Error> {
+        let Some(cmd) = search.begin() else {
+            return Ok(None);
+        };
+
+        if self.blocking_reset().is_err() {
+            search.done = true;
+            return Ok(None);
+        }
+        self.blocking_write_byte(cmd);
+
+        for bit in 0..64 {
+            // Read the bit and its complement back-to-back through the FIFO
+            self.clear_rx();
+            self.push(Command::Bit, 1);
+            self.push(Command::Bit, 1);
+            self.blocking_flush();
+            let id_bit = self.pop_bit();
+            let cmp_bit = self.pop_bit();
+
+            let Some(dir) = search.step(bit, id_bit, cmp_bit) else {
+                return Ok(None);
+            };
+            self.blocking_write_bit(dir);
+        }
+
+        search.finish().map(Some)
+    }
+}
+
+impl<'d> OneWire<'d, Blocking> {
+    /// Returns a new instance of a blocking ONEWIRE driver with given base time tick (in nanoseconds).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if ONEWIRE is not supported.
+    ///
+    /// Returns [`Error::InvalidTiming`] if the base time tick can't be represented at the current CPU clock frequency.
+    pub fn new_blocking<T: Instance>(
+        _instance: Peri<'d, T>,
+        base_tick_ns: u32,
+    ) -> Result<Self, Error> {
+        Self::new_inner(_instance, base_tick_ns)
+    }
+}
+
+impl<'d> OneWire<'d, Async> {
+    /// Returns a new instance of an async ONEWIRE driver with given base time tick (in nanoseconds).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if ONEWIRE is not supported.
+    ///
+    /// Returns [`Error::InvalidTiming`] if the base time tick can't be represented at the current CPU clock frequency.
+    pub fn new_async<T: Instance>(
+        _instance: Peri<'d, T>,
+        base_tick_ns: u32,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_inner(_instance, base_tick_ns)
+    }
+
+    /// Waits until the bus is idle and all queued operations have completed.
+    pub async fn flush(&mut self) {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            if !self.busy() {
+                Poll::Ready(())
+            } else {
+                // SAFETY: It is valid to enable interrupts here, since it is level triggered
+                // and if the bus becomes idle between the above check and here, we won't miss it
+                unsafe { crate::enable_periph_irq!(ONEWIRE) }
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Generates a bus reset pulse and checks for a device presence pulse.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoPresence`] if no device responded.
+    pub async fn reset(&mut self) -> Result<(), Error> {
+        self.clear_rx();
+        self.push(Command::Reset, 0);
+        self.flush().await;
+
+        if self.presence() {
+            Ok(())
+        } else {
+            Err(Error::NoPresence)
+        }
+    }
+
+    /// Writes a single bit to the bus.
+    pub async fn write_bit(&mut self, bit: bool) {
+        self.clear_rx();
+        self.push(Command::Bit, bit as u8);
+        self.flush().await;
+    }
+
+    /// Reads a single bit from the bus.
+    pub async fn read_bit(&mut self) -> bool {
+        self.clear_rx();
+        self.push(Command::Bit, 1);
+        self.flush().await;
+        self.pop_bit()
+    }
+
+    /// Writes a single byte to the bus (LSB first).
+    pub async fn write_byte(&mut self, byte: u8) {
+        self.write(&[byte]).await;
+    }
+
+    /// Reads a single byte from the bus (LSB first).
+    pub async fn read_byte(&mut self) -> u8 {
+        let mut byte = [0];
+        self.read(&mut byte).await;
+        byte[0]
+    }
+
+    /// Writes all bytes to the bus.
+    pub async fn write(&mut self, data: &[u8]) {
+        // Write in FIFO-sized chunks so the RX FIFO never overflows with results we don't care about
+        for chunk in data.chunks(self.fifo_depth()) {
+            self.clear_rx();
+            for &byte in chunk {
+                self.push(Command::Byte, byte);
+            }
+            self.flush().await;
+        }
+    }
+
+    /// Reads bytes from the bus until the buffer is filled.
+    pub async fn read(&mut self, data: &mut [u8]) {
+        for chunk in data.chunks_mut(self.fifo_depth()) {
+            self.clear_rx();
+            for _ in 0..chunk.len() {
+                self.push(Command::Byte, DUMMY);
+            }
+            self.flush().await;
+
+            for byte in chunk.iter_mut() {
+                while !self.rx_avail() {}
+                *byte = self.pop();
+            }
+        }
+    }
+
+    /// Reads the ROM ID of the only device on the bus (READ ROM).
+    ///
+    /// **Note**: If multiple devices are present, the result will be a garbled (and
+    /// most likely CRC-invalid) combination of their IDs.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoPresence`] if no device responded to reset.
+    ///
+    /// Returns [`Error::CrcMismatch`] if the received ROM ID is invalid.
+    pub async fn read_rom(&mut self) -> Result<RomId, Error> {
+        self.reset().await?;
+        self.write_byte(READ_ROM).await;
+
+        let mut rom = [0; 8];
+        self.read(&mut rom).await;
+        RomId::from_bytes(rom)
+    }
+
+    /// Resets the bus and selects the device with given ROM ID (MATCH ROM).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoPresence`] if no device responded to reset.
+    pub async fn match_rom(&mut self, rom: &RomId) -> Result<(), Error> {
+        self.reset().await?;
+        self.write_byte(MATCH_ROM).await;
+        self.write(&rom.0).await;
+        Ok(())
+    }
+
+    /// Resets the bus and selects all devices (SKIP ROM).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoPresence`] if no device responded to reset.
+    pub async fn skip_rom(&mut self) -> Result<(), Error> {
+        self.reset().await?;
+        self.write_byte(SKIP_ROM).await;
+        Ok(())
+    }
+
+    /// Performs a single pass of the search, returning the next discovered ROM ID.
+    ///
+    /// Returns `None` once all devices have been enumerated, or if no device responded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CrcMismatch`] if the discovered ROM ID is invalid (such as due to bus noise).
+    pub async fn search_next(&mut self, search: &mut Search) -> Result<Option<RomId>, Error> {
+        let Some(cmd) = search.begin() else {
+            return Ok(None);
+        };
+
+        if self.reset().await.is_err() {
+            search.done = true;
+            return Ok(None);
+        }
+        self.write_byte(cmd).await;
+
+        for bit in 0..64 {
+            // Read the bit and its complement back-to-back through the FIFO
+            self.clear_rx();
+            self.push(Command::Bit, 1);
+            self.push(Command::Bit, 1);
+            self.flush().await;
+            let id_bit = self.pop_bit();
+            let cmp_bit = self.pop_bit();
+
+            let Some(dir) = search.step(bit, id_bit, cmp_bit) else {
+                return Ok(None);
+            };
+            self.write_bit(dir).await;
+        }
+
+        search.finish().map(Some)
+    }
+}
+
+impl<'d, M: IoMode> Drop for OneWire<'d, M> {
+    fn drop(&mut self) {
+        self.blocking_flush();
+        self.reg
+            .ctrl()
+            .modify(|_, w| w.onewire_ctrl_en().clear_bit());
+    }
+}
+
+trait SealedIoMode {}
+
+/// ONEWIRE IO mode.
+#[allow(private_bounds)]
+pub trait IoMode: SealedIoMode {}
+
+/// Blocking ONEWIRE.
+pub struct Blocking;
+impl SealedIoMode for Blocking {}
+impl IoMode for Blocking {}
+
+/// Async ONEWIRE.
+pub struct Async;
+impl SealedIoMode for Async {}
+impl IoMode for Async {}
+
+trait SealedInstance {
+    fn reg() -> &'static crate::pac::onewire::RegisterBlock;
+    fn waker() -> &'static AtomicWaker;
+}
+
+/// A valid ONEWIRE peripheral.
+#[allow(private_bounds)]
+pub trait Instance: SealedInstance + PeripheralType {
+    type Interrupt: Interrupt;
+}
+impl SealedInstance for ONEWIRE {
+    fn reg() -> &'static crate::pac::onewire::RegisterBlock {
+        // SAFETY: We own the ONEWIRE peripheral and are sure to use it safely
+        unsafe { &*crate::pac::Onewire::ptr() }
+    }
+
+    fn waker() -> &'static AtomicWaker {
+        static WAKER: AtomicWaker = AtomicWaker::new();
+        &WAKER
+    }
+}
+impl Instance for ONEWIRE {
+    type Interrupt = crate::interrupt::typelevel::ONEWIRE;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rom(family: u8, serial: [u8; 6]) -> RomId {
+        let mut bytes = [family, 0, 0, 0, 0, 0, 0, 0];
+        bytes[1..7].copy_from_slice(&serial);
+        bytes[7] = crc8(&bytes[..7]);
+        RomId::from_bytes(bytes).unwrap()
+    }
+
+    // Performs a search pass against simulated devices on a wired-AND bus
+    fn search_pass(search: &mut Search, devices: &[RomId]) -> Result<Option<RomId>, Error> {
+        if search.begin().is_none() {
+            return Ok(None);
+        }
+        if devices.is_empty() {
+            search.done = true;
+            return Ok(None);
+        }
+
+        // Bit mask of devices still participating in this pass
+        let mut active: u32 = (1 << devices.len()) - 1;
+        for bit in 0..64 {
+            let bit_of = |i: usize| (devices[i].0[bit / 8] >> (bit % 8)) & 1 != 0;
+            let participating = || (0..devices.len()).filter(|&i| (active & (1 << i)) != 0);
+            let id_bit = participating().all(bit_of);
+            let cmp_bit = participating().all(|i| !bit_of(i));
+
+            let Some(dir) = search.step(bit, id_bit, cmp_bit) else {
+                return Ok(None);
+            };
+            let remaining = participating()
+                .filter(|&i| bit_of(i) == dir)
+                .fold(0, |mask, i| mask | (1 << i));
+            active = remaining;
+        }
+
+        search.finish().map(Some)
+    }
+
+    #[test]
+    fn crc8_known_rom_ids() {
+        // Example ROM ID from Maxim application note 27
+        assert_eq!(crc8(&[0x02, 0x1c, 0xb8, 0x01, 0x00, 0x00, 0x00]), 0xa2);
+
+        // DS18B20 ROM ID, whose CRC over all 8 bytes is zero
+        let ds18b20 = [0x28, 0xb1, 0x6d, 0xa1, 0x03, 0x00, 0x00, 0x11];
+        assert_eq!(crc8(&ds18b20[..7]), 0x11);
+        assert_eq!(crc8(&ds18b20), 0);
+
+        let rom = RomId::from_bytes(ds18b20).unwrap();
+        assert_eq!(rom.family_code(), 0x28);
+        assert_eq!(rom.serial_number(), [0xb1, 0x6d, 0xa1, 0x03, 0x00, 0x00]);
+        assert_eq!(rom.crc(), 0x11);
+
+        let mut corrupt = ds18b20;
+        corrupt[3] ^= 0x04;
+        assert!(matches!(
+            RomId::from_bytes(corrupt),
+            Err(Error::CrcMismatch)
+        ));
+    }
+
+    #[test]
+    fn search_enumerates_all_devices() {
+        // Devices diverge both early (serial bit 1) and late (serial bit 47) in the ROM ID
+        let devices = [
+            rom(0x28, [0x01, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            rom(0x28, [0x03, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            rom(0x28, [0x01, 0x00, 0x00, 0x00, 0x00, 0x80]),
+        ];
+
+        let mut search = Search::new(SearchKind::All);
+        let mut found = [None; 3];
+        for slot in &mut found {
+            *slot = search_pass(&mut search, &devices).unwrap();
+        }
+
+        assert!(search.is_done());
+        assert_eq!(search_pass(&mut search, &devices).unwrap(), None);
+        for device in devices {
+            assert_eq!(found.iter().filter(|&&rom| rom == Some(device)).count(), 1);
+        }
+
+        // Once done, further passes don't touch the bus
+        assert_eq!(search.begin(), None);
+    }
+
+    #[test]
+    fn search_terminates_after_last_device() {
+        let device = rom(0x28, [0xb1, 0x6d, 0xa1, 0x03, 0x00, 0x00]);
+
+        let mut search = Search::new(SearchKind::Alarm);
+        assert_eq!(search.begin(), Some(ALARM_SEARCH));
+
+        let mut search = Search::new(SearchKind::All);
+        assert_eq!(search_pass(&mut search, &[device]).unwrap(), Some(device));
+        assert!(search.is_done());
+        assert_eq!(search_pass(&mut search, &[device]).unwrap(), None);
+    }
+
+    #[test]
+    fn search_ends_when_no_device_participates() {
+        let mut search = Search::new(SearchKind::All);
+        assert_eq!(search.step(0, true, true), None);
+        assert!(search.is_done());
+        assert_eq!(search_pass(&mut search, &[]).unwrap(), None);
+    }
+}
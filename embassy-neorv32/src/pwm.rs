@@ -1,8 +1,18 @@
 //! Pulse Width Modulation (PWM)
+use crate::clock::Constraints;
+#[cfg(not(feature = "time-driver-gptmr"))]
+use crate::gptmr::{self, GptmrSlice};
 use crate::sysinfo::SysInfo;
+use core::cell::Cell;
+use core::future::poll_fn;
 use core::marker::PhantomData;
+use core::task::Poll;
 use critical_section::{self, CriticalSection};
 use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::waitqueue::AtomicWaker;
+use embassy_time::{Duration, Ticker};
 
 /// PWM error.
 #[derive(Clone, Copy, Debug)]
@@ -12,6 +22,11 @@ pub enum Error {
     NotSupported,
     /// Invalid duty cycle.
     InvalidDuty,
+    /// The requested frequency is out of range for the configured clock prescaler, or the closest
+    /// representable frequency deviates from it by more than 10%.
+    InvalidFrequency,
+    /// A [`PwmStream`] half buffer was not refilled before it was due to be played again.
+    Underrun,
 }
 
 /// A duty cycle percent.
@@ -36,7 +51,11 @@ impl Percent {
     }
 }
 
-/// PWM clock prescaler.
+/// Clock prescaler.
+///
+/// Shared by peripherals which use the common NEORV32 clock prescaler (such as PWM and GPTMR).
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ClkPrsc {
     /// Divide main CPU clock by 2.
     _2,
@@ -57,7 +76,7 @@ pub enum ClkPrsc {
 }
 
 impl ClkPrsc {
-    fn bits(&self) -> u32 {
+    pub(crate) fn bits(&self) -> u32 {
         match *self {
             Self::_2 => 0b000,
             Self::_4 => 0b001,
@@ -70,7 +89,7 @@ impl ClkPrsc {
         }
     }
 
-    fn from_bits(raw: u32) -> Self {
+    pub(crate) fn from_bits(raw: u32) -> Self {
         match raw {
             0b000 => Self::_2,
             0b001 => Self::_4,
@@ -101,6 +120,8 @@ impl From<ClkPrsc> for u16 {
 }
 
 /// PWM operation mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Mode {
     /// Fast mode.
     Fast,
@@ -145,18 +166,20 @@ impl<'d> Pwm<'d> {
 
     /// Create a new instance of a PWM channel driver with given mode and frequency and enables it.
     ///
-    /// Depending on main clock frequency and prescaler, not all frequencies can be represented exactly.
+    /// Depending on main clock frequency and prescaler, not all frequencies can be represented
+    /// exactly, in which case the closest representable frequency is used.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if `pwm_freq == 0`, or `pwm_freq` is too small or too large to be represented.
+    /// Returns [`Error::InvalidFrequency`] if `pwm_freq` can't be represented with the
+    /// configured clock prescaler.
     pub fn new_channel<T: ChannelInstance>(
         &self,
         _instance: Peri<'d, T>,
         mode: Mode,
         pwm_freq: u32,
         invert_polarity: bool,
-    ) -> PwmChan<'d> {
+    ) -> Result<PwmChan<'d>, Error> {
         PwmChan::new(_instance, self.reg, mode, pwm_freq, invert_polarity)
     }
 }
@@ -180,25 +203,26 @@ impl<'d> PwmChan<'d> {
         mode: Mode,
         pwm_freq: u32,
         invert_polarity: bool,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let mut pwm = Self {
             reg,
             channel: T::channel(),
             _phantom: PhantomData,
         };
 
-        pwm.set_freq(pwm_freq);
+        let top = pwm.solve_top(mode, pwm_freq)?;
+        pwm.write_topcmp(top, 0);
 
         // These all modify config registers, which all PWM channels share, hence the CS here
         critical_section::with(|cs| {
             if invert_polarity {
                 pwm.invert_polarity(cs);
             }
-            pwm.set_mode(cs, mode);
+            pwm.write_mode(cs, mode);
             pwm.enable(cs);
         });
 
-        pwm
+        Ok(pwm)
     }
 
     fn enable(&mut self, _cs: CriticalSection) {
@@ -222,7 +246,7 @@ impl<'d> PwmChan<'d> {
             .modify(|r, w| unsafe { w.bits(r.bits() | (1 << self.channel)) });
     }
 
-    fn set_mode(&mut self, _cs: CriticalSection, mode: Mode) {
+    fn write_mode(&mut self, _cs: CriticalSection, mode: Mode) {
         // SAFETY: Bit mask preserves previous channel values
         self.reg.mode().modify(|r, w| unsafe {
             w.bits(match mode {
@@ -232,15 +256,6 @@ impl<'d> PwmChan<'d> {
         });
     }
 
-    fn mode(&self) -> Mode {
-        let mode_raw = self.reg.mode().read().bits() & (1 << self.channel);
-        if mode_raw != 0 {
-            Mode::PhaseCorrect
-        } else {
-            Mode::Fast
-        }
-    }
-
     fn top(&self) -> u16 {
         self.reg.channel(self.channel).topcmp().read().top().bits()
     }
@@ -249,51 +264,330 @@ impl<'d> PwmChan<'d> {
         self.reg.channel(self.channel).topcmp().read().cmp().bits()
     }
 
+    fn write_cmp(&mut self, cmp: u16) {
+        // SAFETY: Any CMP value is valid, callers bound it by the max duty ticks
+        self.reg
+            .channel(self.channel)
+            .topcmp()
+            .modify(|_, w| unsafe { w.cmp().bits(cmp) });
+    }
+
+    fn write_topcmp(&mut self, top: u16, cmp: u16) {
+        // Written together so the channel never runs with a mismatched TOP and CMP pair
+        // SAFETY: Any TOP and CMP values are valid
+        self.reg
+            .channel(self.channel)
+            .topcmp()
+            .write(|w| unsafe { w.top().bits(top).cmp().bits(cmp) });
+    }
+
+    fn topcmp_ptr(&self) -> *mut u32 {
+        self.reg.channel(self.channel).topcmp().as_ptr()
+    }
+
     fn clkprsc(&self) -> ClkPrsc {
         ClkPrsc::from_bits(self.reg.clkprsc().read().bits())
     }
 
     fn duty_cycle(&self) -> Percent {
-        let cmp = self.cmp() as u32;
-        let top = self.top() as u32;
-        let denom = top + 1;
-        let percent = ((100 * cmp + (denom / 2)) / denom) as u8;
+        let max = self.max_duty_ticks() as u32;
+        let percent = ((100 * self.cmp() as u32 + (max / 2)) / max) as u8;
 
-        Percent::new(percent).expect("Infallible")
+        Percent::new(percent.min(100)).expect("Clamped to 100")
     }
 
-    fn set_freq(&mut self, pwm_freq: u32) {
-        assert!(pwm_freq != 0);
+    fn solve_top(&self, mode: Mode, pwm_freq: u32) -> Result<u16, Error> {
+        let clkprsc = [u16::from(self.clkprsc()) as u32];
+        let (constraints, top_offset) = constraints(mode, &clkprsc);
 
-        let clkprsc = u16::from(self.clkprsc()) as u64;
-        let cpuclk = SysInfo::clock_freq() as u64;
+        let divider = crate::clock::solve(SysInfo::clock_freq(), pwm_freq, &constraints)
+            .map_err(|_| Error::InvalidFrequency)?;
 
-        let new_top = match self.mode() {
-            Mode::Fast => (cpuclk / (clkprsc * pwm_freq as u64)) - 1,
-            Mode::PhaseCorrect => cpuclk / (2 * clkprsc * pwm_freq as u64),
-        };
+        // The solver bounds the divider so TOP always fits in 16 bits
+        Ok((divider.div - top_offset) as u16)
+    }
 
-        assert!(new_top <= u16::MAX as u64);
+    /// Returns the channel's current operation mode.
+    pub fn mode(&self) -> Mode {
+        if self.reg.mode().read().bits() & (1 << self.channel) != 0 {
+            Mode::PhaseCorrect
+        } else {
+            Mode::Fast
+        }
+    }
 
-        // SAFETY: We've ensured a valid TOP value above
-        self.reg
-            .channel(self.channel)
-            .topcmp()
-            .modify(|_, w| unsafe { w.top().bits(new_top as u16) });
+    /// Returns the PWM frequency actually produced by the channel, rounded to the nearest Hz.
+    ///
+    /// This may differ slightly from the frequency requested if it isn't exactly representable.
+    pub fn frequency(&self) -> u32 {
+        let clkprsc = [u16::from(self.clkprsc()) as u32];
+        let (constraints, top_offset) = constraints(self.mode(), &clkprsc);
+        let div = self.top() as u32 + top_offset;
+
+        if div == 0 {
+            0
+        } else {
+            constraints.rate(SysInfo::clock_freq(), 0, div)
+        }
+    }
+
+    /// Changes the PWM frequency of the channel while it is running.
+    ///
+    /// The compare value is rescaled so the duty cycle is kept as closely as the new resolution
+    /// allows. Depending on main clock frequency and prescaler, the closest representable
+    /// frequency is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFrequency`] if `pwm_freq` can't be represented with the
+    /// configured clock prescaler, in which case the channel is left unchanged.
+    pub fn set_frequency(&mut self, pwm_freq: u32) -> Result<(), Error> {
+        let top = self.solve_top(self.mode(), pwm_freq)?;
+        let cmp = rescale_ticks(self.cmp(), self.max_duty_ticks(), max_ticks(top));
+        self.write_topcmp(top, cmp);
+        Ok(())
+    }
+
+    /// Changes the operation mode of the channel while it is running.
+    ///
+    /// The PWM frequency and duty cycle are preserved as closely as the new mode allows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFrequency`] if the current frequency can't be represented in the
+    /// new mode, in which case the channel is left unchanged.
+    pub fn set_mode(&mut self, mode: Mode) -> Result<(), Error> {
+        if mode == self.mode() {
+            return Ok(());
+        }
+
+        let top = self.solve_top(mode, self.frequency())?;
+        let cmp = rescale_ticks(self.cmp(), self.max_duty_ticks(), max_ticks(top));
+
+        critical_section::with(|cs| {
+            self.write_mode(cs, mode);
+            self.write_topcmp(top, cmp);
+        });
+
+        Ok(())
+    }
+
+    /// Returns the number of compare ticks corresponding to a 100% duty cycle.
+    ///
+    /// This is `TOP + 1` and depends on the channel's frequency, so it may change after calling
+    /// [`Self::set_frequency`] or [`Self::set_mode`]. If `TOP` is `u16::MAX`, a 100% duty cycle
+    /// can't be represented and this saturates to `u16::MAX`.
+    pub fn max_duty_ticks(&self) -> u16 {
+        max_ticks(self.top())
+    }
+
+    /// Returns the current compare value in ticks.
+    pub fn duty_ticks(&self) -> u16 {
+        self.cmp()
+    }
+
+    /// Set the PWM channel duty cycle as a raw compare value, using the full timer resolution.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDuty`] if `ticks` exceeds [`Self::max_duty_ticks`].
+    pub fn set_duty_ticks(&mut self, ticks: u16) -> Result<(), Error> {
+        if ticks > self.max_duty_ticks() {
+            return Err(Error::InvalidDuty);
+        }
+
+        self.write_cmp(ticks);
+        Ok(())
+    }
+
+    /// Set the PWM channel duty cycle to the fraction `num / denom`, rounded to the nearest tick.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDuty`] if `denom` is zero or `num > denom`.
+    pub fn set_duty_fraction(&mut self, num: u32, denom: u32) -> Result<(), Error> {
+        let ticks = fraction_ticks(num, denom, self.max_duty_ticks()).ok_or(Error::InvalidDuty)?;
+        self.write_cmp(ticks);
+        Ok(())
     }
 
     /// Set the PWM channel duty cycle in percent.
     pub fn set_duty_cycle(&mut self, percent: Percent) {
-        let percent = u32::from(percent.inner());
-        let top = self.top() as u32;
-        let denom = 100;
-        let cmp = (((percent) * (top + 1) + (denom / 2)) / denom) as u16;
+        let ticks = fraction_ticks(percent.inner() as u32, 100, self.max_duty_ticks())
+            .expect("Percent is at most 100");
+        self.write_cmp(ticks);
+    }
+}
 
-        // SAFETY: We've ensured a valid CMP value above
-        self.reg
-            .channel(self.channel)
-            .topcmp()
-            .modify(|_, w| unsafe { w.cmp().bits(cmp) });
+// Fast mode counts 0..=TOP, phase-correct mode counts up to TOP and back down again
+fn constraints(mode: Mode, clkprsc: &[u32]) -> (Constraints<'_>, u32) {
+    match mode {
+        Mode::Fast => {
+            let constraints = Constraints {
+                scale: 1,
+                prescalers: clkprsc,
+                min_div: 1,
+                max_div: u16::MAX as u32 + 1,
+                tolerance_permille: 100,
+            };
+            (constraints, 1)
+        }
+        Mode::PhaseCorrect => {
+            let constraints = Constraints {
+                scale: 2,
+                prescalers: clkprsc,
+                min_div: 1,
+                max_div: u16::MAX as u32,
+                tolerance_permille: 100,
+            };
+            (constraints, 0)
+        }
+    }
+}
+
+// The output is active while the counter is below CMP, so CMP = TOP + 1 is a 100% duty cycle
+fn max_ticks(top: u16) -> u16 {
+    top.saturating_add(1)
+}
+
+fn fraction_ticks(num: u32, denom: u32, max: u16) -> Option<u16> {
+    if denom == 0 || num > denom {
+        return None;
+    }
+
+    let (num, denom) = (num as u64, denom as u64);
+    Some(((num * max as u64 + denom / 2) / denom) as u16)
+}
+
+fn rescale_ticks(ticks: u16, old_max: u16, new_max: u16) -> u16 {
+    fraction_ticks(ticks.min(old_max) as u32, old_max as u32, new_max).unwrap_or(0)
+}
+
+/// A group of PWM channels whose compare values are updated together.
+///
+/// Useful when several outputs must change in lockstep, such as the phases of a motor driver
+/// during commutation.
+///
+/// For channels to share period boundaries they should be configured with the same mode and
+/// frequency, then aligned with [`Self::restart`].
+///
+/// **Note**: The PWM channels will be disabled when dropped.
+pub struct PwmGroup<'d, const N: usize> {
+    channels: [PwmChan<'d>; N],
+    mask: u32,
+}
+
+impl<'d, const N: usize> PwmGroup<'d, N> {
+    /// Create a new group from the given channels.
+    pub fn new(channels: [PwmChan<'d>; N]) -> Self {
+        let mask = channels
+            .iter()
+            .fold(0, |mask, chan| mask | (1 << chan.channel));
+        Self { channels, mask }
+    }
+
+    /// Returns the channels of the group, in the order given to [`Self::new`].
+    pub fn channels(&self) -> &[PwmChan<'d>; N] {
+        &self.channels
+    }
+
+    /// Returns a mutable reference to the channels of the group.
+    ///
+    /// Changes made through individual channels are not synchronized with each other.
+    pub fn channels_mut(&mut self) -> &mut [PwmChan<'d>; N] {
+        &mut self.channels
+    }
+
+    /// Releases the channels from the group.
+    pub fn release(self) -> [PwmChan<'d>; N] {
+        self.channels
+    }
+
+    /// Enables all channels of the group with a single register write.
+    pub fn enable(&mut self) {
+        // SAFETY: Bit mask preserves other channel values
+        self.reg()
+            .enable()
+            .modify(|r, w| unsafe { w.bits(r.bits() | self.mask) });
+    }
+
+    /// Disables all channels of the group with a single register write.
+    pub fn disable(&mut self) {
+        // SAFETY: Bit mask preserves other channel values
+        self.reg()
+            .enable()
+            .modify(|r, w| unsafe { w.bits(r.bits() & !self.mask) });
+    }
+
+    /// Restarts the counters of all channels of the group at the same time, aligning their
+    /// periods.
+    ///
+    /// The current period of each channel is cut short.
+    pub fn restart(&mut self) {
+        critical_section::with(|_| {
+            self.disable();
+            self.enable();
+        });
+    }
+
+    /// Set the raw compare values of all channels, in the order given to [`Self::new`].
+    ///
+    /// All values are validated first, then written back-to-back within a single critical
+    /// section. The whole update therefore only takes a few bus cycles and can't be preempted,
+    /// so it lands within a single PWM period unless the period is shorter than that window.
+    /// If even that must be ruled out, use [`Self::restart_with_duty_ticks`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDuty`] if any value exceeds its channel's
+    /// [`PwmChan::max_duty_ticks`], in which case no channel is updated.
+    pub fn set_duty_ticks(&mut self, ticks: [u16; N]) -> Result<(), Error> {
+        self.validate(&ticks)?;
+        critical_section::with(|_| self.write_cmps(&ticks));
+        Ok(())
+    }
+
+    /// Set the raw compare values of all channels and restart their counters together, so the
+    /// new values all take effect at the start of the same period.
+    ///
+    /// Unlike [`Self::set_duty_ticks`], this guarantees no period ever mixes old and new values,
+    /// at the cost of cutting the current period short.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDuty`] if any value exceeds its channel's
+    /// [`PwmChan::max_duty_ticks`], in which case no channel is updated.
+    pub fn restart_with_duty_ticks(&mut self, ticks: [u16; N]) -> Result<(), Error> {
+        self.validate(&ticks)?;
+        critical_section::with(|_| {
+            self.disable();
+            self.write_cmps(&ticks);
+            self.enable();
+        });
+        Ok(())
+    }
+
+    fn reg(&self) -> &'static crate::pac::pwm::RegisterBlock {
+        crate::peripherals::PWM::reg()
+    }
+
+    fn validate(&self, ticks: &[u16; N]) -> Result<(), Error> {
+        let valid = self
+            .channels
+            .iter()
+            .zip(ticks)
+            .all(|(chan, &ticks)| ticks <= chan.max_duty_ticks());
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::InvalidDuty)
+        }
+    }
+
+    fn write_cmps(&mut self, ticks: &[u16; N]) {
+        for (chan, &ticks) in self.channels.iter_mut().zip(ticks) {
+            chan.write_cmp(ticks);
+        }
     }
 }
 
@@ -303,6 +597,397 @@ impl<'d> Drop for PwmChan<'d> {
     }
 }
 
+// Playback position shared between a `PwmStream` and the code pacing it
+#[derive(Clone, Copy)]
+struct Cursor {
+    buf: *const u32,
+    dst: *mut u32,
+    len: usize,
+    pos: usize,
+    looping: bool,
+    running: bool,
+    // Number of half buffers played so far (wrapping)
+    halves: u32,
+}
+
+// SAFETY: The pointers are only dereferenced by `stream_tick` while the owning `Playback` is alive
+unsafe impl Send for Cursor {}
+
+impl Cursor {
+    const IDLE: Self = Self {
+        buf: core::ptr::null(),
+        dst: core::ptr::null_mut(),
+        len: 0,
+        pos: 0,
+        looping: false,
+        running: false,
+        halves: 0,
+    };
+
+    // Returns the index of the next sample to play, or `None` once a one-shot playback is done
+    fn advance(&mut self) -> Option<usize> {
+        if !self.running {
+            return None;
+        }
+
+        if self.pos == self.len {
+            if self.looping {
+                self.pos = 0;
+            } else {
+                self.running = false;
+                return None;
+            }
+        }
+
+        let index = self.pos;
+        self.pos += 1;
+        if self.pos == self.len / 2 || self.pos == self.len {
+            self.halves = self.halves.wrapping_add(1);
+        }
+
+        Some(index)
+    }
+}
+
+// The GPTMR hook carries no context, so at most one stream can be playing at a time
+struct StreamState {
+    cursor: Mutex<CriticalSectionRawMutex, Cell<Cursor>>,
+    waker: AtomicWaker,
+}
+
+static STREAM: StreamState = StreamState {
+    cursor: Mutex::new(Cell::new(Cursor::IDLE)),
+    waker: AtomicWaker::new(),
+};
+
+// Writes the next sample, called once per sample period from the GPTMR interrupt or a ticker
+fn stream_tick() {
+    let changed = STREAM.cursor.lock(|cursor| {
+        let mut cur = cursor.get();
+        let before = (cur.halves, cur.running);
+
+        if let Some(index) = cur.advance() {
+            // SAFETY: The `Playback` owning the buffer stops playback before releasing it, and
+            // `dst` is the `TOPCMP` register of the channel held borrowed by the stream
+            unsafe { cur.dst.write_volatile(cur.buf.add(index).read()) };
+        }
+
+        cursor.set(cur);
+        (cur.halves, cur.running) != before
+    });
+
+    if changed {
+        STREAM.waker.wake();
+    }
+}
+
+/// Streams samples from memory to a PWM channel, for sampled audio or arbitrary waveforms.
+///
+/// Every sample period, the next sample is written into the channel's `TOPCMP` register, paced
+/// either by a GPTMR slice interrupt (see [`Self::new_gptmr`]) or by an embassy-time ticker
+/// (see [`Self::new_ticker`]).
+///
+/// **Note**: The NEORV32 DMA has no hardware request lines, so it can't be paced by a timer.
+/// Samples are therefore written by the CPU, which is a single register store per sample.
+///
+/// Samples are whole `TOPCMP` register words, so buffers must be prepared with a
+/// [`SampleEncoder`] (see [`Self::encoder`]), which combines each duty cycle with the channel's
+/// `TOP` value.
+///
+/// The PWM frequency should be well above the sample rate so each sample spans several PWM
+/// periods, for example with the smallest clock prescaler and 8-bit resolution for audio.
+pub struct PwmStream<'a, 'd> {
+    chan: &'a mut PwmChan<'d>,
+    #[cfg(not(feature = "time-driver-gptmr"))]
+    slice: Option<(&'a mut GptmrSlice<'d, gptmr::Async>, u32)>,
+    ticker: Option<Ticker>,
+}
+
+impl<'a, 'd> PwmStream<'a, 'd> {
+    /// Create a new stream whose samples are paced by a GPTMR slice.
+    ///
+    /// The next sample is written directly from the GPTMR interrupt, so playback is unaffected
+    /// by executor latency. The slice is only running while a [`Playback`] is alive.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFrequency`] if `sample_rate` is zero or above the GPTMR tick rate.
+    #[cfg(not(feature = "time-driver-gptmr"))]
+    pub fn new_gptmr(
+        chan: &'a mut PwmChan<'d>,
+        slice: &'a mut GptmrSlice<'d, gptmr::Async>,
+        sample_rate: u32,
+    ) -> Result<Self, Error> {
+        if sample_rate == 0 {
+            return Err(Error::InvalidFrequency);
+        }
+
+        let ticks = (slice.tick_freq() + sample_rate / 2) / sample_rate;
+        if ticks == 0 {
+            return Err(Error::InvalidFrequency);
+        }
+
+        Ok(Self {
+            chan,
+            slice: Some((slice, ticks)),
+            ticker: None,
+        })
+    }
+
+    /// Create a new stream whose samples are paced by an embassy-time ticker.
+    ///
+    /// Samples are only played while a future returned by the stream or its [`Playback`] is
+    /// being awaited, and timing is subject to executor latency and the time driver tick rate.
+    /// This suits one-shot [`Self::play`] at low sample rates, or configurations where no GPTMR
+    /// slice is available.
+    ///
+    /// **Note**: With [`Self::play_continuous`], playback stalls (holding the current sample)
+    /// whenever [`Playback::next_half`] is not being awaited, such as while refilling a half.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFrequency`] if `sample_rate` is zero.
+    pub fn new_ticker(chan: &'a mut PwmChan<'d>, sample_rate: u32) -> Result<Self, Error> {
+        if sample_rate == 0 {
+            return Err(Error::InvalidFrequency);
+        }
+
+        Ok(Self {
+            chan,
+            #[cfg(not(feature = "time-driver-gptmr"))]
+            slice: None,
+            ticker: Some(Ticker::every(Duration::from_hz(sample_rate as u64))),
+        })
+    }
+
+    /// Returns an encoder for preparing sample buffers for this stream's channel.
+    pub fn encoder(&self) -> SampleEncoder {
+        SampleEncoder {
+            top: self.chan.top(),
+            max: self.chan.max_duty_ticks(),
+        }
+    }
+
+    /// Plays the encoded `samples` once, returning when the last one has been written.
+    ///
+    /// The channel keeps the last sample's duty cycle afterwards.
+    ///
+    /// **Note**: Playback is stopped if cancelled/dropped before completion.
+    pub async fn play(&mut self, samples: &[u32]) {
+        if samples.is_empty() {
+            return;
+        }
+
+        let playback = Playback::new(self, samples.as_ptr().cast_mut(), samples.len(), false);
+        playback.stream.wait_until(|cur| !cur.running).await;
+    }
+
+    /// Starts continuously playing `buffer` in a loop as a double buffer.
+    ///
+    /// `buffer` should already hold encoded samples. Each time one half has finished playing,
+    /// [`Playback::next_half`] returns it so it can be refilled while the other half plays.
+    ///
+    /// Playback stops when the returned [`Playback`] is dropped.
+    ///
+    /// **Note**: If the stream is paced by a ticker, samples only advance while
+    /// [`Playback::next_half`] is being awaited (see [`Self::new_ticker`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `buffer` length is zero or odd.
+    pub fn play_continuous<'s>(&'s mut self, buffer: &'s mut [u32]) -> Playback<'s, 'a, 'd> {
+        assert!(!buffer.is_empty() && buffer.len().is_multiple_of(2));
+        Playback::new(self, buffer.as_mut_ptr(), buffer.len(), true)
+    }
+
+    fn start(&mut self, buf: *const u32, len: usize, looping: bool) {
+        let cursor = Cursor {
+            buf,
+            dst: self.chan.topcmp_ptr(),
+            len,
+            looping,
+            running: true,
+            ..Cursor::IDLE
+        };
+        STREAM.cursor.lock(|cur| cur.set(cursor));
+
+        if let Some(ticker) = &mut self.ticker {
+            ticker.reset();
+        }
+
+        #[cfg(not(feature = "time-driver-gptmr"))]
+        if let Some((slice, ticks)) = &mut self.slice {
+            slice.set_hook(Some(stream_tick));
+            slice.start_periodic(*ticks);
+        }
+    }
+
+    fn stop(&mut self) {
+        #[cfg(not(feature = "time-driver-gptmr"))]
+        if let Some((slice, _)) = &mut self.slice {
+            slice.stop();
+            slice.set_hook(None);
+        }
+
+        STREAM.cursor.lock(|cur| cur.set(Cursor::IDLE));
+    }
+
+    async fn wait_until(&mut self, done: impl Fn(&Cursor) -> bool) -> Cursor {
+        // Without a GPTMR slice, samples are played from here as the ticker fires
+        if let Some(ticker) = &mut self.ticker {
+            loop {
+                let cur = STREAM.cursor.lock(|cur| cur.get());
+                if done(&cur) {
+                    return cur;
+                }
+
+                ticker.next().await;
+                stream_tick();
+            }
+        }
+
+        poll_fn(|cx| {
+            STREAM.waker.register(cx.waker());
+
+            let cur = STREAM.cursor.lock(|cur| cur.get());
+            if done(&cur) {
+                Poll::Ready(cur)
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+/// Continuous double-buffered playback started by [`PwmStream::play_continuous`].
+///
+/// **Note**: Playback is stopped when dropped.
+pub struct Playback<'s, 'a, 'd> {
+    stream: &'s mut PwmStream<'a, 'd>,
+    buf: *mut u32,
+    len: usize,
+    // Number of half buffers handed out by `next_half` (wrapping)
+    taken: u32,
+    _buffer: PhantomData<&'s mut [u32]>,
+}
+
+impl<'s, 'a, 'd> Playback<'s, 'a, 'd> {
+    fn new(stream: &'s mut PwmStream<'a, 'd>, buf: *mut u32, len: usize, looping: bool) -> Self {
+        stream.start(buf, len, looping);
+        Self {
+            stream,
+            buf,
+            len,
+            taken: 0,
+            _buffer: PhantomData,
+        }
+    }
+
+    /// Waits until a half of the buffer has finished playing and returns it for refilling.
+    ///
+    /// Halves are returned alternately, starting with the first half. The returned half must be
+    /// refilled before the other half finishes playing, otherwise stale samples are played.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Underrun`] if more than one half finished playing since the previous
+    /// call. Playback continues, and the next call returns the next half to finish.
+    pub async fn next_half(&mut self) -> Result<&mut [u32], Error> {
+        let taken = self.taken;
+        let cur = self.stream.wait_until(|cur| cur.halves != taken).await;
+
+        if cur.halves.wrapping_sub(self.taken) > 1 {
+            self.taken = cur.halves;
+            return Err(Error::Underrun);
+        }
+
+        self.taken = self.taken.wrapping_add(1);
+        let half = self.len / 2;
+        let start = if self.taken % 2 == 1 { 0 } else { half };
+
+        // SAFETY: Samples are now being played from the other half, and the buffer is borrowed for 's
+        Ok(unsafe { core::slice::from_raw_parts_mut(self.buf.add(start), half) })
+    }
+
+    /// Stops playback.
+    pub fn stop(self) {}
+}
+
+impl<'s, 'a, 'd> Drop for Playback<'s, 'a, 'd> {
+    fn drop(&mut self) {
+        self.stream.stop();
+    }
+}
+
+/// Encodes samples into `TOPCMP` register words for a [`PwmStream`].
+///
+/// The encoder is a snapshot of the channel's `TOP` value, so it remains usable while the stream
+/// is borrowed by a [`Playback`].
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SampleEncoder {
+    top: u16,
+    max: u16,
+}
+
+impl SampleEncoder {
+    /// Returns the number of compare ticks corresponding to a 100% duty cycle.
+    ///
+    /// See [`PwmChan::max_duty_ticks`].
+    pub fn max_duty_ticks(&self) -> u16 {
+        self.max
+    }
+
+    /// Encodes raw compare values into `dst`, clamping each to [`Self::max_duty_ticks`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `src` buffer length does not match the `dst` buffer length.
+    pub fn encode_ticks(&self, src: &[u16], dst: &mut [u32]) {
+        assert!(src.len() == dst.len());
+        for (word, &ticks) in dst.iter_mut().zip(src) {
+            *word = topcmp_word(self.top, ticks.min(self.max));
+        }
+    }
+
+    /// Encodes unsigned 8-bit PCM samples into `dst`, scaling them to the full duty cycle range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `src` buffer length does not match the `dst` buffer length.
+    pub fn encode_u8(&self, src: &[u8], dst: &mut [u32]) {
+        assert!(src.len() == dst.len());
+        for (word, &sample) in dst.iter_mut().zip(src) {
+            let cmp = scale_sample(sample as u32, u8::MAX as u32, self.max);
+            *word = topcmp_word(self.top, cmp);
+        }
+    }
+
+    /// Encodes signed 16-bit PCM samples into `dst`, scaling them to the full duty cycle range
+    /// (with silence at 50%).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `src` buffer length does not match the `dst` buffer length.
+    pub fn encode_i16(&self, src: &[i16], dst: &mut [u32]) {
+        assert!(src.len() == dst.len());
+        for (word, &sample) in dst.iter_mut().zip(src) {
+            let sample = (sample as i32 - i16::MIN as i32) as u32;
+            let cmp = scale_sample(sample, u16::MAX as u32, self.max);
+            *word = topcmp_word(self.top, cmp);
+        }
+    }
+}
+
+fn topcmp_word(top: u16, cmp: u16) -> u32 {
+    ((top as u32) << 16) | cmp as u32
+}
+
+fn scale_sample(sample: u32, full_scale: u32, max: u16) -> u16 {
+    ((sample as u64 * max as u64 + full_scale as u64 / 2) / full_scale as u64) as u16
+}
+
 trait SealedInstance {
     fn reg() -> &'static crate::pac::pwm::RegisterBlock;
 }
@@ -407,12 +1092,83 @@ impl<'d> embedded_hal_1::pwm::ErrorType for PwmChan<'d> {
 
 impl<'d> embedded_hal_1::pwm::SetDutyCycle for PwmChan<'d> {
     fn max_duty_cycle(&self) -> u16 {
-        100
+        self.max_duty_ticks()
     }
 
     fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
-        let percent = Percent::new(duty as u8)?;
-        self.set_duty_cycle(percent);
-        Ok(())
+        self.set_duty_ticks(duty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fraction_ticks_round_to_nearest() {
+        assert_eq!(fraction_ticks(0, 100, 1000), Some(0));
+        assert_eq!(fraction_ticks(1, 3, 1000), Some(333));
+        assert_eq!(fraction_ticks(2, 3, 1000), Some(667));
+        assert_eq!(fraction_ticks(100, 100, u16::MAX), Some(u16::MAX));
+        assert_eq!(fraction_ticks(u32::MAX, u32::MAX, 1000), Some(1000));
+    }
+
+    #[test]
+    fn fraction_ticks_rejects_invalid() {
+        assert_eq!(fraction_ticks(1, 0, 1000), None);
+        assert_eq!(fraction_ticks(101, 100, 1000), None);
+    }
+
+    #[test]
+    fn max_ticks_saturates() {
+        assert_eq!(max_ticks(0), 1);
+        assert_eq!(max_ticks(999), 1000);
+        assert_eq!(max_ticks(u16::MAX), u16::MAX);
+    }
+
+    #[test]
+    fn samples_scale_to_full_range() {
+        assert_eq!(scale_sample(0, 255, 1000), 0);
+        assert_eq!(scale_sample(255, 255, 1000), 1000);
+        assert_eq!(scale_sample(128, 255, 256), 129);
+        assert_eq!(scale_sample(32768, 65535, 256), 128);
+        assert_eq!(topcmp_word(0x1234, 0x5678), 0x1234_5678);
+    }
+
+    #[test]
+    fn cursor_counts_halves() {
+        let mut cur = Cursor {
+            len: 4,
+            looping: true,
+            running: true,
+            ..Cursor::IDLE
+        };
+
+        let indices: [_; 6] = core::array::from_fn(|_| cur.advance());
+        assert_eq!(indices, [0, 1, 2, 3, 0, 1].map(Some));
+        assert_eq!(cur.halves, 3);
+    }
+
+    #[test]
+    fn cursor_stops_after_one_shot() {
+        let mut cur = Cursor {
+            len: 3,
+            running: true,
+            ..Cursor::IDLE
+        };
+
+        let indices: [_; 4] = core::array::from_fn(|_| cur.advance());
+        assert_eq!(indices, [Some(0), Some(1), Some(2), None]);
+        assert!(!cur.running);
+    }
+
+    #[test]
+    fn rescale_preserves_duty() {
+        assert_eq!(rescale_ticks(250, 1000, 4000), 1000);
+        assert_eq!(rescale_ticks(1000, 1000, 10), 10);
+        assert_eq!(rescale_ticks(0, 1000, 10), 0);
+        // CMP beyond max is treated as a 100% duty cycle
+        assert_eq!(rescale_ticks(2000, 1000, 10), 10);
+        assert_eq!(rescale_ticks(5, 0, 10), 0);
     }
 }
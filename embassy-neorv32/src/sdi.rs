@@ -0,0 +1,504 @@
+//! Serial Data Interface (SDI)
+//!
+//! Allows the NEORV32 to act as an SPI device (peripheral), clocked by an external SPI host.
+//!
+//! **Note**: The hardware provides no interrupt for chip-select changes, so while waiting for
+//! chip-select deassertion (and thus the end of a [`Sdi::transaction`]) the task wakes on each
+//! received byte and otherwise re-checks chip-select every [`CS_POLL_INTERVAL`].
+use crate::dma;
+use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
+use crate::peripherals::SDI;
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::task::Poll;
+use embassy_futures::select::select;
+use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::waitqueue::AtomicWaker;
+use embassy_time::{Duration, Timer};
+
+/// Interval at which chip-select is re-checked while waiting for it to be deasserted.
+pub const CS_POLL_INTERVAL: Duration = Duration::from_micros(100);
+
+/// SDI interrupt handler binding.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        // We disable the interrupt since it is level triggered and there is no apparent way to acknowledge it
+        T::Interrupt::disable();
+        T::waker().wake();
+    }
+}
+
+/// SDI error.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The NEORV32 configuration does not support SDI.
+    NotSupported,
+    /// A DMA bus error occurred.
+    DmaBusError,
+}
+
+// The FIFO conditions which can trigger an SDI interrupt
+#[derive(Clone, Copy, PartialEq)]
+enum Irq {
+    RxNotEmpty,
+    RxFull,
+    TxEmpty,
+}
+
+/// Serial Data Interface (SDI) driver.
+pub struct Sdi<'d, M: IoMode> {
+    reg: &'static crate::pac::sdi::RegisterBlock,
+    waker: &'static AtomicWaker,
+    dma: Option<dma::Dma<'d>>,
+    _phantom: PhantomData<&'d M>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d, M: IoMode> Send for Sdi<'d, M> {}
+
+impl<'d, M: IoMode> Sdi<'d, M> {
+    fn new_inner<T: Instance>(_instance: Peri<'d, T>) -> Result<Self, Error> {
+        if !crate::sysinfo::SysInfo::soc_config().has_sdi() {
+            return Err(Error::NotSupported);
+        }
+
+        // Enable SDI with all interrupt sources disabled, and clear any stale FIFO data
+        T::reg().ctrl().write(|w| {
+            w.sdi_ctrl_en()
+                .set_bit()
+                .sdi_ctrl_clr_rx()
+                .set_bit()
+                .sdi_ctrl_clr_tx()
+                .set_bit()
+        });
+
+        Ok(Self {
+            reg: T::reg(),
+            waker: T::waker(),
+            dma: None,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn rx_empty(&self) -> bool {
+        self.reg.ctrl().read().sdi_ctrl_rx_empty().bit_is_set()
+    }
+
+    fn rx_full(&self) -> bool {
+        self.reg.ctrl().read().sdi_ctrl_rx_full().bit_is_set()
+    }
+
+    fn tx_empty(&self) -> bool {
+        self.reg.ctrl().read().sdi_ctrl_tx_empty().bit_is_set()
+    }
+
+    fn tx_full(&self) -> bool {
+        self.reg.ctrl().read().sdi_ctrl_tx_full().bit_is_set()
+    }
+
+    fn read_byte(&mut self) -> u8 {
+        self.reg.data().read().bits() as u8
+    }
+
+    fn write_byte(&mut self, byte: u8) {
+        // SAFETY: Only the lowest 8 bits are used, so any value is valid
+        self.reg.data().write(|w| unsafe { w.bits(byte as u32) });
+    }
+
+    // Only a single interrupt source is enabled at a time, depending on what we are waiting for
+    fn set_irq(&mut self, irq: Irq) {
+        self.reg.ctrl().modify(|_, w| {
+            w.sdi_ctrl_irq_rx_nempty()
+                .bit(irq == Irq::RxNotEmpty)
+                .sdi_ctrl_irq_rx_full()
+                .bit(irq == Irq::RxFull)
+                .sdi_ctrl_irq_tx_empty()
+                .bit(irq == Irq::TxEmpty)
+        });
+    }
+
+    // Reads any available bytes into `rx` (discarding those which don't fit) and refills the TX FIFO
+    // from `tx`, updating the respective counts
+    fn service_frame(&mut self, rx: &mut [u8], rxi: &mut usize, tx: &[u8], txi: &mut usize) {
+        while !self.rx_empty() {
+            let byte = self.read_byte();
+            if let Some(r) = rx.get_mut(*rxi) {
+                *r = byte;
+            }
+            *rxi += 1;
+        }
+
+        while *txi < tx.len() && !self.tx_full() {
+            self.write_byte(tx[*txi]);
+            *txi += 1;
+        }
+    }
+
+    /// Returns the depth of the RX and TX FIFOs.
+    pub fn fifo_depth(&self) -> usize {
+        // Value in register is log2 of fifo depth
+        1 << self.reg.ctrl().read().sdi_ctrl_fifo().bits()
+    }
+
+    /// Returns true if the chip-select line is currently asserted by the host.
+    pub fn is_cs_active(&self) -> bool {
+        self.reg.ctrl().read().sdi_ctrl_cs_active().bit_is_set()
+    }
+
+    /// Discards all bytes in the RX FIFO.
+    pub fn clear_rx(&mut self) {
+        // Clear bit auto-clears so we don't need to clear it ourselves
+        self.reg.ctrl().modify(|_, w| w.sdi_ctrl_clr_rx().set_bit());
+    }
+
+    /// Discards all bytes in the TX FIFO.
+    pub fn clear_tx(&mut self) {
+        // Clear bit auto-clears so we don't need to clear it ourselves
+        self.reg.ctrl().modify(|_, w| w.sdi_ctrl_clr_tx().set_bit());
+    }
+
+    /// Reads a single received byte if available.
+    pub fn try_read(&mut self) -> Option<u8> {
+        if self.rx_empty() {
+            None
+        } else {
+            Some(self.read_byte())
+        }
+    }
+
+    /// Preloads as many bytes as will fit into the TX FIFO, to be sent on the next host transfer.
+    ///
+    /// Returns the number of bytes loaded.
+    pub fn preload(&mut self, data: &[u8]) -> usize {
+        let mut n = 0;
+        for &byte in data {
+            if self.tx_full() {
+                break;
+            }
+            self.write_byte(byte);
+            n += 1;
+        }
+        n
+    }
+
+    /// Reads bytes clocked in by the host until the buffer is filled, blocking until done.
+    pub fn blocking_read(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            while self.rx_empty() {}
+            *byte = self.read_byte();
+        }
+    }
+
+    /// Queues all bytes to be clocked out by the host, blocking until all are in the TX FIFO.
+    pub fn blocking_write(&mut self, data: &[u8]) {
+        for &byte in data {
+            while self.tx_full() {}
+            self.write_byte(byte);
+        }
+    }
+
+    /// Blocks until the host asserts chip-select.
+    pub fn blocking_wait_cs_asserted(&self) {
+        while !self.is_cs_active() {}
+    }
+
+    /// Blocks until the host deasserts chip-select.
+    pub fn blocking_wait_cs_deasserted(&self) {
+        while self.is_cs_active() {}
+    }
+
+    /// Performs a single frame (from chip-select assertion to deassertion), blocking until done.
+    ///
+    /// Bytes from `tx` are sent to the host, with the FIFOs being cleared and preloaded beforehand.
+    /// Received bytes are stored in `rx`, and any which don't fit are discarded.
+    /// If the host clocks more bytes than `tx` contains, the remaining bytes sent are undefined.
+    ///
+    /// Returns the total number of bytes clocked by the host during the frame.
+    pub fn blocking_transaction(&mut self, rx: &mut [u8], tx: &[u8]) -> usize {
+        self.clear_rx();
+        self.clear_tx();
+
+        let mut txi = self.preload(tx);
+        let mut rxi = 0;
+
+        self.blocking_wait_cs_asserted();
+        while self.is_cs_active() {
+            self.service_frame(rx, &mut rxi, tx, &mut txi);
+        }
+
+        // Collect any bytes received right before chip-select was released
+        self.service_frame(rx, &mut rxi, tx, &mut txi);
+        rxi
+    }
+}
+
+impl<'d> Sdi<'d, Blocking> {
+    /// Returns a new instance of a blocking SDI driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SDI is not supported.
+    pub fn new_blocking<T: Instance>(_instance: Peri<'d, T>) -> Result<Self, Error> {
+        Self::new_inner(_instance)
+    }
+}
+
+impl<'d> Sdi<'d, Async> {
+    /// Returns a new instance of an async SDI driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SDI is not supported.
+    pub fn new_async<T: Instance>(
+        _instance: Peri<'d, T>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_inner(_instance)
+    }
+
+    async fn wait_for(&mut self, irq: Irq) {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            let ready = match irq {
+                Irq::RxNotEmpty => !self.rx_empty(),
+                Irq::RxFull => self.rx_full(),
+                Irq::TxEmpty => self.tx_empty(),
+            };
+
+            if ready {
+                Poll::Ready(())
+            } else {
+                self.set_irq(irq);
+                // SAFETY: It is valid to enable interrupts here, since it is level triggered
+                // and if the FIFO condition is met between the above check and here, we won't miss it
+                unsafe { crate::enable_periph_irq!(SDI) }
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Reads bytes clocked in by the host until the buffer is filled.
+    ///
+    /// If DMA is available, full FIFOs are copied out by DMA.
+    ///
+    /// # Errors
+    ///
+    /// Returns [Error::DmaBusError] if DMA transfer fails.
+    pub async fn read(&mut self, data: &mut [u8]) -> Result<(), Error> {
+        let depth = self.fifo_depth();
+        let mut i = 0;
+
+        while i < data.len() {
+            // If DMA available, wait for a full FIFO and use DMA to transfer it to the buffer
+            if self.dma.is_some() && data.len() - i >= depth {
+                self.wait_for(Irq::RxFull).await;
+
+                let src = self.reg.data().as_ptr() as *const u8;
+                // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
+                let src = unsafe { src.as_ref().unwrap_unchecked() };
+                // Unwrap is safe since we checked DMA is available above
+                let dma = self.dma.as_mut().unwrap();
+                dma.read(src, &mut data[i..i + depth], false)
+                    .await
+                    .map_err(|_| Error::DmaBusError)?;
+                i += depth;
+
+            // Otherwise, manually read each available byte into buffer
+            } else {
+                self.wait_for(Irq::RxNotEmpty).await;
+                while i < data.len() && !self.rx_empty() {
+                    data[i] = self.read_byte();
+                    i += 1;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Queues all bytes to be clocked out by the host, waiting until all are in the TX FIFO.
+    ///
+    /// If DMA is available, empty FIFOs are refilled by DMA.
+    ///
+    /// # Errors
+    ///
+    /// Returns [Error::DmaBusError] if DMA transfer fails.
+    pub async fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        let depth = self.fifo_depth();
+        let mut i = 0;
+
+        while i < data.len() {
+            self.wait_for(Irq::TxEmpty).await;
+
+            // If DMA available, use it to transfer a full FIFO worth of data from buffer
+            if let Some(dma) = &mut self.dma
+                && data.len() - i >= depth
+            {
+                let dst = self.reg.data().as_ptr() as *mut u8;
+                // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
+                let dst = unsafe { dst.as_mut().unwrap_unchecked() };
+                dma.write(&data[i..i + depth], dst, false)
+                    .await
+                    .map_err(|_| Error::DmaBusError)?;
+                i += depth;
+
+            // Otherwise, manually write bytes until the TX FIFO is full
+            } else {
+                i += self.preload(&data[i..]);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Waits until the host asserts chip-select.
+    ///
+    /// Assertion is detected either directly or by the arrival of the first byte,
+    /// so the RX FIFO interrupt is used to avoid polling.
+    pub async fn wait_cs_asserted(&mut self) {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            if self.is_cs_active() || !self.rx_empty() {
+                Poll::Ready(())
+            } else {
+                self.set_irq(Irq::RxNotEmpty);
+                // SAFETY: It is valid to enable interrupts here, since it is level triggered
+                // and if a byte arrives between the above check and here, we won't miss it
+                unsafe { crate::enable_periph_irq!(SDI) }
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Waits until the host deasserts chip-select.
+    ///
+    /// **Note**: Since there is no interrupt for chip-select, this re-checks it every
+    /// [`CS_POLL_INTERVAL`] (and whenever a byte arrives in an empty RX FIFO).
+    pub async fn wait_cs_deasserted(&mut self) {
+        while self.is_cs_active() {
+            self.wait_frame_activity().await;
+        }
+    }
+
+    // Waits until a byte arrives in an empty RX FIFO or the chip-select poll interval elapses,
+    // since there is no interrupt for chip-select deassertion
+    async fn wait_frame_activity(&mut self) {
+        if self.rx_empty() {
+            select(
+                self.wait_for(Irq::RxNotEmpty),
+                Timer::after(CS_POLL_INTERVAL),
+            )
+            .await;
+        } else {
+            // Unread bytes would keep the level triggered interrupt asserted, so only wait on the timer
+            Timer::after(CS_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Performs a single frame (from chip-select assertion to deassertion).
+    ///
+    /// Bytes from `tx` are sent to the host, with the FIFOs being cleared and preloaded beforehand.
+    /// Received bytes are stored in `rx`, and any which don't fit are discarded.
+    /// If the host clocks more bytes than `tx` contains, the remaining bytes sent are undefined.
+    ///
+    /// Returns the total number of bytes clocked by the host during the frame.
+    ///
+    /// **Note**: The frame is serviced each time a byte is received, but since there is no interrupt
+    /// for chip-select deassertion, the end of the frame is only noticed within [`CS_POLL_INTERVAL`].
+    pub async fn transaction(&mut self, rx: &mut [u8], tx: &[u8]) -> usize {
+        self.clear_rx();
+        self.clear_tx();
+
+        let mut txi = self.preload(tx);
+        let mut rxi = 0;
+
+        self.wait_cs_asserted().await;
+        loop {
+            let active = self.is_cs_active();
+            self.service_frame(rx, &mut rxi, tx, &mut txi);
+
+            if !active {
+                // Chip-select was released before we drained, so collect any final bytes
+                self.service_frame(rx, &mut rxi, tx, &mut txi);
+                break;
+            }
+
+            // The RX FIFO was just drained, so this wakes on the next byte clocked by the host
+            self.wait_frame_activity().await;
+        }
+
+        rxi
+    }
+
+    /// Gives the DMA controller to the SDI driver.
+    /// It can later be retrieved via [Self::take_dma] for use with other peripherals.
+    ///
+    /// This is for flexibility purposes as there is only one DMA channel available.
+    /// If no DMA is provided, data must be manually copied to/from FIFOs.
+    pub fn give_dma(&mut self, dma: dma::Dma<'d>) {
+        let _ = self.dma.replace(dma);
+    }
+
+    /// Retrieves the DMA controller if available, allowing it to be used by other peripherals again.
+    ///
+    /// See [Self::give_dma] for the implications of this.
+    pub fn take_dma(&mut self) -> Option<dma::Dma<'d>> {
+        self.dma.take()
+    }
+}
+
+impl<'d, M: IoMode> Drop for Sdi<'d, M> {
+    fn drop(&mut self) {
+        self.reg.ctrl().modify(|_, w| w.sdi_ctrl_en().clear_bit());
+    }
+}
+
+trait SealedIoMode {}
+
+/// SDI IO mode.
+#[allow(private_bounds)]
+pub trait IoMode: SealedIoMode {}
+
+/// Blocking SDI.
+pub struct Blocking;
+impl SealedIoMode for Blocking {}
+impl IoMode for Blocking {}
+
+/// Async SDI.
+pub struct Async;
+impl SealedIoMode for Async {}
+impl IoMode for Async {}
+
+trait SealedInstance {
+    fn reg() -> &'static crate::pac::sdi::RegisterBlock;
+    fn waker() -> &'static AtomicWaker;
+}
+
+/// A valid SDI peripheral.
+#[allow(private_bounds)]
+pub trait Instance: SealedInstance + PeripheralType {
+    type Interrupt: Interrupt;
+}
+impl SealedInstance for SDI {
+    fn reg() -> &'static crate::pac::sdi::RegisterBlock {
+        // SAFETY: We own the SDI peripheral and are sure to use it safely
+        unsafe { &*crate::pac::Sdi::ptr() }
+    }
+
+    fn waker() -> &'static AtomicWaker {
+        static WAKER: AtomicWaker = AtomicWaker::new();
+        &WAKER
+    }
+}
+impl Instance for SDI {
+    type Interrupt = crate::interrupt::typelevel::SDI;
+}
@@ -0,0 +1,933 @@
+//! Stream Link Interface (SLINK)
+//!
+//! Provides AXI4-stream compatible RX and TX links of 32-bit words, with end-of-packet
+//! delimiters (`TLAST`) and 4-bit routing IDs (`TID`/`TDEST`).
+use crate::dma::{self, Dma};
+use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
+use crate::peripherals::SLINK;
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::Poll;
+use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::waitqueue::AtomicWaker;
+
+// Routing IDs are 4 bits wide
+const ROUTE_MAX: u8 = 0xf;
+
+/// SLINK interrupt handler binding.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        let reg = T::info().reg;
+        let ctrl = reg.ctrl().read();
+
+        // If RX FIFO is not empty or full (whichever we are waiting on), disable those IRQs and wake RX task
+        let rx_nempty = ctrl.slink_ctrl_irq_rx_nempty().bit_is_set()
+            && ctrl.slink_ctrl_rx_empty().bit_is_clear();
+        let rx_full =
+            ctrl.slink_ctrl_irq_rx_full().bit_is_set() && ctrl.slink_ctrl_rx_full().bit_is_set();
+
+        if rx_nempty || rx_full {
+            reg.ctrl().modify(|_, w| {
+                w.slink_ctrl_irq_rx_nempty()
+                    .clear_bit()
+                    .slink_ctrl_irq_rx_full()
+                    .clear_bit()
+            });
+            T::info().rx_waker.wake();
+        }
+
+        // If TX FIFO is not full or empty (whichever we are waiting on), disable those IRQs and wake TX task
+        let tx_nfull =
+            ctrl.slink_ctrl_irq_tx_nfull().bit_is_set() && ctrl.slink_ctrl_tx_full().bit_is_clear();
+        let tx_empty =
+            ctrl.slink_ctrl_irq_tx_empty().bit_is_set() && ctrl.slink_ctrl_tx_empty().bit_is_set();
+
+        if tx_nfull || tx_empty {
+            reg.ctrl().modify(|_, w| {
+                w.slink_ctrl_irq_tx_nfull()
+                    .clear_bit()
+                    .slink_ctrl_irq_tx_empty()
+                    .clear_bit()
+            });
+            T::info().tx_waker.wake();
+        }
+    }
+}
+
+/// SLINK error.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The NEORV32 configuration does not support SLINK.
+    NotSupported,
+    /// A DMA error occurred.
+    Dma(dma::Error),
+    /// A received packet did not fit in the buffer, so the remaining words were discarded.
+    PacketOverflow,
+    /// The routing ID does not fit in 4 bits.
+    InvalidRoute,
+}
+
+/// A word received from the RX link, along with its stream metadata.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RxWord {
+    /// The received word.
+    pub data: u32,
+    /// Whether the word is the last word of a packet.
+    pub last: bool,
+    /// The source routing ID of the word.
+    pub route: u8,
+}
+
+/// Stream Link Interface (SLINK) driver.
+pub struct Slink<'d, M: IoMode> {
+    rx: SlinkRx<'d, M>,
+    tx: SlinkTx<'d, M>,
+}
+
+impl<'d, M: IoMode> Slink<'d, M> {
+    fn new_inner<T: Instance>(
+        _instance: Peri<'d, T>,
+        rx_dma: Option<Dma<'d>>,
+        tx_dma: Option<Dma<'d>>,
+    ) -> Result<Self, Error> {
+        let rx = SlinkRx::new_inner::<T>(rx_dma)?;
+        let tx = SlinkTx::new_inner::<T>(tx_dma)?;
+        Ok(Self { rx, tx })
+    }
+
+    /// Reads a word from the RX link, blocking if empty.
+    pub fn blocking_read_word(&mut self) -> RxWord {
+        self.rx.blocking_read_word()
+    }
+
+    /// Reads a packet from the RX link into the buffer, blocking until its last word is received.
+    ///
+    /// Returns the number of words in the packet and its source routing ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PacketOverflow`] if the packet did not fit in the buffer.
+    pub fn blocking_read_packet(&mut self, buf: &mut [u32]) -> Result<(usize, u8), Error> {
+        self.rx.blocking_read_packet(buf)
+    }
+
+    /// Writes a word to the TX link, blocking if full.
+    ///
+    /// Marks the word as the end of a packet if `last` is true.
+    pub fn blocking_write_word(&mut self, word: u32, last: bool) {
+        self.tx.blocking_write_word(word, last)
+    }
+
+    /// Writes all words as a single packet to the TX link, blocking if full.
+    pub fn blocking_write_packet(&mut self, words: &[u32]) {
+        self.tx.blocking_write_packet(words)
+    }
+
+    /// Splits the SLINK driver into separate [`SlinkRx`] and [`SlinkTx`] drivers.
+    ///
+    /// Helpful for sharing the SLINK among consumer/producer tasks.
+    pub fn split(self) -> (SlinkRx<'d, M>, SlinkTx<'d, M>) {
+        (self.rx, self.tx)
+    }
+
+    /// Splits the SLINK driver into separate [`SlinkRx`] and [`SlinkTx`] drivers by mutable reference.
+    ///
+    /// Helpful for sharing the SLINK among consumer/producer tasks without destroying the original [`Slink`] instance.
+    pub fn split_ref(&mut self) -> (&mut SlinkRx<'d, M>, &mut SlinkTx<'d, M>) {
+        (&mut self.rx, &mut self.tx)
+    }
+}
+
+impl<'d> Slink<'d, Blocking> {
+    /// Creates a new blocking SLINK driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SLINK is not supported.
+    pub fn new_blocking<T: Instance>(_instance: Peri<'d, T>) -> Result<Self, Error> {
+        Self::new_inner(_instance, None, None)
+    }
+}
+
+impl<'d> Slink<'d, Async> {
+    fn new_async_inner<T: Instance>(
+        _instance: Peri<'d, T>,
+        rx_dma: Option<Dma<'d>>,
+        tx_dma: Option<Dma<'d>>,
+    ) -> Result<Self, Error> {
+        let slink = Self::new_inner(_instance, rx_dma, tx_dma)?;
+        // SAFETY: It is valid to enable SLINK interrupt here
+        unsafe { T::Interrupt::enable() }
+        Ok(slink)
+    }
+
+    /// Creates a new async SLINK driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SLINK is not supported.
+    pub fn new_async<T: Instance>(
+        _instance: Peri<'d, T>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_async_inner(_instance, None, None)
+    }
+
+    /// Creates a new async SLINK driver.
+    ///
+    /// Additionally provides the DMA peripheral for RX transfers.
+    /// See [`SlinkRx::new_async_with_dma`] for considerations on whether to use DMA or not.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SLINK is not supported.
+    ///
+    /// Returns [`Error::Dma`] if DMA is not supported.
+    pub fn new_async_with_rx_dma<T: Instance, D: dma::Instance>(
+        _instance: Peri<'d, T>,
+        dma: Peri<'d, D>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>>
+        + Binding<D::Interrupt, dma::InterruptHandler<D>>
+        + 'd,
+    ) -> Result<Self, Error> {
+        let dma = dma::Dma::new(dma, _irq).map_err(Error::Dma)?;
+        Self::new_async_inner(_instance, Some(dma), None)
+    }
+
+    /// Creates a new async SLINK driver.
+    ///
+    /// Additionally provides the DMA peripheral for TX transfers.
+    /// See [`SlinkTx::new_async_with_dma`] for considerations on whether to use DMA or not.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SLINK is not supported.
+    ///
+    /// Returns [`Error::Dma`] if DMA is not supported.
+    pub fn new_async_with_tx_dma<T: Instance, D: dma::Instance>(
+        _instance: Peri<'d, T>,
+        dma: Peri<'d, D>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>>
+        + Binding<D::Interrupt, dma::InterruptHandler<D>>
+        + 'd,
+    ) -> Result<Self, Error> {
+        let dma = dma::Dma::new(dma, _irq).map_err(Error::Dma)?;
+        Self::new_async_inner(_instance, None, Some(dma))
+    }
+
+    /// Reads words from the RX link until the buffer is full, ignoring packet boundaries.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Dma`] if DMA error occurred during transfer.
+    pub fn read(&mut self, buf: &mut [u32]) -> impl Future<Output = Result<(), Error>> {
+        self.rx.read(buf)
+    }
+
+    /// Reads a packet from the RX link into the buffer, completing once its last word is received.
+    ///
+    /// Returns the number of words in the packet and its source routing ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PacketOverflow`] if the packet did not fit in the buffer.
+    pub fn read_packet(
+        &mut self,
+        buf: &mut [u32],
+    ) -> impl Future<Output = Result<(usize, u8), Error>> {
+        self.rx.read_packet(buf)
+    }
+
+    /// Writes all words to the TX link without marking the end of a packet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Dma`] if DMA error occurred during transfer.
+    pub fn write(&mut self, words: &[u32]) -> impl Future<Output = Result<(), Error>> {
+        self.tx.write(words)
+    }
+
+    /// Writes all words as a single packet to the TX link.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Dma`] if DMA error occurred during transfer.
+    pub fn write_packet(&mut self, words: &[u32]) -> impl Future<Output = Result<(), Error>> {
+        self.tx.write_packet(words)
+    }
+}
+
+/// RX-only SLINK driver.
+pub struct SlinkRx<'d, M: IoMode> {
+    info: Info,
+    dma: Option<dma::Dma<'d>>,
+    _phantom: PhantomData<&'d M>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d, M: IoMode> Send for SlinkRx<'d, M> {}
+
+impl<'d, M: IoMode> SlinkRx<'d, M> {
+    fn new_inner<T: Instance>(dma: Option<dma::Dma<'d>>) -> Result<Self, Error> {
+        if !crate::sysinfo::SysInfo::soc_config().has_slink() {
+            return Err(Error::NotSupported);
+        }
+
+        // Mark RX as active and enable SLINK
+        T::info().active.rx.store(true, Ordering::Release);
+        critical_section::with(|_| {
+            T::info()
+                .reg
+                .ctrl()
+                .modify(|_, w| w.slink_ctrl_en().set_bit())
+        });
+
+        Ok(Self {
+            info: T::info(),
+            dma,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn read_inner(&mut self) -> RxWord {
+        // The status describes the word at the head of the FIFO, so latch it before popping the word
+        let last = self
+            .info
+            .reg
+            .ctrl()
+            .read()
+            .slink_ctrl_rx_last()
+            .bit_is_set();
+        let route = self.info.reg.route().read().slink_route().bits();
+        let data = self.info.reg.data().read().bits();
+        RxWord { data, last, route }
+    }
+
+    fn fifo_empty(&self) -> bool {
+        self.info
+            .reg
+            .ctrl()
+            .read()
+            .slink_ctrl_rx_empty()
+            .bit_is_set()
+    }
+
+    fn fifo_full(&self) -> bool {
+        self.info
+            .reg
+            .ctrl()
+            .read()
+            .slink_ctrl_rx_full()
+            .bit_is_set()
+    }
+
+    /// Returns the depth of the RX FIFO (in words).
+    pub fn fifo_depth(&self) -> usize {
+        // Value in register is log2 of fifo depth
+        1 << self.info.reg.ctrl().read().slink_ctrl_rx_fifo().bits()
+    }
+
+    /// Reads a word from the RX link if available.
+    pub fn try_read_word(&mut self) -> Option<RxWord> {
+        if self.fifo_empty() {
+            None
+        } else {
+            Some(self.read_inner())
+        }
+    }
+
+    /// Reads a word from the RX link, blocking if empty.
+    pub fn blocking_read_word(&mut self) -> RxWord {
+        while self.fifo_empty() {}
+        self.read_inner()
+    }
+
+    /// Reads words from the RX link until the buffer is full, blocking if empty.
+    ///
+    /// Packet boundaries are ignored.
+    pub fn blocking_read(&mut self, buf: &mut [u32]) {
+        for word in buf {
+            *word = self.blocking_read_word().data;
+        }
+    }
+
+    /// Reads a packet from the RX link into the buffer, blocking until its last word is received.
+    ///
+    /// Returns the number of words in the packet and its source routing ID (that of its last word).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PacketOverflow`] if the packet did not fit in the buffer.
+    /// The rest of the packet is still consumed, so the next read starts at a packet boundary.
+    pub fn blocking_read_packet(&mut self, buf: &mut [u32]) -> Result<(usize, u8), Error> {
+        let mut n = 0;
+        loop {
+            let word = self.blocking_read_word();
+            if let Some(w) = buf.get_mut(n) {
+                *w = word.data;
+            }
+            n += 1;
+
+            if word.last {
+                return packet_result(buf, n, word.route);
+            }
+        }
+    }
+}
+
+impl<'d> SlinkRx<'d, Blocking> {
+    /// Creates a new RX-only blocking SLINK driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SLINK is not supported.
+    pub fn new_blocking<T: Instance>(_instance: Peri<'d, T>) -> Result<Self, Error> {
+        Self::new_inner::<T>(None)
+    }
+}
+
+impl<'d> SlinkRx<'d, Async> {
+    async fn wait_fifo_nempty(&mut self) {
+        poll_fn(|cx| {
+            self.info.rx_waker.register(cx.waker());
+            if !self.fifo_empty() {
+                Poll::Ready(())
+            } else {
+                // CS used here since interrupt modifies register
+                critical_section::with(|_| {
+                    self.info
+                        .reg
+                        .ctrl()
+                        .modify(|_, w| w.slink_ctrl_irq_rx_nempty().set_bit())
+                });
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    async fn wait_fifo_full(&mut self) {
+        poll_fn(|cx| {
+            self.info.rx_waker.register(cx.waker());
+            if self.fifo_full() {
+                Poll::Ready(())
+            } else {
+                // CS used here since interrupt modifies register
+                critical_section::with(|_| {
+                    self.info
+                        .reg
+                        .ctrl()
+                        .modify(|_, w| w.slink_ctrl_irq_rx_full().set_bit())
+                });
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    async fn read_chunk(&mut self, chunk: &mut [u32]) -> Result<(), Error> {
+        // If DMA available, use it to transfer data from RX FIFO to buffer
+        if let Some(dma) = &mut self.dma {
+            let src = self.info.reg.data().as_ptr() as *const u32;
+            // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
+            let src = unsafe { src.as_ref().unwrap_unchecked() };
+            dma.read(src, chunk, false).await.map_err(Error::Dma)?;
+
+        // Otherwise, manually read each word from RX FIFO
+        } else {
+            for word in chunk {
+                *word = self.read_inner().data;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn new_async_inner<T: Instance>(
+        _instance: Peri<'d, T>,
+        dma: Option<Dma<'d>>,
+    ) -> Result<Self, Error> {
+        let slink = Self::new_inner::<T>(dma)?;
+        // SAFETY: It is valid to enable SLINK interrupt here
+        unsafe { T::Interrupt::enable() }
+        Ok(slink)
+    }
+
+    /// Creates a new RX-only async SLINK driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SLINK is not supported.
+    pub fn new_async<T: Instance>(
+        _instance: Peri<'d, T>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_async_inner(_instance, None)
+    }
+
+    /// Creates a new RX-only async SLINK driver.
+    ///
+    /// Additionally provides the DMA peripheral for transfers.
+    ///
+    /// **Note**: The DMA peripheral is limited in that it is single-channel only so you have to
+    /// decide which peripheral (if any) will use it. DMA is only used by [`Self::read`] to drain
+    /// full FIFOs, since packet reads need to check the end-of-packet flag of every word.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SLINK is not supported.
+    ///
+    /// Returns [`Error::Dma`] if DMA is not supported.
+    pub fn new_async_with_dma<T: Instance, D: dma::Instance>(
+        _instance: Peri<'d, T>,
+        dma: Peri<'d, D>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>>
+        + Binding<D::Interrupt, dma::InterruptHandler<D>>
+        + 'd,
+    ) -> Result<Self, Error> {
+        let dma = dma::Dma::new(dma, _irq).map_err(Error::Dma)?;
+        Self::new_async_inner(_instance, Some(dma))
+    }
+
+    /// Reads a word from the RX link.
+    pub async fn read_word(&mut self) -> RxWord {
+        self.wait_fifo_nempty().await;
+        self.read_inner()
+    }
+
+    /// Reads words from the RX link until the buffer is full, ignoring packet boundaries.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Dma`] if DMA error occurred during transfer.
+    pub async fn read(&mut self, buf: &mut [u32]) -> Result<(), Error> {
+        let mut chunks = buf.chunks_exact_mut(self.fifo_depth());
+
+        // For chunks that match the FIFO depth, we can wait for FIFO full
+        // then read all words from the FIFO in one shot
+        for chunk in chunks.by_ref() {
+            self.wait_fifo_full().await;
+            self.read_chunk(chunk).await?;
+        }
+
+        // But for the remainder need to interrupt every time the FIFO is not empty
+        // and manually read a single word
+        for word in chunks.into_remainder() {
+            *word = self.read_word().await.data;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a packet from the RX link into the buffer, completing once its last word is received.
+    ///
+    /// Returns the number of words in the packet and its source routing ID (that of its last word).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PacketOverflow`] if the packet did not fit in the buffer.
+    /// The rest of the packet is still consumed, so the next read starts at a packet boundary.
+    pub async fn read_packet(&mut self, buf: &mut [u32]) -> Result<(usize, u8), Error> {
+        let mut n = 0;
+        loop {
+            let word = self.read_word().await;
+            if let Some(w) = buf.get_mut(n) {
+                *w = word.data;
+            }
+            n += 1;
+
+            if word.last {
+                return packet_result(buf, n, word.route);
+            }
+        }
+    }
+}
+
+// Returns the length and route of a received packet, or an error if it did not fit in the buffer
+fn packet_result(buf: &[u32], len: usize, route: u8) -> Result<(usize, u8), Error> {
+    if len > buf.len() {
+        Err(Error::PacketOverflow)
+    } else {
+        Ok((len, route))
+    }
+}
+
+impl<'d, M: IoMode> Drop for SlinkRx<'d, M> {
+    fn drop(&mut self) {
+        self.info.active.rx.store(false, Ordering::Release);
+        drop_slink(&self.info);
+    }
+}
+
+/// TX-only SLINK driver.
+pub struct SlinkTx<'d, M: IoMode> {
+    info: Info,
+    dma: Option<dma::Dma<'d>>,
+    _phantom: PhantomData<&'d M>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d, M: IoMode> Send for SlinkTx<'d, M> {}
+
+impl<'d, M: IoMode> SlinkTx<'d, M> {
+    fn new_inner<T: Instance>(dma: Option<dma::Dma<'d>>) -> Result<Self, Error> {
+        if !crate::sysinfo::SysInfo::soc_config().has_slink() {
+            return Err(Error::NotSupported);
+        }
+
+        // Mark TX as active and enable SLINK
+        T::info().active.tx.store(true, Ordering::Release);
+        critical_section::with(|_| {
+            T::info()
+                .reg
+                .ctrl()
+                .modify(|_, w| w.slink_ctrl_en().set_bit())
+        });
+
+        Ok(Self {
+            info: T::info(),
+            dma,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn write_inner(&mut self, word: u32, last: bool) {
+        // SAFETY: Any u32 is valid data
+        if last {
+            self.info.reg.data_last().write(|w| unsafe { w.bits(word) });
+        } else {
+            self.info.reg.data().write(|w| unsafe { w.bits(word) });
+        }
+    }
+
+    fn fifo_full(&self) -> bool {
+        self.info
+            .reg
+            .ctrl()
+            .read()
+            .slink_ctrl_tx_full()
+            .bit_is_set()
+    }
+
+    fn fifo_empty(&self) -> bool {
+        self.info
+            .reg
+            .ctrl()
+            .read()
+            .slink_ctrl_tx_empty()
+            .bit_is_set()
+    }
+
+    /// Returns the depth of the TX FIFO (in words).
+    pub fn fifo_depth(&self) -> usize {
+        // Value in register is log2 of fifo depth
+        1 << self.info.reg.ctrl().read().slink_ctrl_tx_fifo().bits()
+    }
+
+    /// Sets the destination routing ID for subsequently written words.
+    ///
+    /// **Note**: The routing register is shared with the RX link, where reading it returns
+    /// the source routing ID, so this does not affect [`SlinkRx::route`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidRoute`] if the routing ID does not fit in 4 bits.
+    pub fn set_route(&mut self, route: u8) -> Result<(), Error> {
+        if route > ROUTE_MAX {
+            return Err(Error::InvalidRoute);
+        }
+
+        // SAFETY: We've ensured the routing ID fits in 4 bits
+        self.info
+            .reg
+            .route()
+            .write(|w| unsafe { w.slink_route().bits(route) });
+        Ok(())
+    }
+
+    /// Writes a word to the TX link, blocking if full.
+    ///
+    /// Marks the word as the end of a packet if `last` is true.
+    pub fn blocking_write_word(&mut self, word: u32, last: bool) {
+        while self.fifo_full() {}
+        self.write_inner(word, last);
+    }
+
+    /// Writes all words to the TX link without marking the end of a packet, blocking if full.
+    pub fn blocking_write(&mut self, words: &[u32]) {
+        for &word in words {
+            self.blocking_write_word(word, false);
+        }
+    }
+
+    /// Writes all words as a single packet to the TX link, blocking if full.
+    ///
+    /// The final word is marked as the end of the packet. Nothing is sent if `words` is empty.
+    pub fn blocking_write_packet(&mut self, words: &[u32]) {
+        if let Some((&last, body)) = words.split_last() {
+            self.blocking_write(body);
+            self.blocking_write_word(last, true);
+        }
+    }
+
+    /// Blocks until all words in the TX FIFO have been sent.
+    pub fn blocking_flush(&self) {
+        while !self.fifo_empty() {}
+    }
+}
+
+impl<'d> SlinkTx<'d, Blocking> {
+    /// Creates a new TX-only blocking SLINK driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SLINK is not supported.
+    pub fn new_blocking<T: Instance>(_instance: Peri<'d, T>) -> Result<Self, Error> {
+        Self::new_inner::<T>(None)
+    }
+}
+
+impl<'d> SlinkTx<'d, Async> {
+    async fn wait_fifo_nfull(&mut self) {
+        poll_fn(|cx| {
+            self.info.tx_waker.register(cx.waker());
+            if !self.fifo_full() {
+                Poll::Ready(())
+            } else {
+                // CS used here since interrupt modifies register
+                critical_section::with(|_| {
+                    self.info
+                        .reg
+                        .ctrl()
+                        .modify(|_, w| w.slink_ctrl_irq_tx_nfull().set_bit())
+                });
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    async fn write_chunk(&mut self, chunk: &[u32]) -> Result<(), Error> {
+        // If DMA available, use it to transfer data from buffer to TX FIFO
+        if let Some(dma) = &mut self.dma {
+            let dst = self.info.reg.data().as_ptr();
+            // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
+            let dst = unsafe { dst.as_mut().unwrap_unchecked() };
+            dma.write(chunk, dst, false).await.map_err(Error::Dma)?;
+
+        // Otherwise, manually write each word to TX FIFO
+        } else {
+            for &word in chunk {
+                self.write_inner(word, false);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn new_async_inner<T: Instance>(
+        _instance: Peri<'d, T>,
+        dma: Option<Dma<'d>>,
+    ) -> Result<Self, Error> {
+        let slink = Self::new_inner::<T>(dma)?;
+        // SAFETY: It is valid to enable SLINK interrupt here
+        unsafe { T::Interrupt::enable() }
+        Ok(slink)
+    }
+
+    /// Creates a new TX-only async SLINK driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SLINK is not supported.
+    pub fn new_async<T: Instance>(
+        _instance: Peri<'d, T>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_async_inner(_instance, None)
+    }
+
+    /// Creates a new TX-only async SLINK driver.
+    ///
+    /// Additionally provides the DMA peripheral for transfers.
+    ///
+    /// **Note**: The DMA peripheral is limited in that it is single-channel only so you have to
+    /// decide which peripheral (if any) will use it. Without DMA, the driver will manually
+    /// copy each word into the FIFO. However, depending on the configured FIFO size and how many
+    /// words you are expecting to transfer, this may be more efficient as there is overhead in
+    /// setting up the DMA transfer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if SLINK is not supported.
+    ///
+    /// Returns [`Error::Dma`] if DMA is not supported.
+    pub fn new_async_with_dma<T: Instance, D: dma::Instance>(
+        _instance: Peri<'d, T>,
+        dma: Peri<'d, D>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>>
+        + Binding<D::Interrupt, dma::InterruptHandler<D>>
+        + 'd,
+    ) -> Result<Self, Error> {
+        let dma = dma::Dma::new(dma, _irq).map_err(Error::Dma)?;
+        Self::new_async_inner(_instance, Some(dma))
+    }
+
+    /// Writes a word to the TX link.
+    ///
+    /// Marks the word as the end of a packet if `last` is true.
+    pub async fn write_word(&mut self, word: u32, last: bool) {
+        self.wait_fifo_nfull().await;
+        self.write_inner(word, last);
+    }
+
+    /// Writes all words to the TX link without marking the end of a packet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Dma`] if DMA error occurred during transfer.
+    pub async fn write(&mut self, words: &[u32]) -> Result<(), Error> {
+        let mut chunks = words.chunks_exact(self.fifo_depth());
+
+        // For chunks that match the FIFO depth, we can wait for FIFO empty
+        // then fill the entire FIFO in one shot
+        for chunk in chunks.by_ref() {
+            self.flush().await;
+            self.write_chunk(chunk).await?;
+        }
+
+        // But for the remainder, just write each word as soon as there is room
+        for &word in chunks.remainder() {
+            self.write_word(word, false).await;
+        }
+
+        Ok(())
+    }
+
+    /// Writes all words as a single packet to the TX link.
+    ///
+    /// The final word is marked as the end of the packet. Nothing is sent if `words` is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Dma`] if DMA error occurred during transfer.
+    pub async fn write_packet(&mut self, words: &[u32]) -> Result<(), Error> {
+        if let Some((&last, body)) = words.split_last() {
+            self.write(body).await?;
+            self.write_word(last, true).await;
+        }
+
+        Ok(())
+    }
+
+    /// Waits until all words in the TX FIFO have been sent.
+    pub async fn flush(&mut self) {
+        poll_fn(|cx| {
+            self.info.tx_waker.register(cx.waker());
+            if self.fifo_empty() {
+                Poll::Ready(())
+            } else {
+                // CS used here since interrupt modifies register
+                critical_section::with(|_| {
+                    self.info
+                        .reg
+                        .ctrl()
+                        .modify(|_, w| w.slink_ctrl_irq_tx_empty().set_bit())
+                });
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<'d, M: IoMode> Drop for SlinkTx<'d, M> {
+    fn drop(&mut self) {
+        self.info.active.tx.store(false, Ordering::Release);
+        drop_slink(&self.info);
+    }
+}
+
+fn drop_slink(info: &Info) {
+    // Only disable SLINK if both Rx and Tx have been dropped
+    critical_section::with(|_| {
+        if !info.active.rx.load(Ordering::Acquire) && !info.active.tx.load(Ordering::Acquire) {
+            info.reg.ctrl().modify(|_, w| w.slink_ctrl_en().clear_bit());
+        }
+    })
+}
+
+// Serves as a "reference-counter" so we know when Slink is completely dropped
+// Use two AtomicBools instead of AtomicU8 since fetch_add/fetch_sub are not available without A extension
+struct Active {
+    rx: AtomicBool,
+    tx: AtomicBool,
+}
+
+impl Active {
+    const fn new() -> Self {
+        Self {
+            rx: AtomicBool::new(false),
+            tx: AtomicBool::new(false),
+        }
+    }
+}
+
+struct Info {
+    reg: &'static crate::pac::slink::RegisterBlock,
+    active: &'static Active,
+    rx_waker: &'static AtomicWaker,
+    tx_waker: &'static AtomicWaker,
+}
+
+trait SealedIoMode {}
+
+/// SLINK IO mode.
+#[allow(private_bounds)]
+pub trait IoMode: SealedIoMode {}
+
+/// Blocking SLINK.
+pub struct Blocking;
+impl SealedIoMode for Blocking {}
+impl IoMode for Blocking {}
+
+/// Async SLINK.
+pub struct Async;
+impl SealedIoMode for Async {}
+impl IoMode for Async {}
+
+trait SealedInstance {
+    fn info() -> Info;
+}
+
+/// A valid SLINK peripheral.
+#[allow(private_bounds)]
+pub trait Instance: SealedInstance + PeripheralType {
+    type Interrupt: Interrupt;
+}
+
+impl SealedInstance for SLINK {
+    fn info() -> Info {
+        static RX_WAKER: AtomicWaker = AtomicWaker::new();
+        static TX_WAKER: AtomicWaker = AtomicWaker::new();
+        static ACTIVE: Active = Active::new();
+
+        Info {
+            // SAFETY: We are the sole users of the pointer and are sure to use it safely
+            reg: unsafe { &*crate::pac::Slink::ptr() },
+            active: &ACTIVE,
+            rx_waker: &RX_WAKER,
+            tx_waker: &TX_WAKER,
+        }
+    }
+}
+impl Instance for SLINK {
+    type Interrupt = crate::interrupt::typelevel::SLINK;
+}
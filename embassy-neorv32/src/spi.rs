@@ -2,17 +2,25 @@
 use crate::dma;
 use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
 use crate::peripherals::SPI;
+use core::cell::RefCell;
 use core::future::poll_fn;
 use core::marker::PhantomData;
 use core::task::Poll;
 use embassy_hal_internal::drop::OnDrop;
 use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::blocking_mutex::Mutex as BlockingMutex;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::Mutex;
 use embassy_sync::waitqueue::AtomicWaker;
-pub use embedded_hal_1::spi::{MODE_0, MODE_1, MODE_2, MODE_3, Mode, Phase, Polarity};
+use embedded_hal_1::delay::DelayNs;
+pub use embedded_hal_1::spi::{MODE_0, MODE_1, MODE_2, MODE_3, Mode, Operation, Phase, Polarity};
 
 // Dummy value for writes when we are only interested in the read value
 const DUMMY: u8 = 0xFF;
 
+// Set in a CS command to assert the selected line, cleared to deassert all lines
+const CS_EN: u8 = 1 << 3;
+
 /// SPI interrupt handler binding.
 pub struct InterruptHandler<T: Instance> {
     _phantom: PhantomData<T>,
@@ -34,13 +42,64 @@ pub enum Error {
     NotSupported,
     /// A DMA bus error occurred.
     DmaBusError,
+    /// The requested SPI clock frequency is out of range, or the closest frequency derivable from
+    /// the CPU clock deviates from it by more than 10%.
+    InvalidFrequency,
+}
+
+/// SPI configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// SPI clock frequency in Hz.
+    ///
+    /// When read back via [`Spi::config`], this is the frequency actually achieved.
+    pub frequency: u32,
+    /// SPI clock polarity and phase.
+    pub mode: Mode,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            frequency: 1_000_000,
+            mode: MODE_0,
+        }
+    }
+}
+
+/// Hardware chip-select line driven by the SPI controller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChipSelect {
+    /// Chip-select line 0.
+    Cs0,
+    /// Chip-select line 1.
+    Cs1,
+    /// Chip-select line 2.
+    Cs2,
+    /// Chip-select line 3.
+    Cs3,
+    /// Chip-select line 4.
+    Cs4,
+    /// Chip-select line 5.
+    Cs5,
+    /// Chip-select line 6.
+    Cs6,
+    /// Chip-select line 7.
+    Cs7,
+}
+
+impl From<ChipSelect> for u8 {
+    fn from(cs: ChipSelect) -> Self {
+        cs as u8
+    }
 }
 
 /// Serial Peripheral Interface (SPI) Driver.
 pub struct Spi<'d, M: IoMode> {
     reg: &'static crate::pac::spi::RegisterBlock,
     waker: &'static AtomicWaker,
-    dma: Option<dma::Dma<'d>>,
+    dma: Option<dma::Channel<'d>>,
     _phantom: PhantomData<&'d M>,
 }
 
@@ -74,6 +133,15 @@ impl<'d, M: IoMode> Spi<'d, M> {
         self.reg.data().read().spi_data().bits()
     }
 
+    // This is mainly used in the OnDrop closure, so it can't take a reference to self
+    // Therefore it takes a reference to the register block instead
+    fn write_cmd(reg: &'static crate::pac::spi::RegisterBlock, cmd: u8) {
+        while reg.ctrl().read().spi_ctrl_tx_full().bit_is_set() {}
+        // SAFETY: We ensure command bit is set and any CS command we write is valid
+        reg.data()
+            .write(|w| unsafe { w.spi_data_cmd().set_bit().spi_data().bits(cmd) });
+    }
+
     fn write_byte(&mut self, byte: u8) {
         // SAFETY: We ensure data bit is cleared and any value we drite to data is valid
         self.reg
@@ -93,66 +161,79 @@ impl<'d, M: IoMode> Spi<'d, M> {
         }
     }
 
-    fn new_inner<T: Instance>(
-        _instance: Peri<'d, T>,
-        spi_freq: u32,
-        mode: Mode,
-    ) -> Result<Self, Error> {
+    fn apply_config(&mut self, config: &Config) -> Result<(), Error> {
+        let cpu_freq = crate::sysinfo::SysInfo::clock_freq();
+        let divider = crate::clock::solve(cpu_freq, config.frequency, &crate::clock::SPI)
+            .map_err(|_| Error::InvalidFrequency)?;
+
+        // Set clock phase and polarity, prescaler and divider
+        // SAFETY: The solver ensures psc is valid and cdiv can fit in 4 bits
+        self.reg.ctrl().modify(|_, w| unsafe {
+            w.spi_ctrl_cpol()
+                .bit(config.mode.polarity == Polarity::IdleHigh)
+                .spi_ctrl_cpha()
+                .bit(config.mode.phase == Phase::CaptureOnSecondTransition)
+                .spi_ctrl_prsc()
+                .bits(divider.prsc_sel)
+                .spi_ctrl_cdiv()
+                .bits(divider.div as u8 - 1)
+        });
+
+        Ok(())
+    }
+
+    fn new_inner<T: Instance>(_instance: Peri<'d, T>, config: Config) -> Result<Self, Error> {
         if !crate::sysinfo::SysInfo::soc_config().has_spi() {
             return Err(Error::NotSupported);
         }
 
-        // Configure clock phase and polarity
-        match mode.polarity {
-            Polarity::IdleLow => T::reg().ctrl().modify(|_, w| w.spi_ctrl_cpol().clear_bit()),
-            Polarity::IdleHigh => T::reg().ctrl().modify(|_, w| w.spi_ctrl_cpol().set_bit()),
+        let mut spi = Self {
+            reg: T::reg(),
+            waker: T::waker(),
+            dma: None,
+            _phantom: PhantomData,
         };
-        match mode.phase {
-            Phase::CaptureOnFirstTransition => {
-                T::reg().ctrl().modify(|_, w| w.spi_ctrl_cpha().clear_bit());
-            }
-            Phase::CaptureOnSecondTransition => {
-                T::reg().ctrl().modify(|_, w| w.spi_ctrl_cpha().set_bit());
-            }
-        }
+        spi.apply_config(&config)?;
 
-        let cpu_freq = crate::sysinfo::SysInfo::clock_freq() as u64;
-        let mut cdiv = (cpu_freq / (2 * spi_freq as u64 * 2)) - 1;
-        let mut psc = 0;
+        // Enable SPI
+        T::reg().ctrl().modify(|_, w| w.spi_ctrl_en().set_bit());
 
-        // Calculate prescaler and divider similar to UART
-        // See: https://github.com/stnolting/neorv32/blob/main/sw/lib/source/neorv32_uart.c#L47
-        // Revisit: There are some ways to improve this and make it more accurate
-        while cdiv > 0xf {
-            if psc == 2 || psc == 4 {
-                cdiv >>= 3;
-            } else {
-                cdiv >>= 1;
-            }
-            psc += 1;
-        }
+        Ok(spi)
+    }
 
-        // Set clock prescaler
-        // SAFETY: We are writing a valid psc value
-        T::reg()
-            .ctrl()
-            .modify(|_, w| unsafe { w.spi_ctrl_prsc().bits(psc) });
+    /// Reconfigures the SPI bus, waiting for any transfer in progress to complete first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFrequency`] if the frequency can't be derived from the CPU clock,
+    /// in which case the previous configuration is kept.
+    pub fn set_config(&mut self, config: &Config) -> Result<(), Error> {
+        self.blocking_flush();
+        self.apply_config(config)
+    }
 
-        // Set clock divider
-        // SAFETY: We've ensured cdiv can fit in 4 bits
-        T::reg()
-            .ctrl()
-            .modify(|_, w| unsafe { w.spi_ctrl_cdiv().bits(cdiv as u8) });
+    /// Returns the current configuration, with the SPI clock frequency actually achieved.
+    pub fn config(&self) -> Config {
+        let ctrl = self.reg.ctrl().read();
+        let prsc_sel = ctrl.spi_ctrl_prsc().bits();
+        let cdiv = ctrl.spi_ctrl_cdiv().bits() as u32 + 1;
+        let cpu_freq = crate::sysinfo::SysInfo::clock_freq();
 
-        // Enable SPI
-        T::reg().ctrl().modify(|_, w| w.spi_ctrl_en().set_bit());
+        let polarity = if ctrl.spi_ctrl_cpol().bit_is_set() {
+            Polarity::IdleHigh
+        } else {
+            Polarity::IdleLow
+        };
+        let phase = if ctrl.spi_ctrl_cpha().bit_is_set() {
+            Phase::CaptureOnSecondTransition
+        } else {
+            Phase::CaptureOnFirstTransition
+        };
 
-        Ok(Self {
-            reg: T::reg(),
-            waker: T::waker(),
-            dma: None,
-            _phantom: PhantomData,
-        })
+        Config {
+            frequency: crate::clock::SPI.rate(cpu_freq, prsc_sel, cdiv),
+            mode: Mode { polarity, phase },
+        }
     }
 
     /// Perform a blocking read on the SPI bus.
@@ -217,36 +298,83 @@ impl<'d, M: IoMode> Spi<'d, M> {
     pub fn blocking_flush(&self) {
         while self.busy() {}
     }
+
+    /// Returns true if any hardware chip-select line is currently asserted.
+    pub fn is_cs_active(&self) -> bool {
+        self.reg.ctrl().read().spi_cs_active().bit_is_set()
+    }
+
+    /// Asserts the given hardware chip-select line, blocking until it takes effect.
+    ///
+    /// Only one line can be asserted at a time, so any other line is deasserted.
+    pub fn blocking_cs_assert(&mut self, cs: ChipSelect) {
+        Self::write_cmd(self.reg, CS_EN | u8::from(cs));
+        self.blocking_flush();
+    }
+
+    /// Deasserts all hardware chip-select lines, blocking until it takes effect.
+    pub fn blocking_cs_deassert(&mut self) {
+        Self::write_cmd(self.reg, 0);
+        self.blocking_flush();
+    }
+
+    /// Perform given list of blocking operations with the given hardware chip-select line
+    /// asserted for the duration.
+    ///
+    /// Chip-select is controlled through the data FIFO, so it is asserted right before the first
+    /// byte is shifted out and deasserted right after the last.
+    pub fn blocking_transaction(&mut self, cs: ChipSelect, operations: &mut [Operation<'_, u8>]) {
+        self.blocking_cs_assert(cs);
+
+        for operation in operations {
+            match operation {
+                Operation::Read(data) => self.blocking_read(data),
+                // Regular writes reset the controller to clear the RX FIFO, which would also
+                // deassert CS, so instead let transfer discard what is read
+                Operation::Write(data) => self.blocking_transfer(&mut [], data),
+                Operation::Transfer(read, write) => self.blocking_transfer(read, write),
+                Operation::TransferInPlace(data) => self.blocking_transfer_in_place(data),
+                Operation::DelayNs(ns) => {
+                    self.blocking_flush();
+                    riscv::delay::McycleDelay::new(crate::sysinfo::SysInfo::clock_freq())
+                        .delay_ns(*ns);
+                }
+            }
+        }
+
+        self.blocking_cs_deassert();
+    }
 }
 
 impl<'d> Spi<'d, Blocking> {
-    /// Returns a new instance of a blocking SPI driver with given frequency (in Hz) and mode.
+    /// Returns a new instance of a blocking SPI driver with given configuration.
     ///
     /// # Errors
     ///
-    /// Returns [`Error::NotSupported`] if SPI is not supported.
+    /// Returns [`Error::NotSupported`] if SPI is not supported,
+    /// or [`Error::InvalidFrequency`] if the configured frequency can't be achieved.
     pub fn new_blocking<T: Instance>(
         _instance: Peri<'d, T>,
-        spi_freq: u32,
-        mode: Mode,
+        config: Config,
     ) -> Result<Self, Error> {
-        Self::new_inner(_instance, spi_freq, mode)
+        Self::new_inner(_instance, config)
     }
 }
 
 impl<'d> Spi<'d, Async> {
     async fn read_chunk(&mut self, chunk: &mut [u8]) -> Result<(), Error> {
+        let src = self.reg.data().as_ptr() as *const u8;
+        // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
+        let src = unsafe { src.as_ref().unwrap_unchecked() };
+
         // If DMA available, use it to transfer data from RX FIFO to buffer
-        if let Some(dma) = &mut self.dma {
-            let src = self.reg.data().as_ptr() as *const u8;
-            // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
-            let src = unsafe { src.as_ref().unwrap_unchecked() };
-            dma.read(src, chunk, false)
-                .await
-                .map_err(|_| Error::DmaBusError)?;
+        let done = match &mut self.dma {
+            Some(dma) => dma.read(src, chunk).await.map_err(|_| Error::DmaBusError)?,
+            None => false,
+        };
 
-        // Otherwise, manually read each byte into buffer
-        } else {
+        // Otherwise (or if a shared DMA is busy), manually read each byte into buffer
+        if !done {
             for byte in chunk.iter_mut() {
                 *byte = self.read_byte();
             }
@@ -256,17 +384,21 @@ impl<'d> Spi<'d, Async> {
     }
 
     async fn write_chunk(&mut self, chunk: &[u8]) -> Result<(), Error> {
+        let dst = self.reg.data().as_ptr() as *mut u8;
+        // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
+        let dst = unsafe { dst.as_mut().unwrap_unchecked() };
+
         // If DMA available, use it to transfer data from buffer to TX FIFO
-        if let Some(dma) = &mut self.dma {
-            let dst = self.reg.data().as_ptr() as *mut u8;
-            // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
-            let dst = unsafe { dst.as_mut().unwrap_unchecked() };
-            dma.write(chunk, dst, false)
+        let done = match &mut self.dma {
+            Some(dma) => dma
+                .write(chunk, dst)
                 .await
-                .map_err(|_| Error::DmaBusError)?;
+                .map_err(|_| Error::DmaBusError)?,
+            None => false,
+        };
 
-        // Otherwise, manually write each byte to TX FIFO
-        } else {
+        // Otherwise (or if a shared DMA is busy), manually write each byte to TX FIFO
+        if !done {
             for byte in chunk.iter().copied() {
                 self.write_byte(byte);
             }
@@ -275,18 +407,18 @@ impl<'d> Spi<'d, Async> {
         Ok(())
     }
 
-    /// Returns a new instance of an async SPI driver with given frequency (in Hz) and mode.
+    /// Returns a new instance of an async SPI driver with given configuration.
     ///
     /// # Errors
     ///
-    /// Returns [`Error::NotSupported`] if SPI is not supported.
+    /// Returns [`Error::NotSupported`] if SPI is not supported,
+    /// or [`Error::InvalidFrequency`] if the configured frequency can't be achieved.
     pub fn new_async<T: Instance>(
         _instance: Peri<'d, T>,
-        spi_freq: u32,
-        mode: Mode,
+        config: Config,
         _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
     ) -> Result<Self, Error> {
-        Self::new_inner(_instance, spi_freq, mode)
+        Self::new_inner(_instance, config)
     }
 
     /// Perform a read on the SPI bus.
@@ -379,6 +511,66 @@ impl<'d> Spi<'d, Async> {
         Ok(())
     }
 
+    /// Asserts the given hardware chip-select line, waiting until it takes effect.
+    ///
+    /// Only one line can be asserted at a time, so any other line is deasserted.
+    pub async fn cs_assert(&mut self, cs: ChipSelect) {
+        Self::write_cmd(self.reg, CS_EN | u8::from(cs));
+        self.flush().await;
+    }
+
+    /// Deasserts all hardware chip-select lines, waiting until it takes effect.
+    pub async fn cs_deassert(&mut self) {
+        Self::write_cmd(self.reg, 0);
+        self.flush().await;
+    }
+
+    /// Perform given list of async operations with the given hardware chip-select line
+    /// asserted for the duration.
+    ///
+    /// Chip-select is controlled through the data FIFO, so it is asserted right before the first
+    /// byte is shifted out and deasserted right after the last. It is also deasserted if the
+    /// transaction fails or is cancelled.
+    ///
+    /// **Note**: [`Operation::DelayNs`] is implemented as a busy-wait.
+    ///
+    /// # Errors
+    ///
+    /// Returns [Error::DmaBusError] if DMA transfer fails.
+    pub async fn transaction(
+        &mut self,
+        cs: ChipSelect,
+        operations: &mut [Operation<'_, u8>],
+    ) -> Result<(), Error> {
+        let reg = self.reg;
+        let drop_guard = OnDrop::new(|| {
+            Self::write_cmd(reg, 0);
+            while reg.ctrl().read().spi_ctrl_busy().bit_is_set() {}
+        });
+
+        self.cs_assert(cs).await;
+
+        for operation in operations {
+            match operation {
+                Operation::Read(data) => self.read(data).await?,
+                // Regular writes reset the controller to clear the RX FIFO, which would also
+                // deassert CS, so instead let transfer discard what is read
+                Operation::Write(data) => self.transfer(&mut [], data).await?,
+                Operation::Transfer(read, write) => self.transfer(read, write).await?,
+                Operation::TransferInPlace(data) => self.transfer_in_place(data).await?,
+                Operation::DelayNs(ns) => {
+                    self.flush().await;
+                    riscv::delay::McycleDelay::new(crate::sysinfo::SysInfo::clock_freq())
+                        .delay_ns(*ns);
+                }
+            }
+        }
+
+        drop_guard.defuse();
+        self.cs_deassert().await;
+        Ok(())
+    }
+
     /// Waits until the SPI bus is idle and all transfers have completed.
     pub async fn flush(&mut self) {
         poll_fn(|cx| {
@@ -400,19 +592,131 @@ impl<'d> Spi<'d, Async> {
     /// It can later be retrieved via [Self::take_dma] for use with other peripherals.
     ///
     /// This is for flexibility purposes as there is only one DMA channel available.
+    /// To share it with other peripherals instead, see [Self::give_shared_dma].
     /// If no DMA is provided, data must be manually copied to/from FIFOs.
     ///
     /// However, for small FIFO depths, and/or small transfer sizes,
     /// this would be more efficient as there is overhead in setting up the DMA transfer.
     pub fn give_dma(&mut self, dma: dma::Dma<'d>) {
-        let _ = self.dma.replace(dma);
+        let _ = self.dma.replace(dma::Channel::Exclusive(dma));
+    }
+
+    /// Gives the SPI driver a channel of a shared DMA controller.
+    ///
+    /// Unlike [Self::give_dma], this lets other peripherals use the DMA between SPI transfers.
+    /// If the channel was created with [`dma::OnBusy::Pio`], chunks are copied manually to/from
+    /// FIFOs whenever the DMA is in use elsewhere.
+    pub fn give_shared_dma(&mut self, dma: dma::SharedChannel<'d>) {
+        let _ = self.dma.replace(dma::Channel::Shared(dma));
     }
 
     /// Retrieves the DMA controller if available, allowing it to be used by other peripherals again.
     ///
     /// See [Self::give_dma] for the implications of this.
+    ///
+    /// Returns `None` if no DMA controller was given. A shared DMA channel is simply removed,
+    /// since the controller is still owned by its [`dma::SharedDma`].
     pub fn take_dma(&mut self) -> Option<dma::Dma<'d>> {
-        self.dma.take()
+        match self.dma.take() {
+            Some(dma::Channel::Exclusive(dma)) => Some(dma),
+            _ => None,
+        }
+    }
+}
+
+/// A device on a shared async SPI bus, selected by one of the controller's hardware chip-selects.
+///
+/// Several devices can share one [`Spi`] through an async [`Mutex`], with each device
+/// locking the bus for the duration of a transaction.
+///
+/// For blocking use, see [`BlockingSpiDevice`] instead.
+pub struct SpiDevice<'a, 'd, M: RawMutex> {
+    bus: &'a Mutex<M, Spi<'d, Async>>,
+    cs: ChipSelect,
+    config: Option<Config>,
+}
+
+impl<'a, 'd, M: RawMutex> SpiDevice<'a, 'd, M> {
+    /// Create a new SPI device on the given shared bus using the given hardware chip-select line.
+    pub fn new(bus: &'a Mutex<M, Spi<'d, Async>>, cs: ChipSelect) -> Self {
+        Self {
+            bus,
+            cs,
+            config: None,
+        }
+    }
+
+    /// Create a new SPI device on the given shared bus using the given hardware chip-select line,
+    /// reconfiguring the bus with `config` before each transaction.
+    ///
+    /// Useful when devices sharing the bus need a different SPI mode or frequency.
+    pub fn new_with_config(
+        bus: &'a Mutex<M, Spi<'d, Async>>,
+        cs: ChipSelect,
+        config: Config,
+    ) -> Self {
+        Self {
+            bus,
+            cs,
+            config: Some(config),
+        }
+    }
+
+    /// Sets the configuration applied to the bus before each transaction of this device.
+    pub fn set_config(&mut self, config: Option<Config>) {
+        self.config = config;
+    }
+
+    /// Returns the hardware chip-select line used by this device.
+    pub fn chip_select(&self) -> ChipSelect {
+        self.cs
+    }
+}
+
+/// A device on a shared blocking SPI bus, selected by one of the controller's hardware chip-selects.
+///
+/// Several devices can share one [`Spi`] through a blocking [`BlockingMutex`], with each device
+/// locking the bus for the duration of a transaction.
+pub struct BlockingSpiDevice<'a, 'd, M: RawMutex, IM: IoMode> {
+    bus: &'a BlockingMutex<M, RefCell<Spi<'d, IM>>>,
+    cs: ChipSelect,
+    config: Option<Config>,
+}
+
+impl<'a, 'd, M: RawMutex, IM: IoMode> BlockingSpiDevice<'a, 'd, M, IM> {
+    /// Create a new SPI device on the given shared bus using the given hardware chip-select line.
+    pub fn new(bus: &'a BlockingMutex<M, RefCell<Spi<'d, IM>>>, cs: ChipSelect) -> Self {
+        Self {
+            bus,
+            cs,
+            config: None,
+        }
+    }
+
+    /// Create a new SPI device on the given shared bus using the given hardware chip-select line,
+    /// reconfiguring the bus with `config` before each transaction.
+    ///
+    /// Useful when devices sharing the bus need a different SPI mode or frequency.
+    pub fn new_with_config(
+        bus: &'a BlockingMutex<M, RefCell<Spi<'d, IM>>>,
+        cs: ChipSelect,
+        config: Config,
+    ) -> Self {
+        Self {
+            bus,
+            cs,
+            config: Some(config),
+        }
+    }
+
+    /// Sets the configuration applied to the bus before each transaction of this device.
+    pub fn set_config(&mut self, config: Option<Config>) {
+        self.config = config;
+    }
+
+    /// Returns the hardware chip-select line used by this device.
+    pub fn chip_select(&self) -> ChipSelect {
+        self.cs
     }
 }
 
@@ -482,6 +786,23 @@ impl<'d, M: IoMode> embedded_hal_02::blocking::spi::Write<u8> for Spi<'d, M> {
     }
 }
 
+impl<'d, M: IoMode> embassy_embedded_hal::SetConfig for Spi<'d, M> {
+    type Config = Config;
+    type ConfigError = Error;
+
+    fn set_config(&mut self, config: &Self::Config) -> Result<(), Self::ConfigError> {
+        self.set_config(config)
+    }
+}
+
+impl<'d, M: IoMode> embassy_embedded_hal::GetConfig for Spi<'d, M> {
+    type Config = Config;
+
+    fn get_config(&self) -> Self::Config {
+        self.config()
+    }
+}
+
 impl embedded_hal_1::spi::Error for Error {
     fn kind(&self) -> embedded_hal_1::spi::ErrorKind {
         embedded_hal_1::spi::ErrorKind::Other
@@ -541,3 +862,42 @@ impl<'d> embedded_hal_async::spi::SpiBus<u8> for Spi<'d, Async> {
         Ok(())
     }
 }
+
+impl<'a, 'd, M: RawMutex> embedded_hal_1::spi::ErrorType for SpiDevice<'a, 'd, M> {
+    type Error = Error;
+}
+
+impl<'a, 'd, M: RawMutex> embedded_hal_async::spi::SpiDevice<u8> for SpiDevice<'a, 'd, M> {
+    async fn transaction(
+        &mut self,
+        operations: &mut [Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        let mut bus = self.bus.lock().await;
+        if let Some(config) = &self.config {
+            bus.flush().await;
+            bus.set_config(config)?;
+        }
+        bus.transaction(self.cs, operations).await
+    }
+}
+
+impl<'a, 'd, M: RawMutex, IM: IoMode> embedded_hal_1::spi::ErrorType
+    for BlockingSpiDevice<'a, 'd, M, IM>
+{
+    type Error = Error;
+}
+
+impl<'a, 'd, M: RawMutex, IM: IoMode> embedded_hal_1::spi::SpiDevice<u8>
+    for BlockingSpiDevice<'a, 'd, M, IM>
+{
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        self.bus.lock(|bus| {
+            let mut bus = bus.borrow_mut();
+            if let Some(config) = &self.config {
+                bus.set_config(config)?;
+            }
+            bus.blocking_transaction(self.cs, operations);
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,266 @@
+//! GPTMR Time Driver
+//!
+//! Uses two GPTMR slices to manage time, for NEORV32 configurations which do not implement CLINT.
+//! This is intended to work on both a single-hart and dual-hart configuration.
+//!
+//! Slice 0 runs in continuous mode as a free-running 32-bit counter, which is extended to 64 bits
+//! by counting the number of times it has overflowed. Slice 1 is used in one-shot mode to arm alarms.
+//!
+//! The GPTMR prescaler is chosen such that the timer tick rate matches the `embassy-time` tick rate,
+//! so the configured tick rate must be the CPU clock frequency divided by one of the supported
+//! prescalers (2, 4, 8, 64, 128, 1024, 2048 or 4096).
+//!
+//! In the case of dual-hart, hart 0 will always be the owner of time-keeping,
+//! and is solely responsible for handling timer interrupts and waking tasks
+//! as appropriate on both harts' executors.
+use crate::pwm::ClkPrsc;
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_time_driver::Driver;
+use embassy_time_queue_utils::Queue;
+
+const COUNTER_SLICE: usize = 0;
+const ALARM_SLICE: usize = 1;
+const COUNTER_MASK: u16 = 1 << COUNTER_SLICE;
+const ALARM_MASK: u16 = 1 << ALARM_SLICE;
+
+embassy_time_driver::time_driver_impl!(static DRIVER: GptmrDriver = GptmrDriver {
+    period: AtomicU32::new(0),
+    wrap_pending: AtomicBool::new(false),
+    queue: Mutex::new(RefCell::new(Queue::new()))
+});
+
+#[riscv_rt::core_interrupt(crate::pac::interrupt::CoreInterrupt::GPTMR)]
+fn gptmr_handler() {
+    DRIVER.on_interrupt()
+}
+
+struct GptmrDriver {
+    // Number of times the counter slice has overflowed
+    period: AtomicU32,
+    // Set when the overflow interrupt was serviced before the counter actually wrapped
+    wrap_pending: AtomicBool,
+    queue: Mutex<CriticalSectionRawMutex, RefCell<Queue>>,
+}
+
+impl GptmrDriver {
+    fn on_interrupt(&self) {
+        critical_section::with(|cs| {
+            let pending = gptmr().csr1().read().irq().bits();
+
+            // SAFETY: Register is write 0 to clear, so we bitwise not `pending` to clear only those
+            gptmr()
+                .csr1()
+                .modify(|_, w| unsafe { w.irq().bits(!pending) });
+
+            // Only this handler modifies the period, and it does so inside a critical section,
+            // so a plain load/store is fine here (no need for the A extension)
+            let mut period = self.period.load(Ordering::Relaxed);
+            let mut wrap_pending = self.wrap_pending.load(Ordering::Relaxed);
+
+            // A deferred wrap has happened once the counter has left `u32::MAX`, and certainly
+            // has if the counter has since overflowed again
+            let overflowed = (pending & COUNTER_MASK) != 0;
+            if wrap_pending && (overflowed || read_counter() != u32::MAX) {
+                period = period.wrapping_add(1);
+                wrap_pending = false;
+            }
+
+            // The interrupt is raised as soon as the counter matches the threshold, which is
+            // one timer tick before it actually wraps. Rather than waiting for the wrap, defer
+            // the bump until the next time this handler runs, which `now` accounts for
+            if overflowed {
+                if read_counter() == u32::MAX {
+                    wrap_pending = true;
+                } else {
+                    period = period.wrapping_add(1);
+                }
+            }
+
+            self.period.store(period, Ordering::Relaxed);
+            self.wrap_pending.store(wrap_pending, Ordering::Relaxed);
+
+            if (pending & ALARM_MASK) != 0 {
+                let mut queue = self.queue.borrow(cs).borrow_mut();
+
+                let mut next = queue.next_expiration(self.now());
+                while !self.set_alarm(next) {
+                    next = queue.next_expiration(self.now());
+                }
+            }
+        });
+    }
+
+    fn set_alarm(&self, ts: u64) -> bool {
+        let now = self.now();
+
+        // Timestamp is in the past, so can't set the alarm
+        if ts <= now {
+            false
+        // Nothing left in the queue, so just stop the alarm slice
+        } else if ts == u64::MAX {
+            disarm_alarm();
+            true
+        // Otherwise try to set the alarm but double check the ts isn't in the past again
+        //
+        // If the alarm is further out than the slice can count, it will fire early and
+        // we will simply re-arm it for the remaining ticks in the interrupt handler
+        } else {
+            let ticks = (ts - now).min(u32::MAX as u64) as u32;
+            arm_alarm(ticks);
+            ts > self.now()
+        }
+    }
+}
+
+pub(crate) fn init() {
+    // GPTMR is used for both the counter and alarms which is necessary for time keeping
+    if !crate::sysinfo::SysInfo::soc_config().has_gptmr() {
+        panic!("GPTMR must be supported for time-driver-gptmr to work");
+    }
+
+    // Ensure only hart 0 initializes time-driver
+    assert_eq!(riscv::register::mhartid::read(), 0);
+
+    // Find the prescaler which makes the GPTMR tick at the embassy-time tick rate
+    let cpu_freq = crate::sysinfo::SysInfo::clock_freq() as u64;
+    let clkprsc = [
+        ClkPrsc::_2,
+        ClkPrsc::_4,
+        ClkPrsc::_8,
+        ClkPrsc::_64,
+        ClkPrsc::_128,
+        ClkPrsc::_1024,
+        ClkPrsc::_2048,
+        ClkPrsc::_4096,
+    ]
+    .into_iter()
+    .find(|clkprsc| cpu_freq == embassy_time_driver::TICK_HZ * u16::from(*clkprsc) as u64)
+    .expect("embassy-time tick rate must be CPU clock frequency divided by a GPTMR prescaler");
+
+    // Stop all slices, then set prescaler and clear any stale pending interrupts
+    // SAFETY: Disabling every slice and writing a valid prescaler is always valid
+    gptmr().csr0().write(|w| unsafe { w.bits(0) });
+    gptmr()
+        .csr1()
+        .write(|w| unsafe { w.prsc().bits(clkprsc.bits() as u8) });
+
+    // Setup the counter slice to count through the full 32-bit range and then overflow
+    // SAFETY: Any u32 is a valid threshold and counter value
+    gptmr()
+        .slice(COUNTER_SLICE)
+        .thr()
+        .write(|w| unsafe { w.bits(u32::MAX) });
+    gptmr()
+        .slice(COUNTER_SLICE)
+        .cnt()
+        .write(|w| unsafe { w.bits(0) });
+
+    // Start the counter slice in continuous mode
+    // SAFETY: We are only enabling the counter slice, all other slices are stopped
+    gptmr()
+        .csr0()
+        .write(|w| unsafe { w.enable().bits(COUNTER_MASK).mode().bits(COUNTER_MASK) });
+
+    // SAFETY: It is okay to enable GPTMR interrupts here
+    unsafe { riscv::interrupt::enable_interrupt(crate::pac::interrupt::CoreInterrupt::GPTMR) };
+}
+
+impl Driver for GptmrDriver {
+    fn now(&self) -> u64 {
+        // CS ensures the overflow interrupt can't be serviced while we read the counter
+        critical_section::with(|_| {
+            let mut period = self.period.load(Ordering::Relaxed);
+            let wrap_pending = self.wrap_pending.load(Ordering::Relaxed);
+            let counter = read_counter();
+            let overflow_pending = (gptmr().csr1().read().irq().bits() & COUNTER_MASK) != 0;
+
+            // The interrupt handler defers bumping the period if it ran before the counter
+            // wrapped, so account for that wrap here once it has actually happened
+            if wrap_pending && (overflow_pending || counter != u32::MAX) {
+                period = period.wrapping_add(1);
+            }
+
+            // If the counter has overflowed but the interrupt hasn't been serviced yet
+            // (such as when called from within a critical section), account for it here
+            //
+            // The pending flag is set as soon as the counter matches the threshold, so re-read
+            // the counter since we can't tell if our first read happened before or after overflow.
+            // Until it actually wraps the counter reads `u32::MAX` and still belongs to the
+            // current period
+            let (period, counter) = if overflow_pending {
+                let counter = read_counter();
+                if counter == u32::MAX {
+                    (period, counter)
+                } else {
+                    (period.wrapping_add(1), counter)
+                }
+            } else {
+                (period, counter)
+            };
+
+            ((period as u64) << 32) | counter as u64
+        })
+    }
+
+    fn schedule_wake(&self, at: u64, waker: &core::task::Waker) {
+        critical_section::with(|cs| {
+            let mut queue = self.queue.borrow(cs).borrow_mut();
+            if queue.schedule_wake(at, waker) {
+                let mut next = queue.next_expiration(self.now());
+                while !self.set_alarm(next) {
+                    next = queue.next_expiration(self.now());
+                }
+            }
+        })
+    }
+}
+
+// Only called from within a critical section
+fn arm_alarm(ticks: u32) {
+    disarm_alarm();
+
+    // The slice fires once its counter (starting from 0) reaches the threshold
+    // SAFETY: Any u32 is a valid threshold and counter value
+    gptmr()
+        .slice(ALARM_SLICE)
+        .thr()
+        .write(|w| unsafe { w.bits(ticks.saturating_sub(1)) });
+    gptmr()
+        .slice(ALARM_SLICE)
+        .cnt()
+        .write(|w| unsafe { w.bits(0) });
+
+    // Start the alarm slice in one-shot mode
+    // SAFETY: Bit mask preserves other slice values
+    gptmr().csr0().modify(|r, w| unsafe {
+        w.mode()
+            .bits(r.mode().bits() & !ALARM_MASK)
+            .enable()
+            .bits(r.enable().bits() | ALARM_MASK)
+    });
+}
+
+// Only called from within a critical section
+fn disarm_alarm() {
+    // SAFETY: Bit mask preserves other slice values
+    gptmr()
+        .csr0()
+        .modify(|r, w| unsafe { w.enable().bits(r.enable().bits() & !ALARM_MASK) });
+
+    // SAFETY: Register is write 0 to clear, so only the alarm bit is cleared
+    gptmr()
+        .csr1()
+        .modify(|_, w| unsafe { w.irq().bits(!ALARM_MASK) });
+}
+
+fn read_counter() -> u32 {
+    gptmr().slice(COUNTER_SLICE).cnt().read().bits()
+}
+
+fn gptmr() -> &'static crate::pac::gptmr::RegisterBlock {
+    // SAFETY: The GPTMR peripheral is reserved for the time driver, so we can manage it safely
+    unsafe { &*crate::pac::Gptmr::ptr() }
+}
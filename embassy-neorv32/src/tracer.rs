@@ -0,0 +1,308 @@
+//! Execution Tracer (TRACER)
+//!
+//! The tracer records every non-linear change in control flow (jumps, taken branches, traps)
+//! of a selected hart into a circular trace buffer. Each record holds the source and
+//! destination address of the "delta", which is enough to reconstruct the execution path
+//! afterwards.
+//!
+//! Tracing can either be stopped manually or automatically when the traced hart reaches a
+//! configured stop address, in which case the TRACER interrupt fires.
+use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
+use crate::peripherals::TRACER;
+use core::fmt;
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::task::Poll;
+use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::waitqueue::AtomicWaker;
+
+/// TRACER interrupt handler binding.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        // The stop-address match is latched, so acknowledge it here to prevent a storm
+        T::reg()
+            .ctrl()
+            .modify(|_, w| w.tracer_ctrl_irq_clr().set_bit());
+        T::waker().wake();
+    }
+}
+
+/// TRACER error.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The NEORV32 configuration does not support TRACER.
+    NotSupported,
+}
+
+/// The hart to trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Hart {
+    /// Trace hart 0.
+    Hart0,
+    /// Trace hart 1.
+    ///
+    /// **Note**: Only meaningful if the NEORV32 is configured with dual-core support.
+    Hart1,
+}
+
+/// A single decoded trace record, describing one change in control flow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TraceRecord {
+    /// Address of the instruction that caused the change in control flow.
+    pub src_pc: u32,
+    /// Address execution continued at.
+    pub dst_pc: u32,
+    /// True if the change in control flow was caused by trap entry (exception or interrupt).
+    pub is_trap: bool,
+    /// True if this is the first record captured after tracing was started.
+    pub is_first: bool,
+}
+
+impl TraceRecord {
+    // Instructions are at least 16-bit aligned, so bit 0 of each address is used as a flag
+    fn from_raw(src: u32, dst: u32) -> Self {
+        Self {
+            src_pc: src & !1,
+            dst_pc: dst & !1,
+            is_trap: (dst & 1) != 0,
+            is_first: (src & 1) != 0,
+        }
+    }
+}
+
+impl fmt::Display for TraceRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:08x} -> 0x{:08x}", self.src_pc, self.dst_pc)?;
+        if self.is_trap {
+            f.write_str(" (trap)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a human-readable control-flow history of the given records to `w`, one per line.
+///
+/// Records are expected in the order returned by [`Tracer::drain`] (oldest first).
+///
+/// # Errors
+///
+/// Returns [`fmt::Error`] if writing to `w` fails.
+pub fn write_history<W: fmt::Write>(w: &mut W, records: &[TraceRecord]) -> fmt::Result {
+    for (i, record) in records.iter().enumerate() {
+        if record.is_first {
+            writeln!(w, "--- trace start ---")?;
+        }
+        writeln!(w, "[{i:4}] {record}")?;
+    }
+    Ok(())
+}
+
+/// Execution Tracer (TRACER) driver.
+pub struct Tracer<'d, M: IoMode> {
+    reg: &'static crate::pac::tracer::RegisterBlock,
+    waker: &'static AtomicWaker,
+    _phantom: PhantomData<&'d M>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d, M: IoMode> Send for Tracer<'d, M> {}
+
+impl<'d, M: IoMode> Tracer<'d, M> {
+    fn new_inner<T: Instance>(_instance: Peri<'d, T>) -> Result<Self, Error> {
+        if !crate::sysinfo::SysInfo::soc_config().has_tracer() {
+            return Err(Error::NotSupported);
+        }
+
+        // Reset TRACER and leave it enabled but idle
+        T::reg().ctrl().write(|w| w.tracer_ctrl_en().clear_bit());
+        T::reg()
+            .ctrl()
+            .write(|w| w.tracer_ctrl_en().set_bit().tracer_ctrl_irq_clr().set_bit());
+
+        Ok(Self {
+            reg: T::reg(),
+            waker: T::waker(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the depth of the trace buffer (the maximum number of records it can hold).
+    pub fn buffer_depth(&self) -> usize {
+        // Value in register is log2 of buffer depth
+        1 << self.reg.ctrl().read().tracer_ctrl_tbm().bits()
+    }
+
+    /// Starts tracing the given hart.
+    ///
+    /// The trace buffer is cleared first. If `stop_addr` is given, tracing stops automatically
+    /// once the hart reaches that address, otherwise it runs until [`Self::stop`] is called.
+    /// While running, the buffer only keeps the most recent [`Self::buffer_depth`] records.
+    pub fn start(&mut self, hart: Hart, stop_addr: Option<u32>) {
+        // Toggling enable resets the module, which also discards any previous trace data
+        self.reg.ctrl().write(|w| w.tracer_ctrl_en().clear_bit());
+        self.reg.ctrl().write(|w| {
+            w.tracer_ctrl_en()
+                .set_bit()
+                .tracer_ctrl_hsel()
+                .bit(hart == Hart::Hart1)
+                .tracer_ctrl_irq_clr()
+                .set_bit()
+        });
+
+        // SAFETY: Any address is valid, with zero disabling auto-stop
+        self.reg
+            .stop_addr()
+            .write(|w| unsafe { w.bits(stop_addr.unwrap_or(0)) });
+
+        self.reg
+            .ctrl()
+            .modify(|_, w| w.tracer_ctrl_start().set_bit());
+    }
+
+    /// Manually stops tracing.
+    ///
+    /// Captured trace data is retained and can be read with [`Self::drain`].
+    pub fn stop(&mut self) {
+        self.reg
+            .ctrl()
+            .modify(|_, w| w.tracer_ctrl_stop().set_bit());
+    }
+
+    /// Returns true if tracing is in progress.
+    pub fn is_running(&self) -> bool {
+        self.reg.ctrl().read().tracer_ctrl_run().bit_is_set()
+    }
+
+    /// Returns true if there is trace data available to be read.
+    pub fn data_available(&self) -> bool {
+        self.reg.ctrl().read().tracer_ctrl_avail().bit_is_set()
+    }
+
+    /// Blocks until tracing has stopped.
+    ///
+    /// **Note**: If no stop address was given, this blocks forever.
+    pub fn blocking_wait_stopped(&self) {
+        while self.is_running() {}
+    }
+
+    /// Reads decoded trace records from the buffer into `records`, oldest first.
+    ///
+    /// Returns the number of records read, which is less than `records.len()` if the
+    /// buffer ran empty. Reading while tracing is still in progress is allowed.
+    pub fn drain(&mut self, records: &mut [TraceRecord]) -> usize {
+        let mut n = 0;
+        for record in records.iter_mut() {
+            if !self.data_available() {
+                break;
+            }
+
+            // Reading the destination pops the entry, so source must be read first
+            let src = self.reg.delta_src().read().bits();
+            let dst = self.reg.delta_dst().read().bits();
+            *record = TraceRecord::from_raw(src, dst);
+            n += 1;
+        }
+        n
+    }
+}
+
+impl<'d> Tracer<'d, Blocking> {
+    /// Create a new instance of a blocking TRACER driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if TRACER is not supported.
+    pub fn new_blocking<T: Instance>(_instance: Peri<'d, T>) -> Result<Self, Error> {
+        Self::new_inner(_instance)
+    }
+}
+
+impl<'d> Tracer<'d, Async> {
+    /// Create a new instance of an async TRACER driver.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if TRACER is not supported.
+    pub fn new_async<T: Instance>(
+        _instance: Peri<'d, T>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        let tracer = Self::new_inner(_instance)?;
+        // SAFETY: It is valid to enable TRACER interrupt here
+        unsafe { T::Interrupt::enable() }
+        Ok(tracer)
+    }
+
+    /// Waits until tracing has stopped.
+    ///
+    /// This is intended to be used with a stop address given to [`Self::start`], where
+    /// the stop-address match interrupt wakes the task. If no stop address was given,
+    /// this waits forever.
+    pub async fn wait_stopped(&mut self) {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            if self.is_running() {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await
+    }
+}
+
+impl<'d, M: IoMode> Drop for Tracer<'d, M> {
+    fn drop(&mut self) {
+        // Disable TRACER
+        self.reg.ctrl().write(|w| w.tracer_ctrl_en().clear_bit());
+    }
+}
+
+trait SealedIoMode {}
+
+/// TRACER IO mode.
+#[allow(private_bounds)]
+pub trait IoMode: SealedIoMode {}
+
+/// Blocking TRACER.
+pub struct Blocking;
+impl SealedIoMode for Blocking {}
+impl IoMode for Blocking {}
+
+/// Async TRACER.
+pub struct Async;
+impl SealedIoMode for Async {}
+impl IoMode for Async {}
+
+trait SealedInstance {
+    fn reg() -> &'static crate::pac::tracer::RegisterBlock;
+    fn waker() -> &'static AtomicWaker;
+}
+
+/// A valid TRACER peripheral.
+#[allow(private_bounds)]
+pub trait Instance: SealedInstance + PeripheralType {
+    type Interrupt: Interrupt;
+}
+impl SealedInstance for TRACER {
+    fn reg() -> &'static crate::pac::tracer::RegisterBlock {
+        // SAFETY: This ptr is only used internally and we ensure its used safely
+        unsafe { &*crate::pac::Tracer::ptr() }
+    }
+
+    fn waker() -> &'static AtomicWaker {
+        static WAKER: AtomicWaker = AtomicWaker::new();
+        &WAKER
+    }
+}
+impl Instance for TRACER {
+    type Interrupt = crate::interrupt::typelevel::TRACER;
+}
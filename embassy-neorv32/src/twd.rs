@@ -0,0 +1,573 @@
+//! Two-Wire Device (TWD)
+//!
+//! Allows the NEORV32 to act as an I2C target (device), addressed by an external I2C controller.
+//!
+//! **Note**: The hardware does not stretch the clock, report the R/W bit or signal START/STOP
+//! conditions directly. Instead, bus events are inferred from the FIFOs and the bus busy flag,
+//! so data to be read by the controller must be staged in the TX FIFO ahead of time. Since there
+//! is also no interrupt for the end of a transaction, once a transaction is in progress the busy
+//! flag is re-checked every [`BUSY_POLL_INTERVAL`] (in addition to the FIFO interrupts) to detect
+//! [`Event::Stop`].
+use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
+use crate::peripherals::TWD;
+use core::future::poll_fn;
+use core::marker::PhantomData;
+use core::task::Poll;
+use embassy_futures::select::{Either, select};
+use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::waitqueue::AtomicWaker;
+use embassy_time::{Duration, Timer};
+
+// Largest valid 7-bit device address
+const ADDR_MAX: u8 = 0x7f;
+
+/// Interval at which the bus busy flag is re-checked while a transaction is in progress.
+pub const BUSY_POLL_INTERVAL: Duration = Duration::from_micros(100);
+
+/// TWD interrupt handler binding.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        // We disable the interrupt since it is level triggered and there is no apparent way to acknowledge it
+        T::Interrupt::disable();
+        T::waker().wake();
+    }
+}
+
+/// TWD error.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The NEORV32 configuration does not support TWD.
+    NotSupported,
+    /// The device address is not a valid 7-bit address.
+    InvalidAddress,
+}
+
+/// Bus sampling rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SampleRate {
+    /// Samples the bus at a high rate (CPU clock / 4), for fast bus speeds.
+    High,
+    /// Samples the bus at a low rate (CPU clock / 64), for better noise filtering.
+    Low,
+}
+
+/// A TWD bus event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// The controller addressed us for write and data is waiting in the RX FIFO.
+    ///
+    /// This is reported until the RX FIFO has been drained.
+    AddressedWrite,
+    /// The controller addressed us for read and has consumed all staged TX data.
+    ///
+    /// More data should be staged quickly if the controller is expected to keep reading.
+    AddressedRead,
+    /// The transaction has ended.
+    Stop,
+}
+
+/// Two-Wire Device (TWD) driver.
+pub struct Twd<'d, M: IoMode> {
+    reg: &'static crate::pac::twd::RegisterBlock,
+    waker: &'static AtomicWaker,
+    // Whether data has been staged in the TX FIFO which hasn't yet been reported as consumed
+    staged: bool,
+    // Whether we have observed a transaction which hasn't yet been reported as stopped
+    active: bool,
+    _phantom: PhantomData<&'d M>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d, M: IoMode> Send for Twd<'d, M> {}
+
+impl<'d, M: IoMode> Twd<'d, M> {
+    fn new_inner<T: Instance>(
+        _instance: Peri<'d, T>,
+        addr: u8,
+        sample_rate: SampleRate,
+    ) -> Result<Self, Error> {
+        if !crate::sysinfo::SysInfo::soc_config().has_twd() {
+            return Err(Error::NotSupported);
+        }
+
+        if addr > ADDR_MAX {
+            return Err(Error::InvalidAddress);
+        }
+
+        // Set address and sample rate, enable TWD, and clear any stale FIFO data
+        // SAFETY: We've ensured the address fits in 7 bits
+        T::reg().ctrl().write(|w| unsafe {
+            w.twd_ctrl_dev_addr()
+                .bits(addr)
+                .twd_ctrl_fsel()
+                .bit(sample_rate == SampleRate::Low)
+                .twd_ctrl_en()
+                .set_bit()
+                .twd_ctrl_clr_rx()
+                .set_bit()
+                .twd_ctrl_clr_tx()
+                .set_bit()
+        });
+
+        Ok(Self {
+            reg: T::reg(),
+            waker: T::waker(),
+            staged: false,
+            active: false,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn rx_avail(&self) -> bool {
+        self.reg.ctrl().read().twd_ctrl_rx_avail().bit_is_set()
+    }
+
+    fn tx_empty(&self) -> bool {
+        self.reg.ctrl().read().twd_ctrl_tx_empty().bit_is_set()
+    }
+
+    fn tx_full(&self) -> bool {
+        self.reg.ctrl().read().twd_ctrl_tx_full().bit_is_set()
+    }
+
+    fn busy(&self) -> bool {
+        self.reg.ctrl().read().twd_ctrl_busy().bit_is_set()
+    }
+
+    // Infers the next bus event (if any) from the FIFO and busy flags
+    fn poll_event(&mut self) -> Option<Event> {
+        if self.rx_avail() {
+            self.active = true;
+            Some(Event::AddressedWrite)
+        } else if self.staged && self.tx_empty() {
+            self.staged = false;
+            self.active = true;
+            Some(Event::AddressedRead)
+        } else if self.busy() {
+            self.active = true;
+            None
+        } else if self.active {
+            self.active = false;
+            Some(Event::Stop)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the depth of the RX FIFO.
+    pub fn rx_fifo_depth(&self) -> usize {
+        // Value in register is log2 of fifo depth
+        1 << self.reg.ctrl().read().twd_ctrl_rx_fifo().bits()
+    }
+
+    /// Returns the depth of the TX FIFO.
+    pub fn tx_fifo_depth(&self) -> usize {
+        // Value in register is log2 of fifo depth
+        1 << self.reg.ctrl().read().twd_ctrl_tx_fifo().bits()
+    }
+
+    /// Returns the current state of the SCL and SDA bus lines (`true` if high), in that order.
+    pub fn sense(&self) -> (bool, bool) {
+        let ctrl = self.reg.ctrl().read();
+        (
+            ctrl.twd_ctrl_sense_scl().bit_is_set(),
+            ctrl.twd_ctrl_sense_sda().bit_is_set(),
+        )
+    }
+
+    /// Discards all bytes in the RX FIFO.
+    pub fn clear_rx(&mut self) {
+        // Clear bit auto-clears so we don't need to clear it ourselves
+        self.reg.ctrl().modify(|_, w| w.twd_ctrl_clr_rx().set_bit());
+    }
+
+    /// Discards all staged bytes in the TX FIFO.
+    pub fn clear_tx(&mut self) {
+        // Clear bit auto-clears so we don't need to clear it ourselves
+        self.reg.ctrl().modify(|_, w| w.twd_ctrl_clr_tx().set_bit());
+        self.staged = false;
+    }
+
+    /// Reads a single byte written by the controller if available.
+    pub fn try_read(&mut self) -> Option<u8> {
+        if self.rx_avail() {
+            Some(self.reg.data().read().twi_data().bits())
+        } else {
+            None
+        }
+    }
+
+    /// Reads all bytes currently available in the RX FIFO into the buffer.
+    ///
+    /// Returns the number of bytes read, which may be less than the buffer length.
+    pub fn read(&mut self, data: &mut [u8]) -> usize {
+        let mut n = 0;
+        for byte in data.iter_mut() {
+            match self.try_read() {
+                Some(b) => *byte = b,
+                None => break,
+            }
+            n += 1;
+        }
+        n
+    }
+
+    /// Stages as many bytes as will fit into the TX FIFO, to be sent when the controller reads.
+    ///
+    /// Returns the number of bytes staged.
+    pub fn stage(&mut self, data: &[u8]) -> usize {
+        let mut n = 0;
+        for &byte in data {
+            if self.tx_full() {
+                break;
+            }
+            // SAFETY: Any u8 is valid data
+            self.reg
+                .data()
+                .write(|w| unsafe { w.twi_data().bits(byte) });
+            n += 1;
+        }
+
+        if n > 0 {
+            self.staged = true;
+        }
+        n
+    }
+
+    /// Blocks until the next bus event.
+    pub fn blocking_listen(&mut self) -> Event {
+        loop {
+            if let Some(event) = self.poll_event() {
+                return event;
+            }
+        }
+    }
+}
+
+impl<'d> Twd<'d, Blocking> {
+    /// Returns a new instance of a blocking TWD driver with given 7-bit device address and sample rate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if TWD is not supported.
+    ///
+    /// Returns [`Error::InvalidAddress`] if the address is not a valid 7-bit address.
+    pub fn new_blocking<T: Instance>(
+        _instance: Peri<'d, T>,
+        addr: u8,
+        sample_rate: SampleRate,
+    ) -> Result<Self, Error> {
+        Self::new_inner(_instance, addr, sample_rate)
+    }
+}
+
+impl<'d> Twd<'d, Async> {
+    /// Returns a new instance of an async TWD driver with given 7-bit device address and sample rate.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if TWD is not supported.
+    ///
+    /// Returns [`Error::InvalidAddress`] if the address is not a valid 7-bit address.
+    pub fn new_async<T: Instance>(
+        _instance: Peri<'d, T>,
+        addr: u8,
+        sample_rate: SampleRate,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_inner(_instance, addr, sample_rate)
+    }
+
+    /// Waits for the next bus event.
+    ///
+    /// Data written by the controller and consumption of staged data are interrupt driven,
+    /// but since there is no interrupt for the end of a transaction, once one is in progress the
+    /// busy flag is also re-checked every [`BUSY_POLL_INTERVAL`].
+    ///
+    /// **Note**: A read which consumes only part of the staged data raises no interrupt, so it is
+    /// only noticed if the busy flag is observed while the transaction is in progress.
+    pub async fn listen(&mut self) -> Event {
+        loop {
+            if !self.active {
+                return self.wait_event().await;
+            }
+
+            if let Either::First(event) =
+                select(self.wait_event(), Timer::after(BUSY_POLL_INTERVAL)).await
+            {
+                return event;
+            }
+        }
+    }
+
+    // Waits for the next event reported by the FIFO interrupts
+    async fn wait_event(&mut self) -> Event {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            if let Some(event) = self.poll_event() {
+                return Poll::Ready(event);
+            }
+
+            // TX empty is only of interest if something is staged, otherwise it would fire constantly
+            let staged = self.staged;
+            self.reg.ctrl().modify(|_, w| {
+                w.twd_ctrl_irq_rx_avail()
+                    .set_bit()
+                    .twd_ctrl_irq_tx_empty()
+                    .bit(staged)
+            });
+
+            // SAFETY: It is valid to enable interrupts here, since it is level triggered
+            // and if data arrives or is consumed between the above check and here, we won't miss it
+            unsafe { crate::enable_periph_irq!(TWD) }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+impl<'d, M: IoMode> Drop for Twd<'d, M> {
+    fn drop(&mut self) {
+        self.reg.ctrl().modify(|_, w| w.twd_ctrl_en().clear_bit());
+    }
+}
+
+/// Result of a single transaction served by a [`RegisterBank`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BankAccess {
+    /// Register pointer at the start of the data phase of the transaction.
+    pub start: usize,
+    /// Number of registers written by the controller (starting at `start`, wrapping).
+    pub written: usize,
+    /// Number of registers read by the controller.
+    pub read: usize,
+}
+
+/// Emulates the register map of a typical I2C EEPROM/sensor.
+///
+/// The first byte of every write sets the register pointer, and subsequent bytes are written to
+/// consecutive registers. Reads return consecutive registers starting at the register pointer.
+/// The pointer auto-increments after every access and wraps around at the end of the bank.
+///
+/// Consecutive registers are staged ahead of time to fill the TX FIFO, so the controller can read
+/// up to a FIFO depth of registers in a burst before the FIFO needs to be refilled.
+///
+/// **Note**: Since registers are staged ahead of time, the application should only modify them
+/// between transactions to ensure the controller reads the latest values.
+pub struct RegisterBank<'b> {
+    regs: &'b mut [u8],
+    ptr: usize,
+    // Next register to be staged in the TX FIFO
+    next: usize,
+}
+
+impl<'b> RegisterBank<'b> {
+    /// Creates a new register bank backed by the given registers, with the pointer at register 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bank is empty or has more than 256 registers (since the pointer is a single byte).
+    pub fn new(regs: &'b mut [u8]) -> Self {
+        assert!(!regs.is_empty() && regs.len() <= 256);
+        Self {
+            regs,
+            ptr: 0,
+            next: 0,
+        }
+    }
+
+    /// Returns the registers.
+    pub fn registers(&self) -> &[u8] {
+        self.regs
+    }
+
+    /// Returns the registers for modification.
+    pub fn registers_mut(&mut self) -> &mut [u8] {
+        self.regs
+    }
+
+    /// Returns the current register pointer.
+    pub fn pointer(&self) -> usize {
+        self.ptr
+    }
+
+    fn advance(&mut self) {
+        self.ptr = (self.ptr + 1) % self.regs.len();
+    }
+
+    // Discards any staged registers and fills the TX FIFO with registers starting at the pointer
+    fn restage<M: IoMode>(&mut self, twd: &mut Twd<'_, M>) {
+        twd.clear_tx();
+        self.next = self.ptr;
+        self.fill(twd);
+    }
+
+    // Tops up the TX FIFO with the registers following those already staged, returning how many
+    // were added. Since the FIFO is always left full, this is also how many the controller read
+    fn fill<M: IoMode>(&mut self, twd: &mut Twd<'_, M>) -> usize {
+        let mut n = 0;
+        while twd.stage(&[self.regs[self.next]]) == 1 {
+            self.next = (self.next + 1) % self.regs.len();
+            n += 1;
+        }
+        n
+    }
+
+    // Accounts for staged registers read by the controller and refills the TX FIFO,
+    // returning the number of registers read
+    fn consume<M: IoMode>(
+        &mut self,
+        twd: &mut Twd<'_, M>,
+        access: &mut Option<BankAccess>,
+    ) -> usize {
+        let n = self.fill(twd);
+        if n > 0 {
+            let access = access.get_or_insert(BankAccess {
+                start: self.ptr,
+                written: 0,
+                read: 0,
+            });
+            access.read += n;
+            self.ptr = (self.ptr + n) % self.regs.len();
+        }
+        n
+    }
+
+    // Handles the given event, returning the completed access on stop
+    fn handle<M: IoMode>(
+        &mut self,
+        twd: &mut Twd<'_, M>,
+        event: Event,
+        access: &mut Option<BankAccess>,
+    ) -> Option<BankAccess> {
+        match event {
+            Event::AddressedWrite => {
+                while let Some(byte) = twd.try_read() {
+                    match access {
+                        // First byte of a write sets the pointer
+                        None => {
+                            self.ptr = byte as usize % self.regs.len();
+                            *access = Some(BankAccess {
+                                start: self.ptr,
+                                written: 0,
+                                read: 0,
+                            });
+                        }
+                        Some(access) => {
+                            self.regs[self.ptr] = byte;
+                            self.advance();
+                            access.written += 1;
+                        }
+                    }
+                }
+
+                // Pointer moved, so the staged registers are stale (for a repeated-start read)
+                self.restage(twd);
+                None
+            }
+            Event::AddressedRead => {
+                self.consume(twd, access);
+                None
+            }
+            Event::Stop => {
+                // The controller may have stopped reading before the TX FIFO was emptied
+                self.consume(twd, access);
+                Some(access.take().unwrap_or(BankAccess {
+                    start: self.ptr,
+                    written: 0,
+                    read: 0,
+                }))
+            }
+        }
+    }
+
+    /// Serves a single transaction, blocking until the controller ends it.
+    pub fn blocking_serve<M: IoMode>(&mut self, twd: &mut Twd<'_, M>) -> BankAccess {
+        self.restage(twd);
+
+        let mut access = None;
+        loop {
+            let event = twd.blocking_listen();
+            if let Some(access) = self.handle(twd, event, &mut access) {
+                return access;
+            }
+        }
+    }
+
+    /// Serves a single transaction, completing when the controller ends it.
+    ///
+    /// Since a read which stops before the TX FIFO is emptied raises no interrupt, the staged
+    /// registers are also checked for consumption every [`BUSY_POLL_INTERVAL`].
+    pub async fn serve(&mut self, twd: &mut Twd<'_, Async>) -> BankAccess {
+        self.restage(twd);
+
+        let mut access = None;
+        loop {
+            let event = match select(twd.listen(), Timer::after(BUSY_POLL_INTERVAL)).await {
+                Either::First(event) => event,
+                // A read which doesn't empty the TX FIFO raises no interrupt and may start and end
+                // between checks of the busy flag, so check for consumed registers directly
+                Either::Second(()) => {
+                    if self.consume(twd, &mut access) > 0 {
+                        twd.active = true;
+                    }
+                    continue;
+                }
+            };
+
+            if let Some(access) = self.handle(twd, event, &mut access) {
+                return access;
+            }
+        }
+    }
+}
+
+trait SealedIoMode {}
+
+/// TWD IO mode.
+#[allow(private_bounds)]
+pub trait IoMode: SealedIoMode {}
+
+/// Blocking TWD.
+pub struct Blocking;
+impl SealedIoMode for Blocking {}
+impl IoMode for Blocking {}
+
+/// Async TWD.
+pub struct Async;
+impl SealedIoMode for Async {}
+impl IoMode for Async {}
+
+trait SealedInstance {
+    fn reg() -> &'static crate::pac::twd::RegisterBlock;
+    fn waker() -> &'static AtomicWaker;
+}
+
+/// A valid TWD peripheral.
+#[allow(private_bounds)]
+pub trait Instance: SealedInstance + PeripheralType {
+    type Interrupt: Interrupt;
+}
+impl SealedInstance for TWD {
+    fn reg() -> &'static crate::pac::twd::RegisterBlock {
+        // SAFETY: We own the TWD peripheral and are sure to use it safely
+        unsafe { &*crate::pac::Twd::ptr() }
+    }
+
+    fn waker() -> &'static AtomicWaker {
+        static WAKER: AtomicWaker = AtomicWaker::new();
+        &WAKER
+    }
+}
+impl Instance for TWD {
+    type Interrupt = crate::interrupt::typelevel::TWD;
+}
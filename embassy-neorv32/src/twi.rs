@@ -2,11 +2,19 @@
 //!
 //! **Note**: Unfortunately NEORV32's implementation for TWI in hardware appears problematic
 //! and makes it difficult to write a driver for it. Specifically, receiving bytes from a device
-//! is pretty odd and there is no interrupt for byte received, so an async version of this
-//! driver doesn't seem possible and is not provided.
+//! is pretty odd and there is no interrupt for byte received.
+//!
+//! The only interrupt available fires once the TX FIFO is empty and the bus engine is idle,
+//! so the async driver queues up to a FIFO's worth of commands at a time and awaits that instead.
+//! As a consequence, if a device NACKs a byte in the middle of a queued chunk, the rest of that
+//! chunk is still clocked out before STOP is issued.
+use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
 use crate::peripherals::TWI;
+use core::future::poll_fn;
 use core::marker::PhantomData;
+use core::task::Poll;
 use embassy_hal_internal::{Peri, PeripheralType};
+use embassy_sync::waitqueue::AtomicWaker;
 pub use embedded_hal_1::i2c::Operation;
 
 // A hack/workaround for master ACKs (see `read_byte`)
@@ -59,6 +67,20 @@ impl From<Rw> for u8 {
     }
 }
 
+/// TWI interrupt handler binding.
+pub struct InterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        // The interrupt stays asserted for as long as the bus engine is idle,
+        // so we disable it here to prevent it from storming
+        T::Interrupt::disable();
+        T::waker().wake();
+    }
+}
+
 /// TWI error.
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -69,11 +91,36 @@ pub enum Error {
     NackAddr,
     /// Device failed to ack data.
     NackData,
+    /// The requested TWI clock frequency is out of range, or the closest frequency derivable from
+    /// the CPU clock deviates from it by more than 10%.
+    InvalidFrequency,
+}
+
+/// TWI configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Config {
+    /// TWI clock (SCL) frequency in Hz.
+    ///
+    /// When read back via [`Twi::config`], this is the frequency actually achieved.
+    pub frequency: u32,
+    /// Allow devices to stretch the clock by holding SCL low.
+    pub clock_stretch: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            frequency: 100_000,
+            clock_stretch: false,
+        }
+    }
 }
 
 /// Two-Wire Interface (TWI) Driver.
 pub struct Twi<'d, M: IoMode> {
     reg: &'static crate::pac::twi::RegisterBlock,
+    waker: &'static AtomicWaker,
     _phantom: PhantomData<&'d M>,
 }
 
@@ -89,6 +136,11 @@ impl<'d, M: IoMode> Twi<'d, M> {
         self.reg.ctrl().read().twi_ctrl_busy().bit_is_set()
     }
 
+    fn fifo_depth(&self) -> usize {
+        // Value in register is log2 of fifo depth
+        1 << self.reg.ctrl().read().twi_ctrl_fifo().bits()
+    }
+
     fn device_acked(&self) -> bool {
         self.reg.dcmd().read().twi_dcmd_ack().bit_is_clear()
     }
@@ -176,55 +228,65 @@ impl<'d, M: IoMode> Twi<'d, M> {
         }
     }
 
-    fn new_inner<T: Instance>(
-        _instance: Peri<'d, T>,
-        twi_freq: u32,
-        clock_stretch_en: bool,
-    ) -> Result<Self, Error> {
+    fn apply_config(&mut self, config: &Config) -> Result<(), Error> {
+        let cpu_freq = crate::sysinfo::SysInfo::clock_freq();
+        let divider = crate::clock::solve(cpu_freq, config.frequency, &crate::clock::TWI)
+            .map_err(|_| Error::InvalidFrequency)?;
+
+        // Set clock prescaler, divider and clock stretching enable
+        // SAFETY: The solver ensures psc is valid and cdiv can fit in 4 bits
+        self.reg.ctrl().modify(|_, w| unsafe {
+            w.twi_ctrl_prsc()
+                .bits(divider.prsc_sel)
+                .twi_ctrl_cdiv()
+                .bits(divider.div as u8 - 1)
+                .twi_ctrl_clkstr()
+                .bit(config.clock_stretch)
+        });
+
+        Ok(())
+    }
+
+    fn new_inner<T: Instance>(_instance: Peri<'d, T>, config: Config) -> Result<Self, Error> {
         if !crate::sysinfo::SysInfo::soc_config().has_twi() {
             return Err(Error::NotSupported);
         }
 
-        let cpu_freq = crate::sysinfo::SysInfo::clock_freq() as u64;
-        let mut cdiv = (cpu_freq / (4 * twi_freq as u64 * 2)) - 1;
-        let mut psc = 0;
-
-        // Calculate prescaler and divider similar to UART
-        // See: https://github.com/stnolting/neorv32/blob/main/sw/lib/source/neorv32_uart.c#L47
-        // Revisit: There are some ways to improve this and make it more accurate
-        while cdiv > 0xf {
-            if psc == 2 || psc == 4 {
-                cdiv >>= 3;
-            } else {
-                cdiv >>= 1;
-            }
-            psc += 1;
-        }
+        let mut twi = Self {
+            reg: T::reg(),
+            waker: T::waker(),
+            _phantom: PhantomData,
+        };
+        twi.apply_config(&config)?;
 
-        // Set clock prescaler
-        // SAFETY: We are writing a valid psc value
-        T::reg()
-            .ctrl()
-            .modify(|_, w| unsafe { w.twi_ctrl_prsc().bits(psc) });
+        // Enable TWI
+        T::reg().ctrl().modify(|_, w| w.twi_ctrl_en().set_bit());
 
-        // Set clock divider
-        // SAFETY: We've ensured cdiv can fit in 4 bits
-        T::reg()
-            .ctrl()
-            .modify(|_, w| unsafe { w.twi_ctrl_cdiv().bits(cdiv as u8) });
+        Ok(twi)
+    }
 
-        // Set clock stretching enable
-        T::reg()
-            .ctrl()
-            .modify(|_, w| w.twi_ctrl_clkstr().bit(clock_stretch_en));
+    /// Reconfigures the TWI bus, waiting for any command in progress to complete first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFrequency`] if the frequency can't be derived from the CPU clock,
+    /// in which case the previous configuration is kept.
+    pub fn set_config(&mut self, config: &Config) -> Result<(), Error> {
+        while self.tx_busy() {}
+        self.apply_config(config)
+    }
 
-        // Enable TWI
-        T::reg().ctrl().modify(|_, w| w.twi_ctrl_en().set_bit());
+    /// Returns the current configuration, with the TWI clock frequency actually achieved.
+    pub fn config(&self) -> Config {
+        let ctrl = self.reg.ctrl().read();
+        let prsc_sel = ctrl.twi_ctrl_prsc().bits();
+        let cdiv = ctrl.twi_ctrl_cdiv().bits() as u32 + 1;
+        let cpu_freq = crate::sysinfo::SysInfo::clock_freq();
 
-        Ok(Self {
-            reg: T::reg(),
-            _phantom: PhantomData,
-        })
+        Config {
+            frequency: crate::clock::TWI.rate(cpu_freq, prsc_sel, cdiv),
+            clock_stretch: ctrl.twi_ctrl_clkstr().bit_is_set(),
+        }
     }
 
     /// Perform a blocking read from specified address on the TWI bus into buffer.
@@ -304,17 +366,204 @@ impl<'d, M: IoMode> Twi<'d, M> {
 }
 
 impl<'d> Twi<'d, Blocking> {
-    /// Returns a new instance of a blocking TWI driver with given frequency (in Hz).
+    /// Returns a new instance of a blocking TWI driver with given configuration.
     ///
     /// # Errors
     ///
-    /// Returns [`Error::NotSupported`] if TWI is not supported.
+    /// Returns [`Error::NotSupported`] if TWI is not supported,
+    /// or [`Error::InvalidFrequency`] if the configured frequency can't be achieved.
     pub fn new_blocking<T: Instance>(
         _instance: Peri<'d, T>,
-        twi_freq: u32,
-        clock_stretch_en: bool,
+        config: Config,
     ) -> Result<Self, Error> {
-        Self::new_inner(_instance, twi_freq, clock_stretch_en)
+        Self::new_inner(_instance, config)
+    }
+}
+
+impl<'d> Twi<'d, Async> {
+    /// Returns a new instance of an async TWI driver with given configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if TWI is not supported,
+    /// or [`Error::InvalidFrequency`] if the configured frequency can't be achieved.
+    pub fn new_async<T: Instance>(
+        _instance: Peri<'d, T>,
+        config: Config,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_inner(_instance, config)
+    }
+
+    async fn wait_idle(&self) {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+
+            if self.tx_busy() {
+                // SAFETY: It is valid to enable interrupts here, since it is level triggered
+                // and if the bus went idle in the meantime we won't miss it
+                unsafe { crate::enable_periph_irq!(TWI) }
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await
+    }
+
+    async fn queue(&mut self, cmd: Command, ack: bool, byte: u8) {
+        if self.tx_full() {
+            self.wait_idle().await;
+        }
+
+        // SAFETY: Command enum ensures we are writing valid command
+        self.reg.dcmd().write(|w| unsafe {
+            w.twi_dcmd_cmd()
+                .bits(cmd.into())
+                .twi_dcmd_ack()
+                .bit(ack)
+                .twi_dcmd()
+                .bits(byte)
+        });
+    }
+
+    async fn async_stop(&mut self) {
+        self.queue(Command::Stop, false, 0).await;
+        self.wait_idle().await;
+    }
+
+    async fn async_start_addr(&mut self, addr: u8, rw: Rw) -> Result<(), Error> {
+        self.queue(Command::Start, false, 0).await;
+        self.queue(Command::Data, false, addr | u8::from(rw)).await;
+        self.wait_idle().await;
+
+        if self.device_acked() {
+            Ok(())
+        } else {
+            self.async_stop().await;
+            Err(Error::NackAddr)
+        }
+    }
+
+    async fn async_write_raw(&mut self, write: &[u8]) -> Result<(), Error> {
+        // RX FIFO receives an entry per byte, so only queue as many bytes as it can hold
+        for chunk in write.chunks(self.fifo_depth()) {
+            for &byte in chunk {
+                self.queue(Command::Data, false, byte).await;
+            }
+            self.wait_idle().await;
+
+            // Pop every entry regardless so the RX FIFO is left empty
+            let mut acked = true;
+            for _ in chunk {
+                acked &= self.device_acked();
+            }
+
+            if !acked {
+                self.async_stop().await;
+                return Err(Error::NackData);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn async_read_raw(&mut self, read: &mut [u8]) {
+        let len = read.len();
+        let depth = self.fifo_depth();
+
+        for (c, chunk) in read.chunks_mut(depth).enumerate() {
+            // See `read_byte` for why we write all high bits
+            for i in 0..chunk.len() {
+                let ack = if c * depth + i < len - 1 {
+                    Mack::Ack
+                } else {
+                    Mack::Nack
+                };
+                self.queue(Command::Data, ack.into(), HI_Z).await;
+            }
+            self.wait_idle().await;
+
+            for byte in chunk {
+                *byte = self.reg.dcmd().read().twi_dcmd().bits();
+            }
+        }
+    }
+
+    /// Perform an async read from specified address on the TWI bus into buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [Error::NackAddr] if address is not acknowledged.
+    pub async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Error> {
+        self.transaction(address, &mut [Operation::Read(read)])
+            .await
+    }
+
+    /// Perform an async write to specified address on the TWI bus from buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [Error::NackAddr] or [Error::NackData] if address/data is not acknowledged.
+    pub async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Error> {
+        self.transaction(address, &mut [Operation::Write(write)])
+            .await
+    }
+
+    /// Perform an async write to specified address on the TWI bus from write buffer,
+    /// followed by an async read into read buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [Error::NackAddr] or [Error::NackData] if address/data is not acknowledged.
+    pub async fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Error> {
+        self.transaction(
+            address,
+            &mut [Operation::Write(write), Operation::Read(read)],
+        )
+        .await
+    }
+
+    /// Perform given list of async operations to/from specified address on the TWI bus.
+    ///
+    /// # Errors
+    ///
+    /// Returns [Error::NackAddr] or [Error::NackData] if address/data is not acknowledged.
+    pub async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Error> {
+        let mut prev = None;
+
+        for operation in operations {
+            match operation {
+                Operation::Write(data) => {
+                    if prev != Some(Rw::Write) {
+                        self.async_start_addr(address, Rw::Write).await?;
+                        prev = Some(Rw::Write);
+                    }
+
+                    self.async_write_raw(data).await?;
+                }
+                Operation::Read(data) => {
+                    if prev != Some(Rw::Read) {
+                        self.async_start_addr(address, Rw::Read).await?;
+                        prev = Some(Rw::Read);
+                    }
+
+                    self.async_read_raw(data).await;
+                }
+            }
+        }
+
+        self.async_stop().await;
+        Ok(())
     }
 }
 
@@ -329,25 +578,57 @@ pub struct Blocking;
 impl SealedIoMode for Blocking {}
 impl IoMode for Blocking {}
 
+/// Async TWI.
+pub struct Async;
+impl SealedIoMode for Async {}
+impl IoMode for Async {}
+
 trait SealedInstance {
     fn reg() -> &'static crate::pac::twi::RegisterBlock;
+    fn waker() -> &'static AtomicWaker;
 }
 
 /// A valid TWI peripheral.
 #[allow(private_bounds)]
-pub trait Instance: SealedInstance + PeripheralType {}
+pub trait Instance: SealedInstance + PeripheralType {
+    type Interrupt: Interrupt;
+}
 impl SealedInstance for TWI {
     fn reg() -> &'static crate::pac::twi::RegisterBlock {
         // SAFETY: We own the TWI peripheral and are sure to use it safely
         unsafe { &*crate::pac::Twi::ptr() }
     }
+
+    fn waker() -> &'static AtomicWaker {
+        static WAKER: AtomicWaker = AtomicWaker::new();
+        &WAKER
+    }
+}
+impl Instance for TWI {
+    type Interrupt = crate::interrupt::typelevel::TWI;
+}
+
+impl<'d, M: IoMode> embassy_embedded_hal::SetConfig for Twi<'d, M> {
+    type Config = Config;
+    type ConfigError = Error;
+
+    fn set_config(&mut self, config: &Self::Config) -> Result<(), Self::ConfigError> {
+        self.set_config(config)
+    }
+}
+
+impl<'d, M: IoMode> embassy_embedded_hal::GetConfig for Twi<'d, M> {
+    type Config = Config;
+
+    fn get_config(&self) -> Self::Config {
+        self.config()
+    }
 }
-impl Instance for TWI {}
 
 impl embedded_hal_1::i2c::Error for Error {
     fn kind(&self) -> embedded_hal_1::i2c::ErrorKind {
         match *self {
-            Self::NotSupported => embedded_hal_1::i2c::ErrorKind::Other,
+            Self::NotSupported | Self::InvalidFrequency => embedded_hal_1::i2c::ErrorKind::Other,
             Self::NackAddr => embedded_hal_1::i2c::ErrorKind::NoAcknowledge(
                 embedded_hal_1::i2c::NoAcknowledgeSource::Address,
             ),
@@ -372,9 +653,9 @@ impl<'d, M: IoMode> embedded_hal_1::i2c::I2c for Twi<'d, M> {
     }
 }
 
-// NOTE: TWI does not currently support async, but this is implemented anyway
+// NOTE: This is implemented for the blocking driver anyway
 // to allow it to be used by drivers expecting async
-impl<'d, M: IoMode> embedded_hal_async::i2c::I2c for Twi<'d, M> {
+impl<'d> embedded_hal_async::i2c::I2c for Twi<'d, Blocking> {
     async fn transaction(
         &mut self,
         address: u8,
@@ -383,3 +664,13 @@ impl<'d, M: IoMode> embedded_hal_async::i2c::I2c for Twi<'d, M> {
         self.blocking_transaction(address, operations)
     }
 }
+
+impl<'d> embedded_hal_async::i2c::I2c for Twi<'d, Async> {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.transaction(address, operations).await
+    }
+}
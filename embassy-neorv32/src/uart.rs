@@ -1,13 +1,16 @@
 //! Universal Asynchronous Receiver and Transmitter (UART)
-use crate::dma::{self, Dma};
+use crate::dma;
 use crate::interrupt::typelevel::{Binding, Handler, Interrupt};
 use crate::peripherals::{UART0, UART1};
 use core::future::poll_fn;
 use core::marker::PhantomData;
 use core::sync::atomic::{AtomicBool, Ordering};
 use core::task::Poll;
+use embassy_futures::select::{Either, select};
+use embassy_hal_internal::atomic_ring_buffer::RingBuffer;
 use embassy_hal_internal::{Peri, PeripheralType};
 use embassy_sync::waitqueue::AtomicWaker;
+use embassy_time::{Duration, Timer};
 
 /// UART interrupt handler binding.
 pub struct InterruptHandler<T: Instance> {
@@ -79,6 +82,112 @@ impl<T: Instance> Handler<T::Interrupt> for InterruptHandler<T> {
     }
 }
 
+/// Buffered UART interrupt handler binding.
+///
+/// Must be bound instead of [`InterruptHandler`] when using [`BufferedUart`],
+/// [`BufferedUartRx`] or [`BufferedUartTx`].
+pub struct BufferedInterruptHandler<T: Instance> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Instance> Handler<T::Interrupt> for BufferedInterruptHandler<T> {
+    unsafe fn on_interrupt() {
+        let info = T::info();
+        let state = info.buffered;
+
+        // Drain RX FIFO into the RX ring buffer
+        // SAFETY: The interrupt handler is the only writer of the RX ring buffer
+        if let Some(mut rx_writer) = unsafe { state.rx_buf.try_writer() } {
+            let mut received = false;
+
+            while info.reg.ctrl().read().uart_ctrl_rx_nempty().bit_is_set() {
+                let buf = rx_writer.push_slice();
+
+                // Ring buffer is full, so leave the remaining bytes in the FIFO and disable the IRQ
+                // until the reader makes room, otherwise this would storm
+                if buf.is_empty() {
+                    info.reg
+                        .ctrl()
+                        .modify(|_, w| w.uart_ctrl_irq_rx_nempty().clear_bit());
+                    break;
+                }
+
+                let mut n = 0;
+                while n < buf.len() && info.reg.ctrl().read().uart_ctrl_rx_nempty().bit_is_set() {
+                    buf[n] = info.reg.data().read().bits() as u8;
+                    n += 1;
+                }
+                rx_writer.push_done(n);
+                received = true;
+            }
+
+            if received {
+                state.rx_waker.wake();
+            }
+        }
+
+        // Feed TX FIFO from the TX ring buffer
+        // SAFETY: The interrupt handler is the only reader of the TX ring buffer
+        if let Some(mut tx_reader) = unsafe { state.tx_buf.try_reader() } {
+            let mut sent = false;
+
+            while info.reg.ctrl().read().uart_ctrl_tx_nfull().bit_is_set() {
+                let Some(byte) = tx_reader.pop_one() else {
+                    break;
+                };
+                // SAFETY: We are just writing a byte, the MSB bits are read-only
+                info.reg.data().write(|w| unsafe { w.bits(byte as u32) });
+                sent = true;
+            }
+
+            // Nothing left to send, so disable the TX not full IRQ otherwise this would storm
+            if state.tx_buf.is_empty() {
+                info.reg
+                    .ctrl()
+                    .modify(|_, w| w.uart_ctrl_irq_tx_nfull().clear_bit());
+            }
+
+            // If TX FIFO is empty, disable TX empty IRQ (only enabled while flushing)
+            let tx_empty_irq_set = info.reg.ctrl().read().uart_ctrl_irq_tx_empty().bit_is_set();
+            let tx_empty = info.reg.ctrl().read().uart_ctrl_tx_empty().bit_is_set();
+            if tx_empty_irq_set && tx_empty {
+                info.reg
+                    .ctrl()
+                    .modify(|_, w| w.uart_ctrl_irq_tx_empty().clear_bit());
+                sent = true;
+            }
+
+            if sent {
+                state.tx_waker.wake();
+            }
+        }
+    }
+}
+
+/// UART configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Config {
+    /// Baud rate (in bits per second).
+    ///
+    /// The achieved baud rate may differ slightly depending on the CPU clock.
+    pub baud_rate: u32,
+    /// Enable simulation mode, where TX output is redirected to the simulator console.
+    pub sim: bool,
+    /// Enable RTS/CTS hardware flow control.
+    pub flow_control: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            baud_rate: 19200,
+            sim: false,
+            flow_control: false,
+        }
+    }
+}
+
 /// UART error.
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -87,6 +196,14 @@ pub enum Error {
     NotSupported,
     /// A DMA error occurred.
     Dma(dma::Error),
+    /// The RX FIFO overflowed and received data was lost.
+    ///
+    /// This is sticky, so reads keep failing until it is explicitly cleared with
+    /// [`UartRx::clear_overrun`] (which discards the FIFOs and aborts any transmission).
+    Overrun,
+    /// The requested baud rate is out of range, or the closest rate derivable from the CPU clock
+    /// deviates from it by more than 2.5%.
+    InvalidBaudRate,
 }
 
 impl core::fmt::Display for Error {
@@ -94,6 +211,13 @@ impl core::fmt::Display for Error {
         match self {
             Error::NotSupported => write!(f, "The NEORV32 configuration does not support UART"),
             Error::Dma(e) => write!(f, "A DMA error occurred: {e:?}"),
+            Error::Overrun => write!(f, "The RX FIFO overflowed and received data was lost"),
+            Error::InvalidBaudRate => {
+                write!(
+                    f,
+                    "The requested baud rate can't be derived from the CPU clock"
+                )
+            }
         }
     }
 }
@@ -107,59 +231,20 @@ pub struct Uart<'d, M: IoMode> {
 }
 
 impl<'d, M: IoMode> Uart<'d, M> {
-    fn init<T: Instance>(_instance: Peri<'d, T>, baud_rate: u32, sim: bool, flow_control: bool) {
-        // Enable simulation mode if applicable
-        if sim {
-            T::info()
-                .reg
-                .ctrl()
-                .modify(|_, w| w.uart_ctrl_sim_mode().set_bit());
-        }
-
-        // Enable flow control if applicable
-        if flow_control {
-            T::info()
-                .reg
-                .ctrl()
-                .modify(|_, w| w.uart_ctrl_hwfc_en().set_bit());
-        }
-
-        // baud div is max 10-bits wide
-        const U10_MAX: u16 = 0x3ff;
-        let cpu_freq = crate::sysinfo::SysInfo::clock_freq();
-        let mut baud_div = cpu_freq / (2 * baud_rate);
-        let mut prsc_sel = 0;
-
-        // Calculate clock prescaler and baud rate prescaler
-        // See: https://github.com/stnolting/neorv32/blob/main/sw/lib/source/neorv32_uart.c#L47
-        while baud_div >= U10_MAX as u32 {
-            if prsc_sel == 2 || prsc_sel == 4 {
-                baud_div >>= 3;
-            } else {
-                baud_div >>= 1;
-            }
-            prsc_sel += 1;
-        }
-
-        // Set the clock and baudrate prescalers
-        // SAFETY: The calculation above ensures we are writing valid prscv and baud div
-        T::info().reg.ctrl().modify(|_, w| unsafe {
-            w.uart_ctrl_prsc()
-                .bits(prsc_sel)
-                .uart_ctrl_baud()
-                .bits((baud_div as u16 - 1) & U10_MAX)
-        });
+    fn init<T: Instance>(_instance: Peri<'d, T>, config: &Config) -> Result<(), Error> {
+        T::info().apply_config(config)?;
 
         // Enable UART
         T::info()
             .reg
             .ctrl()
             .modify(|_, w| w.uart_ctrl_en().set_bit());
+        Ok(())
     }
 
     fn new_inner<T: Instance>(
-        rx_dma: Option<Dma<'d>>,
-        tx_dma: Option<Dma<'d>>,
+        rx_dma: Option<dma::Channel<'d>>,
+        tx_dma: Option<dma::Channel<'d>>,
     ) -> Result<Self, Error> {
         let rx = UartRx::new_inner::<T>(rx_dma)?;
         let tx = UartTx::new_inner::<T>(tx_dma)?;
@@ -185,11 +270,39 @@ impl<'d, M: IoMode> Uart<'d, M> {
         self.tx.blocking_write_byte(byte);
     }
 
+    /// Returns true if the RX FIFO has overflowed since the overrun flag was last cleared.
+    pub fn is_overrun(&self) -> bool {
+        self.rx.is_overrun()
+    }
+
+    /// Clears the RX FIFO overrun flag.
+    ///
+    /// **Note**: This discards the contents of both FIFOs and aborts any transmission in progress.
+    /// See [`UartRx::clear_overrun`] for details.
+    pub fn clear_overrun(&mut self) {
+        self.rx.clear_overrun();
+    }
+
     /// Writes bytes to TX FIFO, blocking if full.
     pub fn blocking_write(&mut self, bytes: &[u8]) {
         self.tx.blocking_write(bytes);
     }
 
+    /// Reconfigures the UART, waiting for any transmission in progress to complete first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock,
+    /// in which case the previous configuration is kept.
+    pub fn set_config(&mut self, config: &Config) -> Result<(), Error> {
+        self.tx.set_config(config)
+    }
+
+    /// Returns the current configuration, with the baud rate actually achieved.
+    pub fn config(&self) -> Config {
+        self.rx.config()
+    }
+
     /// Splits the UART driver into separate [`UartRx`] and [`UartTx`] drivers.
     ///
     /// Helpful for sharing the UART among receiver/transmitter tasks.
@@ -206,20 +319,18 @@ impl<'d, M: IoMode> Uart<'d, M> {
 }
 
 impl<'d> Uart<'d, Blocking> {
-    /// Creates a new blocking UART driver with given baud rate.
-    ///
-    /// Enables simulation mode if `sim` is true and hardware flow control if `flow_control` is true.
+    /// Creates a new blocking UART driver with given config.
     ///
     /// # Errors
     ///
     /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
     pub fn new_blocking<T: Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        sim: bool,
-        flow_control: bool,
+        config: Config,
     ) -> Result<Self, Error> {
-        Self::init(_instance, baud_rate, sim, flow_control);
+        Self::init(_instance, &config)?;
         Self::new_inner::<T>(None, None)
     }
 }
@@ -227,14 +338,12 @@ impl<'d> Uart<'d, Blocking> {
 impl<'d> Uart<'d, Async> {
     fn new_async_inner<T: Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        sim: bool,
-        flow_control: bool,
-        rx_dma: Option<Dma<'d>>,
-        tx_dma: Option<Dma<'d>>,
+        config: Config,
+        rx_dma: Option<dma::Channel<'d>>,
+        tx_dma: Option<dma::Channel<'d>>,
     ) -> Result<Self, Error> {
         let uart = Self::new_inner::<T>(rx_dma, tx_dma)?;
-        Self::init(_instance, baud_rate, sim, flow_control);
+        Self::init(_instance, &config)?;
         // SAFETY: It is valid to enable UART interrupt here
         unsafe { T::Interrupt::enable() }
         Ok(uart)
@@ -244,26 +353,22 @@ impl<'d> Uart<'d, Async> {
         self.tx.flush()
     }
 
-    /// Creates a new async UART driver with given baud rate.
-    ///
-    /// Enables simulation mode if `sim` is true and hardware flow control if `flow_control` is true.
+    /// Creates a new async UART driver with given config.
     ///
     /// # Errors
     ///
     /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
     pub fn new_async<T: Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        sim: bool,
-        flow_control: bool,
+        config: Config,
         _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
     ) -> Result<Self, Error> {
-        Self::new_async_inner(_instance, baud_rate, sim, flow_control, None, None)
+        Self::new_async_inner(_instance, config, None, None)
     }
 
-    /// Creates a new async UART driver with given baud rate.
-    ///
-    /// Enables simulation mode if `sim` is true and hardware flow control if `flow_control` is true.
+    /// Creates a new async UART driver with given config.
     ///
     /// Additionally provides the DMA peripheral for TX transfers.
     /// See [`UartTx::new_async_with_dma`] for considerations on whether to use DMA or not.
@@ -272,24 +377,22 @@ impl<'d> Uart<'d, Async> {
     ///
     /// Returns [`Error::NotSupported`] if UART is not supported.
     ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
+    ///
     /// Returns [`Error::Dma`] if DMA is not supported.
     pub fn new_async_with_tx_dma<T: Instance, D: dma::Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        sim: bool,
-        flow_control: bool,
+        config: Config,
         dma: Peri<'d, D>,
         _irq: impl Binding<T::Interrupt, InterruptHandler<T>>
         + Binding<D::Interrupt, dma::InterruptHandler<D>>
         + 'd,
     ) -> Result<Self, Error> {
         let dma = dma::Dma::new(dma, _irq).map_err(Error::Dma)?;
-        Self::new_async_inner(_instance, baud_rate, sim, flow_control, None, Some(dma))
+        Self::new_async_inner(_instance, config, None, Some(dma::Channel::Exclusive(dma)))
     }
 
-    /// Creates a new async UART driver with given baud rate.
-    ///
-    /// Enables simulation mode if `sim` is true and hardware flow control if `flow_control` is true.
+    /// Creates a new async UART driver with given config.
     ///
     /// Additionally provides the DMA peripheral for RX transfers.
     /// See [`UartRx::new_async_with_dma`] for considerations on whether to use DMA or not.
@@ -298,19 +401,44 @@ impl<'d> Uart<'d, Async> {
     ///
     /// Returns [`Error::NotSupported`] if UART is not supported.
     ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
+    ///
     /// Returns [`Error::Dma`] if DMA is not supported.
     pub fn new_async_with_rx_dma<T: Instance, D: dma::Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        sim: bool,
-        flow_control: bool,
+        config: Config,
         dma: Peri<'d, D>,
         _irq: impl Binding<T::Interrupt, InterruptHandler<T>>
         + Binding<D::Interrupt, dma::InterruptHandler<D>>
         + 'd,
     ) -> Result<Self, Error> {
         let dma = dma::Dma::new(dma, _irq).map_err(Error::Dma)?;
-        Self::new_async_inner(_instance, baud_rate, sim, flow_control, Some(dma), None)
+        Self::new_async_inner(_instance, config, Some(dma::Channel::Exclusive(dma)), None)
+    }
+
+    /// Creates a new async UART driver with given config.
+    ///
+    /// Additionally provides channels of a shared DMA for RX and TX transfers, so other
+    /// peripherals can use the DMA as well. See [`dma::SharedDma`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
+    pub fn new_async_with_shared_dma<T: Instance>(
+        _instance: Peri<'d, T>,
+        config: Config,
+        rx_dma: dma::SharedChannel<'d>,
+        tx_dma: dma::SharedChannel<'d>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_async_inner(
+            _instance,
+            config,
+            Some(dma::Channel::Shared(rx_dma)),
+            Some(dma::Channel::Shared(tx_dma)),
+        )
     }
 
     /// Reads bytes from RX FIFO until buffer is full.
@@ -322,6 +450,21 @@ impl<'d> Uart<'d, Async> {
         self.rx.read(buf)
     }
 
+    /// Reads bytes into buffer until it is full or the line goes idle.
+    ///
+    /// See [`UartRx::read_until_idle`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Overrun`] if the RX FIFO overflowed.
+    pub fn read_until_idle(
+        &mut self,
+        buf: &mut [u8],
+        idle_chars: u32,
+    ) -> impl Future<Output = Result<usize, Error>> {
+        self.rx.read_until_idle(buf, idle_chars)
+    }
+
     /// Writes bytes from buffer to TX FIFO.
     ///
     /// # Errors
@@ -336,7 +479,7 @@ impl<'d> Uart<'d, Async> {
 pub struct UartRx<'d, M: IoMode> {
     info: Info,
     fifo_depth: usize,
-    dma: Option<dma::Dma<'d>>,
+    dma: Option<dma::Channel<'d>>,
     _phantom: PhantomData<&'d M>,
 }
 
@@ -347,7 +490,7 @@ pub struct UartRx<'d, M: IoMode> {
 unsafe impl<'d, M: IoMode> Send for UartRx<'d, M> {}
 
 impl<'d, M: IoMode> UartRx<'d, M> {
-    fn new_inner<T: Instance>(dma: Option<dma::Dma<'d>>) -> Result<Self, Error> {
+    fn new_inner<T: Instance>(dma: Option<dma::Channel<'d>>) -> Result<Self, Error> {
         if !T::supported() {
             return Err(Error::NotSupported);
         }
@@ -401,6 +544,46 @@ impl<'d, M: IoMode> UartRx<'d, M> {
         self.info.reg.ctrl().read().uart_ctrl_rx_full().bit_is_set()
     }
 
+    fn check_overrun(&self) -> Result<(), Error> {
+        if self.is_overrun() {
+            Err(Error::Overrun)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Reconfigures the UART.
+    ///
+    /// **Note**: This also affects the TX half if the UART was split, so ensure
+    /// it is idle first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock,
+    /// in which case the previous configuration is kept.
+    pub fn set_config(&mut self, config: &Config) -> Result<(), Error> {
+        self.info.apply_config(config)
+    }
+
+    /// Returns the current configuration, with the baud rate actually achieved.
+    pub fn config(&self) -> Config {
+        self.info.config()
+    }
+
+    /// Returns true if the RX FIFO has overflowed since the overrun flag was last cleared.
+    pub fn is_overrun(&self) -> bool {
+        self.info.rx_overrun()
+    }
+
+    /// Clears the RX FIFO overrun flag.
+    ///
+    /// **Note**: The flag can only be cleared by briefly disabling the UART. This discards the
+    /// contents of both the RX and TX FIFOs and aborts any transmission in progress, including one
+    /// on a split-off TX half owned by another task, so ensure the TX side is idle first.
+    pub fn clear_overrun(&mut self) {
+        self.info.clear_rx_overrun();
+    }
+
     /// Reads a byte from RX FIFO, blocking if empty.
     pub fn blocking_read_byte(&self) -> u8 {
         while self.fifo_empty() {}
@@ -416,20 +599,19 @@ impl<'d, M: IoMode> UartRx<'d, M> {
 }
 
 impl<'d> UartRx<'d, Blocking> {
-    /// Creates a new RX-only blocking UART driver with given baud rate.
-    ///
-    /// Enables hardware flow control if `flow_control` is true.
+    /// Creates a new RX-only blocking UART driver with given config.
     ///
     /// # Errors
     ///
     /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
     pub fn new_blocking<T: Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        flow_control: bool,
+        config: Config,
     ) -> Result<Self, Error> {
         let uart = Self::new_inner::<T>(None)?;
-        Uart::<Blocking>::init(_instance, baud_rate, false, flow_control);
+        Uart::<Blocking>::init(_instance, &config)?;
         Ok(uart)
     }
 }
@@ -464,15 +646,18 @@ impl<'d> UartRx<'d, Async> {
     }
 
     async fn read_chunk(&mut self, chunk: &mut [u8]) -> Result<(), Error> {
+        let src = self.info.reg.data().as_ptr() as *const u8;
+        // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
+        let src = unsafe { src.as_ref().unwrap_unchecked() };
+
         // If DMA available, use it to transfer data from RX FIFO to buffer
-        if let Some(dma) = &mut self.dma {
-            let src = self.info.reg.data().as_ptr() as *const u8;
-            // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
-            let src = unsafe { src.as_ref().unwrap_unchecked() };
-            dma.read(src, chunk, false).await.map_err(Error::Dma)?;
+        let done = match &mut self.dma {
+            Some(dma) => dma.read(src, chunk).await.map_err(Error::Dma)?,
+            None => false,
+        };
 
-        // Otherwise, manually read each byte from RX FIFO
-        } else {
+        // Otherwise (or if a shared DMA is busy), manually read each byte from RX FIFO
+        if !done {
             for byte in chunk {
                 *byte = self.read_inner();
             }
@@ -483,41 +668,38 @@ impl<'d> UartRx<'d, Async> {
 
     fn new_async_inner<T: Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        flow_control: bool,
-        dma: Option<Dma<'d>>,
+        config: Config,
+        dma: Option<dma::Channel<'d>>,
     ) -> Result<Self, Error> {
         let uart = Self::new_inner::<T>(dma)?;
-        Uart::<Async>::init(_instance, baud_rate, false, flow_control);
+        Uart::<Async>::init(_instance, &config)?;
         // SAFETY: It is valid to enable UART interrupt here
         unsafe { T::Interrupt::enable() }
         Ok(uart)
     }
 
-    /// Creates a new RX-only async UART driver with given baud rate.
-    ///
-    /// Enables hardware flow control if `flow_control` is true.
+    /// Creates a new RX-only async UART driver with given config.
     ///
     /// # Errors
     ///
     /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
     pub fn new_async<T: Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        flow_control: bool,
+        config: Config,
         _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
     ) -> Result<Self, Error> {
-        Self::new_async_inner(_instance, baud_rate, flow_control, None)
+        Self::new_async_inner(_instance, config, None)
     }
 
-    /// Creates a new RX-only async UART driver with given baud rate.
-    ///
-    /// Enables hardware flow control if `flow_control` is true.
+    /// Creates a new RX-only async UART driver with given config.
     ///
     /// Additionally provides the DMA peripheral for transfers.
     ///
     /// **Note**: The DMA peripheral is limited in that it is single-channel only so you have to
-    /// decide which peripheral (if any) will use it. Without DMA, the driver will manually
+    /// decide which peripheral (if any) will use it, or share it between peripherals with
+    /// [`dma::SharedDma`] (see [`Self::new_async_with_shared_dma`]). Without DMA, the driver will manually
     /// copy each byte into the FIFO. However, depending on the configured FIFO size and how many
     /// bytes you are expecting to transfer, this may be more efficient as there is overhead in
     /// setting up the DMA transfer.
@@ -526,18 +708,38 @@ impl<'d> UartRx<'d, Async> {
     ///
     /// Returns [`Error::NotSupported`] if UART is not supported.
     ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
+    ///
     /// Returns [`Error::Dma`] if DMA is not supported.
     pub fn new_async_with_dma<T: Instance, D: dma::Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        flow_control: bool,
+        config: Config,
         dma: Peri<'d, D>,
         _irq: impl Binding<T::Interrupt, InterruptHandler<T>>
         + Binding<D::Interrupt, dma::InterruptHandler<D>>
         + 'd,
     ) -> Result<Self, Error> {
         let dma = dma::Dma::new(dma, _irq).map_err(Error::Dma)?;
-        Self::new_async_inner(_instance, baud_rate, flow_control, Some(dma))
+        Self::new_async_inner(_instance, config, Some(dma::Channel::Exclusive(dma)))
+    }
+
+    /// Creates a new RX-only async UART driver with given config.
+    ///
+    /// Additionally provides a channel of a shared DMA for transfers, so other peripherals can
+    /// use the DMA as well. See [`dma::SharedDma`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
+    pub fn new_async_with_shared_dma<T: Instance>(
+        _instance: Peri<'d, T>,
+        config: Config,
+        dma: dma::SharedChannel<'d>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_async_inner(_instance, config, Some(dma::Channel::Shared(dma)))
     }
 
     /// Reads bytes from RX FIFO until buffer is full.
@@ -552,6 +754,7 @@ impl<'d> UartRx<'d, Async> {
         // then read all bytes from the FIFO in one shot
         for chunk in chunks.by_ref() {
             self.wait_fifo_full().await;
+            self.check_overrun()?;
             self.read_chunk(chunk).await?;
         }
 
@@ -559,11 +762,67 @@ impl<'d> UartRx<'d, Async> {
         // and manually read a single byte
         for byte in chunks.into_remainder() {
             self.wait_fifo_nempty().await;
+            self.check_overrun()?;
             *byte = self.read_inner();
         }
 
         Ok(())
     }
+
+    // Time it takes to receive a single character (start bit, 8 data bits and stop bit)
+    fn char_time(&self) -> Duration {
+        // Round up so we never consider the line idle too early
+        let baud_rate = self.info.config().baud_rate as u64;
+        Duration::from_nanos((10 * 1_000_000_000u64).div_ceil(baud_rate))
+    }
+
+    /// Reads bytes into buffer until it is full or the line goes idle.
+    ///
+    /// Waits indefinitely for the first byte, then completes once no further byte has been
+    /// received for `idle_chars` character times (at least one). This suits variable-length
+    /// frames delimited by silence, such as Modbus RTU.
+    ///
+    /// Returns the number of bytes read.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Overrun`] if the RX FIFO overflowed before any byte was read. If it
+    /// overflows after some bytes were read, those are returned and the overrun is reported by
+    /// the next read.
+    pub async fn read_until_idle(
+        &mut self,
+        buf: &mut [u8],
+        idle_chars: u32,
+    ) -> Result<usize, Error> {
+        let timeout = self.char_time() * idle_chars.max(1);
+        let mut n = 0;
+
+        while n < buf.len() {
+            // Drain everything that has arrived so far
+            while n < buf.len() && !self.fifo_empty() {
+                if n > 0 && self.is_overrun() {
+                    return Ok(n);
+                }
+                self.check_overrun()?;
+                buf[n] = self.read_inner();
+                n += 1;
+            }
+
+            if n == buf.len() {
+                break;
+            }
+
+            if n == 0 {
+                self.wait_fifo_nempty().await;
+            } else if let Either::Second(()) =
+                select(self.wait_fifo_nempty(), Timer::after(timeout)).await
+            {
+                break;
+            }
+        }
+
+        Ok(n)
+    }
 }
 
 impl<'d, M: IoMode> Drop for UartRx<'d, M> {
@@ -577,7 +836,7 @@ impl<'d, M: IoMode> Drop for UartRx<'d, M> {
 pub struct UartTx<'d, M: IoMode> {
     info: Info,
     fifo_depth: usize,
-    dma: Option<dma::Dma<'d>>,
+    dma: Option<dma::Channel<'d>>,
     _phantom: PhantomData<&'d M>,
 }
 
@@ -588,7 +847,7 @@ pub struct UartTx<'d, M: IoMode> {
 unsafe impl<'d, M: IoMode> Send for UartTx<'d, M> {}
 
 impl<'d, M: IoMode> UartTx<'d, M> {
-    fn new_inner<T: Instance>(dma: Option<dma::Dma<'d>>) -> Result<Self, Error> {
+    fn new_inner<T: Instance>(dma: Option<dma::Channel<'d>>) -> Result<Self, Error> {
         if !T::supported() {
             return Err(Error::NotSupported);
         }
@@ -643,6 +902,24 @@ impl<'d, M: IoMode> UartTx<'d, M> {
         while self.busy() {}
     }
 
+    /// Reconfigures the UART, waiting for any transmission in progress to complete first.
+    ///
+    /// **Note**: This also affects the RX half if the UART was split.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock,
+    /// in which case the previous configuration is kept.
+    pub fn set_config(&mut self, config: &Config) -> Result<(), Error> {
+        self.blocking_flush();
+        self.info.apply_config(config)
+    }
+
+    /// Returns the current configuration, with the baud rate actually achieved.
+    pub fn config(&self) -> Config {
+        self.info.config()
+    }
+
     /// Writes a byte to TX FIFO, blocking if full.
     pub fn blocking_write_byte(&mut self, byte: u8) {
         while self.fifo_full() {}
@@ -661,36 +938,37 @@ impl<'d, M: IoMode> UartTx<'d, M> {
 }
 
 impl<'d> UartTx<'d, Blocking> {
-    /// Creates a new TX-only blocking UART driver with given baud rate.
-    ///
-    /// Enables simulation mode if `sim` is true and hardware flow control if `flow_control` is true.
+    /// Creates a new TX-only blocking UART driver with given config.
     ///
     /// # Errors
     ///
     /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
     pub fn new_blocking<T: Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        sim: bool,
-        flow_control: bool,
+        config: Config,
     ) -> Result<Self, Error> {
         let uart = Self::new_inner::<T>(None)?;
-        Uart::<Blocking>::init(_instance, baud_rate, sim, flow_control);
+        Uart::<Blocking>::init(_instance, &config)?;
         Ok(uart)
     }
 }
 
 impl<'d> UartTx<'d, Async> {
     async fn write_chunk(&mut self, chunk: &[u8]) -> Result<(), Error> {
+        let dst = self.info.reg.data().as_ptr() as *mut u8;
+        // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
+        let dst = unsafe { dst.as_mut().unwrap_unchecked() };
+
         // If DMA available, use it to transfer data from buffer to TX FIFO
-        if let Some(dma) = &mut self.dma {
-            let dst = self.info.reg.data().as_ptr() as *mut u8;
-            // SAFETY: The PAC ensures the data register pointer is not-null and properly aligned
-            let dst = unsafe { dst.as_mut().unwrap_unchecked() };
-            dma.write(chunk, dst, false).await.map_err(Error::Dma)?;
+        let done = match &mut self.dma {
+            Some(dma) => dma.write(chunk, dst).await.map_err(Error::Dma)?,
+            None => false,
+        };
 
-        // Otherwise, manually write each byte to TX FIFO
-        } else {
+        // Otherwise (or if a shared DMA is busy), manually write each byte to TX FIFO
+        if !done {
             for byte in chunk.iter().copied() {
                 self.write_inner(byte);
             }
@@ -701,13 +979,11 @@ impl<'d> UartTx<'d, Async> {
 
     fn new_async_inner<T: Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        sim: bool,
-        flow_control: bool,
-        dma: Option<Dma<'d>>,
+        config: Config,
+        dma: Option<dma::Channel<'d>>,
     ) -> Result<Self, Error> {
         let uart = Self::new_inner::<T>(dma)?;
-        Uart::<Async>::init(_instance, baud_rate, sim, flow_control);
+        Uart::<Async>::init(_instance, &config)?;
         // SAFETY: It is valid to enable UART interrupt here
         unsafe { T::Interrupt::enable() }
         Ok(uart)
@@ -727,31 +1003,28 @@ impl<'d> UartTx<'d, Async> {
         .await
     }
 
-    /// Creates a new TX-only async UART driver with given baud rate.
-    ///
-    /// Enables simulation mode if `sim` is true and hardware flow control if `flow_control` is true.
+    /// Creates a new TX-only async UART driver with given config.
     ///
     /// # Errors
     ///
     /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
     pub fn new_async<T: Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        sim: bool,
-        flow_control: bool,
+        config: Config,
         _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
     ) -> Result<Self, Error> {
-        Self::new_async_inner(_instance, baud_rate, sim, flow_control, None)
+        Self::new_async_inner(_instance, config, None)
     }
 
-    /// Creates a new TX-only async UART driver with given baud rate.
-    ///
-    /// Enables simulation mode if `sim` is true and hardware flow control if `flow_control` is true.
+    /// Creates a new TX-only async UART driver with given config.
     ///
     /// Additionally provides the DMA peripheral for transfers.
     ///
     /// **Note**: The DMA peripheral is limited in that it is single-channel only so you have to
-    /// decide which peripheral (if any) will use it. Without DMA, the driver will manually
+    /// decide which peripheral (if any) will use it, or share it between peripherals with
+    /// [`dma::SharedDma`] (see [`Self::new_async_with_shared_dma`]). Without DMA, the driver will manually
     /// copy each byte into the FIFO. However, depending on the configured FIFO size and how many
     /// bytes you are expecting to transfer, this may be more efficient as there is overhead in
     /// setting up the DMA transfer.
@@ -760,19 +1033,38 @@ impl<'d> UartTx<'d, Async> {
     ///
     /// Returns [`Error::NotSupported`] if UART is not supported.
     ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
+    ///
     /// Returns [`Error::Dma`] if DMA is not supported.
     pub fn new_async_with_dma<T: Instance, D: dma::Instance>(
         _instance: Peri<'d, T>,
-        baud_rate: u32,
-        sim: bool,
-        flow_control: bool,
+        config: Config,
         dma: Peri<'d, D>,
         _irq: impl Binding<T::Interrupt, InterruptHandler<T>>
         + Binding<D::Interrupt, dma::InterruptHandler<D>>
         + 'd,
     ) -> Result<Self, Error> {
         let dma = dma::Dma::new(dma, _irq).map_err(Error::Dma)?;
-        Self::new_async_inner(_instance, baud_rate, sim, flow_control, Some(dma))
+        Self::new_async_inner(_instance, config, Some(dma::Channel::Exclusive(dma)))
+    }
+
+    /// Creates a new TX-only async UART driver with given config.
+    ///
+    /// Additionally provides a channel of a shared DMA for transfers, so other peripherals can
+    /// use the DMA as well. See [`dma::SharedDma`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
+    pub fn new_async_with_shared_dma<T: Instance>(
+        _instance: Peri<'d, T>,
+        config: Config,
+        dma: dma::SharedChannel<'d>,
+        _irq: impl Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        Self::new_async_inner(_instance, config, Some(dma::Channel::Shared(dma)))
     }
 
     /// Writes bytes from buffer to TX FIFO.
@@ -797,6 +1089,443 @@ impl<'d, M: IoMode> Drop for UartTx<'d, M> {
     }
 }
 
+/// Interrupt-driven buffered UART driver.
+///
+/// The interrupt handler continuously moves received bytes from the RX FIFO into a ring buffer and
+/// feeds the TX FIFO from another, so no bytes are lost while no task is awaiting a read.
+/// Requires [`BufferedInterruptHandler`] to be bound.
+pub struct BufferedUart<'d> {
+    rx: BufferedUartRx<'d>,
+    tx: BufferedUartTx<'d>,
+}
+
+impl<'d> BufferedUart<'d> {
+    /// Creates a new buffered UART driver with given config, using the given buffers as RX and
+    /// TX ring buffers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either buffer is empty.
+    pub fn new<T: Instance>(
+        _instance: Peri<'d, T>,
+        config: Config,
+        rx_buf: &'d mut [u8],
+        tx_buf: &'d mut [u8],
+        _irq: impl Binding<T::Interrupt, BufferedInterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        let rx = BufferedUartRx::new_inner::<T>(rx_buf)?;
+        let tx = BufferedUartTx::new_inner::<T>(tx_buf)?;
+        Uart::<Async>::init(_instance, &config)?;
+        rx.start();
+        // SAFETY: It is valid to enable UART interrupt here
+        unsafe { T::Interrupt::enable() }
+        Ok(Self { rx, tx })
+    }
+
+    /// Splits the buffered UART driver into separate [`BufferedUartRx`] and [`BufferedUartTx`] drivers.
+    ///
+    /// Helpful for sharing the UART among receiver/transmitter tasks.
+    pub fn split(self) -> (BufferedUartRx<'d>, BufferedUartTx<'d>) {
+        (self.rx, self.tx)
+    }
+
+    /// Splits the buffered UART driver into separate [`BufferedUartRx`] and [`BufferedUartTx`]
+    /// drivers by mutable reference.
+    ///
+    /// Helpful for sharing the UART among receiver/transmitter tasks without destroying the original
+    /// [`BufferedUart`] instance.
+    pub fn split_ref(&mut self) -> (&mut BufferedUartRx<'d>, &mut BufferedUartTx<'d>) {
+        (&mut self.rx, &mut self.tx)
+    }
+
+    /// Reads available bytes into buffer, waiting until at least one byte is available.
+    ///
+    /// Returns the number of bytes read.
+    pub fn read(&mut self, buf: &mut [u8]) -> impl Future<Output = usize> {
+        self.rx.read(buf)
+    }
+
+    /// Writes as many bytes as fit into the TX ring buffer, waiting until there is room for at
+    /// least one.
+    ///
+    /// Returns the number of bytes written.
+    pub fn write(&mut self, bytes: &[u8]) -> impl Future<Output = usize> {
+        self.tx.write(bytes)
+    }
+
+    /// Waits until all buffered bytes have been transmitted.
+    pub fn flush(&mut self) -> impl Future<Output = ()> {
+        self.tx.flush()
+    }
+
+    /// Returns true if the RX FIFO has overflowed since the overrun flag was last cleared.
+    pub fn is_overrun(&self) -> bool {
+        self.rx.is_overrun()
+    }
+
+    /// Clears the RX FIFO overrun flag.
+    ///
+    /// **Note**: This discards the contents of both FIFOs and aborts any transmission in progress.
+    /// See [`UartRx::clear_overrun`] for details.
+    pub fn clear_overrun(&mut self) {
+        self.rx.clear_overrun();
+    }
+
+    /// Reconfigures the UART.
+    ///
+    /// **Note**: Bytes still waiting in the TX ring buffer are sent with the new configuration,
+    /// so [`Self::flush`] first if that matters.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock,
+    /// in which case the previous configuration is kept.
+    pub fn set_config(&mut self, config: &Config) -> Result<(), Error> {
+        self.rx.set_config(config)
+    }
+
+    /// Returns the current configuration, with the baud rate actually achieved.
+    pub fn config(&self) -> Config {
+        self.rx.config()
+    }
+}
+
+/// RX-only interrupt-driven buffered UART driver.
+///
+/// See [`BufferedUart`] for details.
+pub struct BufferedUartRx<'d> {
+    info: Info,
+    _phantom: PhantomData<&'d mut [u8]>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d> Send for BufferedUartRx<'d> {}
+
+impl<'d> BufferedUartRx<'d> {
+    fn new_inner<T: Instance>(rx_buf: &'d mut [u8]) -> Result<Self, Error> {
+        if !T::supported() {
+            return Err(Error::NotSupported);
+        }
+        assert!(!rx_buf.is_empty());
+
+        // Mark RX as active
+        T::info().active.rx.store(true, Ordering::Release);
+
+        // SAFETY: The buffer outlives the driver, and the ring buffer is deinitialized on drop
+        unsafe {
+            T::info()
+                .buffered
+                .rx_buf
+                .init(rx_buf.as_mut_ptr(), rx_buf.len())
+        };
+
+        Ok(Self {
+            info: T::info(),
+            _phantom: PhantomData,
+        })
+    }
+
+    // Starts draining the RX FIFO into the ring buffer in the background
+    fn start(&self) {
+        // CS used here since interrupt modifies register
+        critical_section::with(|_| {
+            self.info
+                .reg
+                .ctrl()
+                .modify(|_, w| w.uart_ctrl_irq_rx_nempty().set_bit())
+        });
+    }
+
+    /// Creates a new RX-only buffered UART driver with given config, using the given buffer as
+    /// RX ring buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is empty.
+    pub fn new<T: Instance>(
+        _instance: Peri<'d, T>,
+        config: Config,
+        rx_buf: &'d mut [u8],
+        _irq: impl Binding<T::Interrupt, BufferedInterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        let uart = Self::new_inner::<T>(rx_buf)?;
+        Uart::<Async>::init(_instance, &config)?;
+        uart.start();
+        // SAFETY: It is valid to enable UART interrupt here
+        unsafe { T::Interrupt::enable() }
+        Ok(uart)
+    }
+
+    /// Returns true if the RX FIFO has overflowed since the overrun flag was last cleared.
+    ///
+    /// Since the interrupt handler drains the RX FIFO continuously, this only happens if the
+    /// ring buffer stays full for long enough that the FIFO fills up as well.
+    pub fn is_overrun(&self) -> bool {
+        self.info.rx_overrun()
+    }
+
+    /// Clears the RX FIFO overrun flag.
+    ///
+    /// **Note**: This discards the contents of both FIFOs and aborts any transmission in progress.
+    /// See [`UartRx::clear_overrun`] for details.
+    pub fn clear_overrun(&mut self) {
+        self.info.clear_rx_overrun();
+    }
+
+    /// Reconfigures the UART.
+    ///
+    /// **Note**: This also affects the TX half if the UART was split, so ensure
+    /// it is idle first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock,
+    /// in which case the previous configuration is kept.
+    pub fn set_config(&mut self, config: &Config) -> Result<(), Error> {
+        self.info.apply_config(config)
+    }
+
+    /// Returns the current configuration, with the baud rate actually achieved.
+    pub fn config(&self) -> Config {
+        self.info.config()
+    }
+
+    /// Returns the number of bytes currently waiting in the RX ring buffer.
+    pub fn available(&self) -> usize {
+        self.info.buffered.rx_buf.len()
+    }
+
+    async fn fill_buf(&mut self) -> &[u8] {
+        let (ptr, len) = poll_fn(|cx| {
+            self.info.buffered.rx_waker.register(cx.waker());
+
+            // SAFETY: We are the only reader of the RX ring buffer
+            let mut rx_reader = unsafe { self.info.buffered.rx_buf.reader() };
+            let (ptr, len) = rx_reader.pop_buf();
+            if len == 0 {
+                Poll::Pending
+            } else {
+                Poll::Ready((ptr, len))
+            }
+        })
+        .await;
+
+        // SAFETY: The interrupt handler never writes to bytes that haven't been popped yet,
+        // and nothing is popped while the returned slice borrows self
+        unsafe { core::slice::from_raw_parts(ptr, len) }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // SAFETY: We are the only reader of the RX ring buffer
+        let mut rx_reader = unsafe { self.info.buffered.rx_buf.reader() };
+        rx_reader.pop_done(amt);
+
+        // Now that there is room in the ring buffer again, make sure the IRQ is enabled
+        self.start();
+    }
+
+    /// Reads available bytes into buffer, waiting until at least one byte is available.
+    ///
+    /// Returns the number of bytes read.
+    pub async fn read(&mut self, buf: &mut [u8]) -> usize {
+        if buf.is_empty() {
+            return 0;
+        }
+
+        let data = self.fill_buf().await;
+        let n = data.len().min(buf.len());
+        buf[..n].copy_from_slice(&data[..n]);
+        self.consume(n);
+        n
+    }
+}
+
+impl<'d> Drop for BufferedUartRx<'d> {
+    fn drop(&mut self) {
+        critical_section::with(|_| {
+            self.info
+                .reg
+                .ctrl()
+                .modify(|_, w| w.uart_ctrl_irq_rx_nempty().clear_bit())
+        });
+        // SAFETY: The interrupt no longer touches the RX ring buffer
+        unsafe { self.info.buffered.rx_buf.deinit() };
+
+        self.info.active.rx.store(false, Ordering::Release);
+        drop_uart(&self.info);
+    }
+}
+
+/// TX-only interrupt-driven buffered UART driver.
+///
+/// See [`BufferedUart`] for details.
+pub struct BufferedUartTx<'d> {
+    info: Info,
+    _phantom: PhantomData<&'d mut [u8]>,
+}
+
+// Allows for use in a Mutex (to share safely between harts and tasks)
+unsafe impl<'d> Send for BufferedUartTx<'d> {}
+
+impl<'d> BufferedUartTx<'d> {
+    fn new_inner<T: Instance>(tx_buf: &'d mut [u8]) -> Result<Self, Error> {
+        if !T::supported() {
+            return Err(Error::NotSupported);
+        }
+        assert!(!tx_buf.is_empty());
+
+        // Mark TX as active
+        T::info().active.tx.store(true, Ordering::Release);
+
+        // SAFETY: The buffer outlives the driver, and the ring buffer is deinitialized on drop
+        unsafe {
+            T::info()
+                .buffered
+                .tx_buf
+                .init(tx_buf.as_mut_ptr(), tx_buf.len())
+        };
+
+        Ok(Self {
+            info: T::info(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Creates a new TX-only buffered UART driver with given config, using the given buffer as
+    /// TX ring buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSupported`] if UART is not supported.
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is empty.
+    pub fn new<T: Instance>(
+        _instance: Peri<'d, T>,
+        config: Config,
+        tx_buf: &'d mut [u8],
+        _irq: impl Binding<T::Interrupt, BufferedInterruptHandler<T>> + 'd,
+    ) -> Result<Self, Error> {
+        let uart = Self::new_inner::<T>(tx_buf)?;
+        Uart::<Async>::init(_instance, &config)?;
+        // SAFETY: It is valid to enable UART interrupt here
+        unsafe { T::Interrupt::enable() }
+        Ok(uart)
+    }
+
+    /// Reconfigures the UART.
+    ///
+    /// **Note**: Bytes still waiting in the TX ring buffer are sent with the new configuration,
+    /// so [`Self::flush`] first if that matters. This also affects the RX half if the UART was split.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBaudRate`] if the baud rate can't be derived from the CPU clock,
+    /// in which case the previous configuration is kept.
+    pub fn set_config(&mut self, config: &Config) -> Result<(), Error> {
+        self.info.apply_config(config)
+    }
+
+    /// Returns the current configuration, with the baud rate actually achieved.
+    pub fn config(&self) -> Config {
+        self.info.config()
+    }
+
+    /// Writes as many bytes as fit into the TX ring buffer, waiting until there is room for at
+    /// least one.
+    ///
+    /// Returns the number of bytes written.
+    pub async fn write(&mut self, bytes: &[u8]) -> usize {
+        if bytes.is_empty() {
+            return 0;
+        }
+
+        poll_fn(|cx| {
+            self.info.buffered.tx_waker.register(cx.waker());
+
+            // SAFETY: We are the only writer of the TX ring buffer
+            let mut tx_writer = unsafe { self.info.buffered.tx_buf.writer() };
+            let buf = tx_writer.push_slice();
+            if buf.is_empty() {
+                return Poll::Pending;
+            }
+
+            let n = buf.len().min(bytes.len());
+            buf[..n].copy_from_slice(&bytes[..n]);
+            tx_writer.push_done(n);
+
+            // CS used here since interrupt modifies register
+            critical_section::with(|_| {
+                self.info
+                    .reg
+                    .ctrl()
+                    .modify(|_, w| w.uart_ctrl_irq_tx_nfull().set_bit())
+            });
+            Poll::Ready(n)
+        })
+        .await
+    }
+
+    /// Waits until all buffered bytes have been transmitted.
+    pub async fn flush(&mut self) {
+        poll_fn(|cx| {
+            self.info.buffered.tx_waker.register(cx.waker());
+
+            // Interrupt handler wakes us as it drains the ring buffer
+            if !self.info.buffered.tx_buf.is_empty() {
+                return Poll::Pending;
+            }
+
+            if !self.info.reg.ctrl().read().uart_ctrl_tx_busy().bit_is_set() {
+                Poll::Ready(())
+            } else {
+                // CS used here since interrupt modifies register
+                critical_section::with(|_| {
+                    self.info
+                        .reg
+                        .ctrl()
+                        .modify(|_, w| w.uart_ctrl_irq_tx_empty().set_bit())
+                });
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<'d> Drop for BufferedUartTx<'d> {
+    fn drop(&mut self) {
+        critical_section::with(|_| {
+            self.info.reg.ctrl().modify(|_, w| {
+                w.uart_ctrl_irq_tx_nfull()
+                    .clear_bit()
+                    .uart_ctrl_irq_tx_empty()
+                    .clear_bit()
+            })
+        });
+        // SAFETY: The interrupt no longer touches the TX ring buffer
+        unsafe { self.info.buffered.tx_buf.deinit() };
+
+        self.info.active.tx.store(false, Ordering::Release);
+        drop_uart(&self.info);
+    }
+}
+
 fn drop_uart(info: &Info) {
     // Only disable UART if both Rx and Tx have been dropped
     critical_section::with(|_| {
@@ -822,6 +1551,25 @@ impl Active {
     }
 }
 
+// Ring buffers and wakers used by the buffered drivers
+struct BufferedState {
+    rx_buf: RingBuffer,
+    tx_buf: RingBuffer,
+    rx_waker: AtomicWaker,
+    tx_waker: AtomicWaker,
+}
+
+impl BufferedState {
+    const fn new() -> Self {
+        Self {
+            rx_buf: RingBuffer::new(),
+            tx_buf: RingBuffer::new(),
+            rx_waker: AtomicWaker::new(),
+            tx_waker: AtomicWaker::new(),
+        }
+    }
+}
+
 struct Info {
     // Note: uart0 and uart1 can both share uart0::RegisterBlock
     // PAC is able to coerce uart1::ptr() to it with correct base address
@@ -829,6 +1577,63 @@ struct Info {
     active: &'static Active,
     rx_waker: &'static AtomicWaker,
     tx_waker: &'static AtomicWaker,
+    buffered: &'static BufferedState,
+}
+
+impl Info {
+    fn apply_config(&self, config: &Config) -> Result<(), Error> {
+        // baud div is max 10-bits wide
+        const U10_MAX: u16 = 0x3ff;
+        let cpu_freq = crate::sysinfo::SysInfo::clock_freq();
+        let divider = crate::clock::solve(cpu_freq, config.baud_rate, &crate::clock::UART)
+            .map_err(|_| Error::InvalidBaudRate)?;
+
+        // Set the clock and baudrate prescalers along with simulation mode and flow control
+        // SAFETY: The calculation above ensures we are writing valid prscv and baud div
+        // CS used here since interrupt modifies register
+        critical_section::with(|_| {
+            self.reg.ctrl().modify(|_, w| unsafe {
+                w.uart_ctrl_prsc()
+                    .bits(divider.prsc_sel)
+                    .uart_ctrl_baud()
+                    .bits((divider.div as u16 - 1) & U10_MAX)
+                    .uart_ctrl_sim_mode()
+                    .bit(config.sim)
+                    .uart_ctrl_hwfc_en()
+                    .bit(config.flow_control)
+            })
+        });
+
+        Ok(())
+    }
+
+    // Returns the configuration actually applied, with the achieved baud rate
+    fn config(&self) -> Config {
+        let ctrl = self.reg.ctrl().read();
+        let prsc_sel = ctrl.uart_ctrl_prsc().bits();
+        let baud_div = ctrl.uart_ctrl_baud().bits() as u32 + 1;
+        let cpu_freq = crate::sysinfo::SysInfo::clock_freq();
+
+        Config {
+            baud_rate: crate::clock::UART.rate(cpu_freq, prsc_sel, baud_div),
+            sim: ctrl.uart_ctrl_sim_mode().bit_is_set(),
+            flow_control: ctrl.uart_ctrl_hwfc_en().bit_is_set(),
+        }
+    }
+
+    fn rx_overrun(&self) -> bool {
+        self.reg.ctrl().read().uart_ctrl_rx_over().bit_is_set()
+    }
+
+    fn clear_rx_overrun(&self) {
+        // Overrun flag is only cleared by disabling the UART, which also flushes both FIFOs
+        // and aborts any transmission in progress
+        // CS used here since interrupt modifies register
+        critical_section::with(|_| {
+            self.reg.ctrl().modify(|_, w| w.uart_ctrl_en().clear_bit());
+            self.reg.ctrl().modify(|_, w| w.uart_ctrl_en().set_bit());
+        });
+    }
 }
 
 trait SealedIoMode {}
@@ -865,6 +1670,7 @@ macro_rules! impl_instance {
                 static RX_WAKER: AtomicWaker = AtomicWaker::new();
                 static TX_WAKER: AtomicWaker = AtomicWaker::new();
                 static ACTIVE: Active = Active::new();
+                static BUFFERED: BufferedState = BufferedState::new();
 
                 Info {
                     // SAFETY: We are the sole users of the pointer and are sure to use it safely
@@ -872,6 +1678,7 @@ macro_rules! impl_instance {
                     active: &ACTIVE,
                     rx_waker: &RX_WAKER,
                     tx_waker: &TX_WAKER,
+                    buffered: &BUFFERED,
                 }
             }
 
@@ -888,6 +1695,38 @@ macro_rules! impl_instance {
 impl_instance!(UART0, Uart0, has_uart0);
 impl_instance!(UART1, Uart1, has_uart1);
 
+macro_rules! impl_config {
+    ($($driver:ident$(<$m:ident>)?),*) => {
+        $(
+            impl<'d $(, $m: IoMode)?> embassy_embedded_hal::SetConfig for $driver<'d $(, $m)?> {
+                type Config = Config;
+                type ConfigError = Error;
+
+                fn set_config(&mut self, config: &Self::Config) -> Result<(), Self::ConfigError> {
+                    self.set_config(config)
+                }
+            }
+
+            impl<'d $(, $m: IoMode)?> embassy_embedded_hal::GetConfig for $driver<'d $(, $m)?> {
+                type Config = Config;
+
+                fn get_config(&self) -> Self::Config {
+                    self.config()
+                }
+            }
+        )*
+    };
+}
+
+impl_config!(
+    Uart<M>,
+    UartRx<M>,
+    UartTx<M>,
+    BufferedUart,
+    BufferedUartRx,
+    BufferedUartTx
+);
+
 // Convenience for writing formatted strings to UART
 impl<'d, M: IoMode> core::fmt::Write for Uart<'d, M> {
     fn write_str(&mut self, s: &str) -> core::fmt::Result {
@@ -933,7 +1772,11 @@ impl<'d, M: IoMode> embedded_hal_02::blocking::serial::Write<u8> for UartTx<'d,
 
 impl embedded_io::Error for Error {
     fn kind(&self) -> embedded_io::ErrorKind {
-        embedded_io::ErrorKind::Other
+        match *self {
+            // Received data is incomplete, so whatever was read can't be trusted
+            Self::Overrun => embedded_io::ErrorKind::InvalidData,
+            _ => embedded_io::ErrorKind::Other,
+        }
     }
 }
 
@@ -943,6 +1786,12 @@ impl<'d, M: IoMode> embedded_io::ErrorType for Uart<'d, M> {
 
 impl<'d, M: IoMode> embedded_io::Read for Uart<'d, M> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        // Report an overrun before consuming any data. The flag is left set, since clearing it
+        // discards the FIFOs, so the caller must decide when to call `clear_overrun`
+        if self.is_overrun() {
+            return Err(Error::Overrun);
+        }
+
         self.blocking_read(buf);
         Ok(buf.len())
     }
@@ -950,6 +1799,10 @@ impl<'d, M: IoMode> embedded_io::Read for Uart<'d, M> {
 
 impl<'d> embedded_io_async::Read for Uart<'d, Async> {
     async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if self.is_overrun() {
+            return Err(Error::Overrun);
+        }
+
         self.read(buf).await.map(|_| buf.len())
     }
 }
@@ -1010,6 +1863,11 @@ impl<'d, M: IoMode> embedded_io::ErrorType for UartRx<'d, M> {
 
 impl<'d, M: IoMode> embedded_io::Read for UartRx<'d, M> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        // Report an overrun before consuming any data, leaving the flag set (see above)
+        if self.is_overrun() {
+            return Err(Error::Overrun);
+        }
+
         self.blocking_read(buf);
         Ok(buf.len())
     }
@@ -1017,6 +1875,105 @@ impl<'d, M: IoMode> embedded_io::Read for UartRx<'d, M> {
 
 impl<'d> embedded_io_async::Read for UartRx<'d, Async> {
     async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if self.is_overrun() {
+            return Err(Error::Overrun);
+        }
+
         self.read(buf).await.map(|_| buf.len())
     }
 }
+
+impl<'d> embedded_io::ErrorType for BufferedUart<'d> {
+    type Error = Error;
+}
+
+impl<'d> embedded_io_async::Read for BufferedUart<'d> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        embedded_io_async::Read::read(&mut self.rx, buf).await
+    }
+}
+
+impl<'d> embedded_io_async::BufRead for BufferedUart<'d> {
+    async fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        Ok(self.rx.fill_buf().await)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.rx.consume(amt);
+    }
+}
+
+impl<'d> embedded_io::ReadReady for BufferedUart<'d> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        self.rx.read_ready()
+    }
+}
+
+impl<'d> embedded_io_async::Write for BufferedUart<'d> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Ok(self.tx.write(buf).await)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.tx.flush().await;
+        Ok(())
+    }
+}
+
+impl<'d> embedded_io::WriteReady for BufferedUart<'d> {
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        self.tx.write_ready()
+    }
+}
+
+impl<'d> embedded_io::ErrorType for BufferedUartRx<'d> {
+    type Error = Error;
+}
+
+impl<'d> embedded_io_async::Read for BufferedUartRx<'d> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        // Report an overrun before consuming any data, leaving the flag set (see above)
+        if self.is_overrun() {
+            return Err(Error::Overrun);
+        }
+
+        Ok(self.read(buf).await)
+    }
+}
+
+impl<'d> embedded_io_async::BufRead for BufferedUartRx<'d> {
+    async fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        Ok(self.fill_buf().await)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.consume(amt);
+    }
+}
+
+impl<'d> embedded_io::ReadReady for BufferedUartRx<'d> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.info.buffered.rx_buf.is_empty())
+    }
+}
+
+impl<'d> embedded_io::ErrorType for BufferedUartTx<'d> {
+    type Error = Error;
+}
+
+impl<'d> embedded_io_async::Write for BufferedUartTx<'d> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Ok(self.write(buf).await)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.flush().await;
+        Ok(())
+    }
+}
+
+impl<'d> embedded_io::WriteReady for BufferedUartTx<'d> {
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.info.buffered.tx_buf.is_full())
+    }
+}
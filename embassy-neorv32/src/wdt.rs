@@ -37,6 +37,9 @@ impl From<u8> for ResetCause {
 pub enum Error {
     /// The NEORV32 configuration does not support WDT.
     NotSupported,
+    /// The requested timeout is too long for the 24-bit timeout counter, or too short to be
+    /// represented at the WDT clock frequency.
+    InvalidTimeout,
 }
 
 /// Watchdog Timer (WDT) Driver.
@@ -97,12 +100,19 @@ impl<'d> Wdt<'d, Unlocked> {
 
     /// Sets WDT timeout value in milliseconds (ms).
     ///
-    /// Millisecond precision may not be possible depending on configured main clock frequency.
-    pub fn set_timeout_ms(&self, timeout_ms: u32) {
-        let wdt_clock_freq: u32 = crate::sysinfo::SysInfo::clock_freq() / 4096;
-        let ticks_per_ms: u32 = wdt_clock_freq / 1000;
-        let timeout = timeout_ms * ticks_per_ms;
+    /// Millisecond precision may not be possible depending on configured main clock frequency,
+    /// in which case the timeout is rounded to the nearest WDT clock tick.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidTimeout`] if the timeout can't be represented, in which case the
+    /// previous timeout is kept.
+    pub fn set_timeout_ms(&self, timeout_ms: u32) -> Result<(), Error> {
+        let cpu_freq = crate::sysinfo::SysInfo::clock_freq();
+        let timeout = crate::clock::ms_to_ticks(cpu_freq, 4096, timeout_ms, 0xffffff)
+            .map_err(|_| Error::InvalidTimeout)?;
         self.set_timeout(timeout);
+        Ok(())
     }
 
     /// Returns a locked WDT which prevents illegal access.